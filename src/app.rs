@@ -1,5 +1,6 @@
-use std::sync::Arc;
+use std::{fs, path::Path, sync::Arc};
 
+use duckdb::DuckdbConnectionManager;
 use r2d2::Pool;
 use rbatis::RBatis;
 use rbdc_mysql::MysqlDriver;
@@ -10,7 +11,14 @@ use crate::{
         pet::service::{
             pet_service::PetService, pet_type_service::PetTypeService,
         },
-        transtask::service::trans_task_service::TransTaskService,
+        transtask::service::{
+            trans_dag_service::TransDagService,
+            trans_task_column_service::TransTaskColumnService,
+            trans_task_quality_service::TransTaskQualityService,
+            trans_task_run_service::TransTaskRunService,
+            trans_task_scheduler::TransTaskScheduler,
+            trans_task_service::TransTaskService,
+        },
     },
     config::config::AppConfig,
     sys::user::service::user_service::UserService,
@@ -25,13 +33,16 @@ pub struct AppState {
 
 #[derive(Clone)]
 pub struct Infrastructure {
+    pub config: Arc<AppConfig>,
     pub batis: Arc<RBatis>,
     pub pool: Arc<ConnectionPool>,
 }
 
 #[derive(Clone)]
 pub struct ConnectionPool {
+    pub redis_client: redis::Client,
     pub redis_pool: Pool<redis::Client>,
+    pub duck_pool: Pool<DuckdbConnectionManager>,
 }
 
 #[derive(Clone)]
@@ -41,16 +52,29 @@ pub struct ServiceContainer {
     pub pet_type_service: PetTypeService,
     pub data_source_service: DataSourceService,
     pub trans_task_service: TransTaskService,
+    pub trans_task_run_service: TransTaskRunService,
+    pub trans_task_column_service: TransTaskColumnService,
+    pub trans_task_quality_service: TransTaskQualityService,
+    pub trans_dag_service: TransDagService,
 }
 
 impl ServiceContainer {
     pub fn new(infra: &Infrastructure) -> Self {
+        // DAG执行与接口共用同一个任务服务，以识别正在执行的任务
+        let trans_task_service = TransTaskService::new(infra);
         Self {
             user_service: UserService::new(infra),
             pet_service: PetService::new(infra),
             pet_type_service: PetTypeService::new(infra),
             data_source_service: DataSourceService::new(infra),
-            trans_task_service: TransTaskService::new(infra),
+            trans_dag_service: TransDagService::new(
+                infra,
+                trans_task_service.clone(),
+            ),
+            trans_task_service,
+            trans_task_run_service: TransTaskRunService::new(infra),
+            trans_task_column_service: TransTaskColumnService::new(infra),
+            trans_task_quality_service: TransTaskQualityService::new(infra),
         }
     }
 }
@@ -68,7 +92,15 @@ pub async fn init_app(config: &AppConfig) -> Arc<AppState> {
         ),
     };
     let redis_client = redis::Client::open(redis_url).unwrap();
-    let redis_pool = Pool::builder().build(redis_client).unwrap();
+    let redis_pool = Pool::builder().build(redis_client.clone()).unwrap();
+
+    // 创建DuckDB数仓连接池
+    if let Some(parent) = Path::new(&config.duckdb.path).parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    let duck_manager =
+        DuckdbConnectionManager::file(&config.duckdb.path).unwrap();
+    let duck_pool = Pool::builder().build(duck_manager).unwrap();
 
     // 创建RBatis实例
     let rb = RBatis::new();
@@ -93,12 +125,20 @@ pub async fn init_app(config: &AppConfig) -> Arc<AppState> {
 
     // 创建共享应用状态，包含RBatis和r2d2连接池
     let infra = Infrastructure {
+        config: Arc::new(config.clone()),
         batis: Arc::new(rb.clone()),
         pool: Arc::new(ConnectionPool {
+            redis_client,
             redis_pool: redis_pool,
+            duck_pool,
         }),
     };
     let services = ServiceContainer::new(&infra);
+
+    // 启动传输任务调度器，与接口共用同一个任务服务以识别正在执行的任务
+    TransTaskScheduler::new(&infra, services.trans_task_service.clone())
+        .start();
+
     Arc::new(AppState {
         infra: infra,
         services: services,
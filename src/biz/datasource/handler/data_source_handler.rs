@@ -2,15 +2,21 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
 };
 use validator::Validate;
 
 use crate::{
     app::AppState,
-    biz::datasource::model::data_source::{
-        DataSourceCreateBo, DataSourceDetailVo, DataSourceListVo,
-        DataSourceUpdateBo,
+    biz::datasource::model::{
+        catalog::{
+            CatalogTableBo, CatalogTableSchemaVo, CatalogTableVo,
+            TablePreviewBo, TablePreviewVo,
+        },
+        data_source::{
+            DataSourceCreateBo, DataSourceDetailVo, DataSourceListVo,
+            DataSourceUpdateBo,
+        },
     },
     common::vo::response::R,
     middleware::extractors::CurrentUser,
@@ -139,3 +145,78 @@ pub async fn test_data_source(
         R::error_with_message(message)
     }
 }
+
+pub async fn list_data_source_schemas(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> R<Vec<String>> {
+    let result = state.services.data_source_service.list_schemas(id).await;
+
+    match result {
+        Ok(schemas) => R::ok_with_data(schemas),
+        Err(e) => {
+            tracing::error!("查询数据源schema失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn list_data_source_tables(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Query(bo): Query<CatalogTableBo>,
+) -> R<Vec<CatalogTableVo>> {
+    let result = state
+        .services
+        .data_source_service
+        .list_tables(id, bo.schema, bo.refresh)
+        .await;
+
+    match result {
+        Ok(tables) => R::ok_with_data(tables),
+        Err(e) => {
+            tracing::error!("查询数据源表失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn get_data_source_table_columns(
+    State(state): State<Arc<AppState>>,
+    Path((id, table)): Path<(i64, String)>,
+    Query(bo): Query<CatalogTableBo>,
+) -> R<CatalogTableSchemaVo> {
+    let result = state
+        .services
+        .data_source_service
+        .get_table_schema(id, bo.schema, table, bo.refresh)
+        .await;
+
+    match result {
+        Ok(table_schema) => R::ok_with_data(table_schema),
+        Err(e) => {
+            tracing::error!("查询数据源表结构失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn preview_data_source_table(
+    State(state): State<Arc<AppState>>,
+    Path((id, table)): Path<(i64, String)>,
+    Query(bo): Query<TablePreviewBo>,
+) -> R<TablePreviewVo> {
+    let result = state
+        .services
+        .data_source_service
+        .preview_table(id, table, bo)
+        .await;
+
+    match result {
+        Ok(preview) => R::ok_with_data(preview),
+        Err(e) => {
+            tracing::error!("预览数据源表数据失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// 查询表列表、表结构的参数，未指定schema时使用数据源的默认schema。
+// refresh 为 true 时跳过缓存重新读取
+#[derive(Clone, Debug, Deserialize)]
+pub struct CatalogTableBo {
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+// 数据源中的表或视图
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CatalogTableVo {
+    pub schema: String,
+    pub table_name: String,
+    // 取 information_schema 的表类型：BASE TABLE、VIEW 等
+    pub table_type: String,
+    pub comment: String,
+    // 存储引擎，仅MySQL有
+    pub engine: Option<String>,
+    // 取自数据库统计信息的估算行数与数据大小（字节），未收集统计时为空
+    pub estimated_rows: Option<i64>,
+    pub data_size: Option<i64>,
+}
+
+// 表的列定义
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CatalogColumnVo {
+    pub column_name: String,
+    // 源库中的完整类型，如 varchar(64)、numeric(10,2)
+    pub data_type: String,
+    pub nullable: bool,
+    // 默认值表达式，如 0、CURRENT_TIMESTAMP、nextval('seq'::regclass)
+    pub default_value: Option<String>,
+    pub primary_key: bool,
+    pub comment: String,
+    // 从1开始的列序号
+    pub ordinal_position: i64,
+}
+
+// 索引，主键与唯一约束也以唯一索引的形式列出
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CatalogIndexVo {
+    pub index_name: String,
+    // 按索引内顺序的列名，表达式索引为表达式文本
+    pub columns: Vec<String>,
+    pub unique: bool,
+    pub primary: bool,
+}
+
+// 外键
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CatalogForeignKeyVo {
+    pub constraint_name: String,
+    pub columns: Vec<String>,
+    pub referenced_schema: String,
+    pub referenced_table: String,
+    // 引用表的列，与 columns 一一对应
+    pub referenced_columns: Vec<String>,
+}
+
+// 表结构：列定义、主键、唯一键、索引与外键
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CatalogTableSchemaVo {
+    pub schema: String,
+    pub table_name: String,
+    pub columns: Vec<CatalogColumnVo>,
+    pub primary_key: Vec<String>,
+    pub unique_keys: Vec<Vec<String>>,
+    pub indexes: Vec<CatalogIndexVo>,
+    pub foreign_keys: Vec<CatalogForeignKeyVo>,
+}
+
+impl CatalogTableSchemaVo {
+    // 主键优先取主键索引以保持键内列顺序，没有主键索引时（如SQLite的rowid别名列）取列定义
+    pub fn new(
+        schema: String,
+        table_name: String,
+        columns: Vec<CatalogColumnVo>,
+        indexes: Vec<CatalogIndexVo>,
+        foreign_keys: Vec<CatalogForeignKeyVo>,
+    ) -> Self {
+        let primary_key = match indexes.iter().find(|index| index.primary) {
+            Some(index) => index.columns.clone(),
+            None => columns
+                .iter()
+                .filter(|column| column.primary_key)
+                .map(|column| column.column_name.clone())
+                .collect(),
+        };
+        let unique_keys = indexes
+            .iter()
+            .filter(|index| index.unique && !index.primary)
+            .map(|index| index.columns.clone())
+            .collect();
+        Self {
+            schema,
+            table_name,
+            columns,
+            primary_key,
+            unique_keys,
+            indexes,
+            foreign_keys,
+        }
+    }
+}
+
+// 查询结果列
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResultColumnVo {
+    pub name: String,
+    // 源库返回的列类型名
+    pub data_type: String,
+}
+
+// 预览表数据的参数，未指定schema时使用数据源的默认schema。
+// sample 为 true 时随机抽取，否则取前 limit 行
+#[derive(Clone, Debug, Deserialize)]
+pub struct TablePreviewBo {
+    pub schema: Option<String>,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub sample: bool,
+}
+
+impl TablePreviewBo {
+    // 默认预览100行，不超过配置的上限
+    pub fn limit(&self, max_rows: usize) -> usize {
+        self.limit.unwrap_or(100).clamp(1, max_rows.max(1))
+    }
+}
+
+// 表数据预览结果，每行的值与 columns 一一对应
+#[derive(Clone, Debug, Serialize)]
+pub struct TablePreviewVo {
+    pub columns: Vec<ResultColumnVo>,
+    pub rows: Vec<Vec<Value>>,
+}
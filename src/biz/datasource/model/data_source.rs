@@ -1,12 +1,85 @@
+use std::collections::{BTreeMap, HashMap};
+
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 use crate::{
     common::model::entity::BaseEntity,
     sys::user::model::user::User,
-    util::crypto_util::{decrypt_password, encrypt_password},
+    util::{
+        crypto_util::{decrypt_password, encrypt_password},
+        path_util,
+    },
 };
 
+// 数据源类型，比较时不区分大小写
+pub const DB_TYPE_MYSQL: &str = "mysql";
+pub const DB_TYPE_POSTGRESQL: &str = "postgresql";
+// 文件型数据源，db_name 为 [datasource] file_root 下的本地路径，无需主机与账号。
+// SQLite、DuckDB为数据库文件；CSV、JSON Lines、Parquet为目录、glob或单个文件
+pub const DB_TYPE_SQLITE: &str = "sqlite";
+pub const DB_TYPE_DUCKDB: &str = "duckdb";
+pub const DB_TYPE_CSV: &str = "csv";
+pub const DB_TYPE_JSONL: &str = "jsonl";
+pub const DB_TYPE_PARQUET: &str = "parquet";
+
+const DB_TYPES: &[&str] = &[
+    DB_TYPE_MYSQL,
+    DB_TYPE_POSTGRESQL,
+    DB_TYPE_SQLITE,
+    DB_TYPE_DUCKDB,
+    DB_TYPE_CSV,
+    DB_TYPE_JSONL,
+    DB_TYPE_PARQUET,
+];
+const FILE_DB_TYPES: &[&str] = &[
+    DB_TYPE_SQLITE,
+    DB_TYPE_DUCKDB,
+    DB_TYPE_CSV,
+    DB_TYPE_JSONL,
+    DB_TYPE_PARQUET,
+];
+
+// 按表覆盖推断出的列类型：表名 -> 列名 -> DuckDB类型
+pub type FileColumnTypes = HashMap<String, BTreeMap<String, String>>;
+
+// 列类型覆盖可用的DuckDB类型名
+const FILE_COLUMN_TYPES: &[&str] = &[
+    "BOOLEAN",
+    "BOOL",
+    "TINYINT",
+    "SMALLINT",
+    "INTEGER",
+    "INT",
+    "BIGINT",
+    "HUGEINT",
+    "UTINYINT",
+    "USMALLINT",
+    "UINTEGER",
+    "UBIGINT",
+    "UHUGEINT",
+    "FLOAT",
+    "REAL",
+    "DOUBLE",
+    "DECIMAL",
+    "NUMERIC",
+    "VARCHAR",
+    "TEXT",
+    "BLOB",
+    "DATE",
+    "TIME",
+    "TIMESTAMP",
+    "TIMESTAMPTZ",
+    "TIMESTAMP WITH TIME ZONE",
+    "INTERVAL",
+    "UUID",
+    "JSON",
+];
+
+// 可带精度参数的类型名
+const PARAMETERIZED_FILE_COLUMN_TYPES: &[&str] =
+    &["DECIMAL", "NUMERIC", "VARCHAR"];
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DataSource {
     pub data_source_id: Option<i64>,
@@ -19,6 +92,15 @@ pub struct DataSource {
     pub db_name: String,
     pub db_username: String,
     pub db_password: String,
+    // CSV、JSON Lines数据源的读取选项：分隔符、是否有表头、字符编码、
+    // 日期格式与压缩方式（auto/none/gzip/zstd），未设置时由DuckDB自动检测
+    pub file_delimiter: Option<String>,
+    pub file_header: Option<bool>,
+    pub file_encoding: Option<String>,
+    pub file_date_format: Option<String>,
+    pub file_compression: Option<String>,
+    // 列类型覆盖，JSON格式见 FileColumnTypes
+    pub file_column_types: Option<String>,
     #[serde(flatten)]
     pub base_entity: BaseEntity,
 }
@@ -41,6 +123,13 @@ impl DataSource {
             db_name: bo.db_name,
             db_username: bo.db_username,
             db_password: encrypted_password,
+            // 分隔符可以是制表符等空白字符，不做trim
+            file_delimiter: bo.file_delimiter.filter(|d| !d.is_empty()),
+            file_header: bo.file_header,
+            file_encoding: non_empty(bo.file_encoding),
+            file_date_format: non_empty(bo.file_date_format),
+            file_compression: non_empty(bo.file_compression),
+            file_column_types: non_empty(bo.file_column_types),
             base_entity: BaseEntity::new(user.get_user_id()),
         }
     }
@@ -65,6 +154,12 @@ impl DataSource {
         self.db_name = bo.db_name;
         self.db_username = bo.db_username;
         self.db_password = encrypted_password;
+        self.file_delimiter = bo.file_delimiter.filter(|d| !d.is_empty());
+        self.file_header = bo.file_header;
+        self.file_encoding = non_empty(bo.file_encoding);
+        self.file_date_format = non_empty(bo.file_date_format);
+        self.file_compression = non_empty(bo.file_compression);
+        self.file_column_types = non_empty(bo.file_column_types);
         self.base_entity.update(user.get_user_id());
     }
 
@@ -97,51 +192,95 @@ impl DataSource {
             db_name: self.db_name.clone(),
             db_username: self.db_username.clone(),
             db_password: decrypted_password,
+            file_delimiter: self.file_delimiter.clone(),
+            file_header: self.file_header,
+            file_encoding: self.file_encoding.clone(),
+            file_date_format: self.file_date_format.clone(),
+            file_compression: self.file_compression.clone(),
+            file_column_types: self.file_column_types.clone(),
             base_entity: self.base_entity.clone(),
         }
     }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_create_bo"))]
 pub struct DataSourceCreateBo {
-    #[validate(length(min = 1, message = "code cannot be empty"))]
+    #[validate(
+        length(min = 1, message = "code cannot be empty"),
+        custom(function = "validate_code")
+    )]
     pub code: String,
     #[validate(length(min = 1, message = "name cannot be empty"))]
     pub name: String,
     pub remark: String,
-    #[validate(length(min = 1, message = "db_type cannot be empty"))]
+    #[validate(custom(function = "validate_db_type"))]
     pub db_type: String,
-    #[validate(length(min = 1, message = "db_url cannot be empty"))]
+    #[serde(default)]
     pub db_host: String,
+    #[serde(default)]
     pub db_port: u16,
     #[validate(length(min = 1, message = "db_name cannot be empty"))]
     pub db_name: String,
-    #[validate(length(min = 1, message = "db_username cannot be empty"))]
+    #[serde(default)]
     pub db_username: String,
-    #[validate(length(min = 1, message = "db_password cannot be empty"))]
+    #[serde(default)]
     pub db_password: String,
+    #[serde(default)]
+    pub file_delimiter: Option<String>,
+    #[serde(default)]
+    pub file_header: Option<bool>,
+    #[serde(default)]
+    pub file_encoding: Option<String>,
+    #[serde(default)]
+    pub file_date_format: Option<String>,
+    #[serde(default)]
+    #[validate(custom(function = "validate_file_compression"))]
+    pub file_compression: Option<String>,
+    #[serde(default)]
+    #[validate(custom(function = "validate_file_column_types"))]
+    pub file_column_types: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_update_bo"))]
 pub struct DataSourceUpdateBo {
     #[validate(required)]
     pub data_source_id: Option<i64>,
-    #[validate(length(min = 1, message = "code cannot be empty"))]
+    #[validate(
+        length(min = 1, message = "code cannot be empty"),
+        custom(function = "validate_code")
+    )]
     pub code: String,
     #[validate(length(min = 1, message = "name cannot be empty"))]
     pub name: String,
     pub remark: String,
-    #[validate(length(min = 1, message = "db_type cannot be empty"))]
+    #[validate(custom(function = "validate_db_type"))]
     pub db_type: String,
-    #[validate(length(min = 1, message = "db_url cannot be empty"))]
+    #[serde(default)]
     pub db_host: String,
+    #[serde(default)]
     pub db_port: u16,
     #[validate(length(min = 1, message = "db_name cannot be empty"))]
     pub db_name: String,
-    #[validate(length(min = 1, message = "db_username cannot be empty"))]
+    #[serde(default)]
     pub db_username: String,
-    #[validate(length(min = 1, message = "db_password cannot be empty"))]
+    #[serde(default)]
     pub db_password: String,
+    #[serde(default)]
+    pub file_delimiter: Option<String>,
+    #[serde(default)]
+    pub file_header: Option<bool>,
+    #[serde(default)]
+    pub file_encoding: Option<String>,
+    #[serde(default)]
+    pub file_date_format: Option<String>,
+    #[serde(default)]
+    #[validate(custom(function = "validate_file_compression"))]
+    pub file_compression: Option<String>,
+    #[serde(default)]
+    #[validate(custom(function = "validate_file_column_types"))]
+    pub file_column_types: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate)]
@@ -168,6 +307,194 @@ pub struct DataSourceDetailVo {
     pub db_name: String,
     pub db_username: String,
     pub db_password: String,
+    pub file_delimiter: Option<String>,
+    pub file_header: Option<bool>,
+    pub file_encoding: Option<String>,
+    pub file_date_format: Option<String>,
+    pub file_compression: Option<String>,
+    pub file_column_types: Option<String>,
     #[serde(flatten)]
     pub base_entity: BaseEntity,
 }
+
+impl DataSourceDetailVo {
+    pub fn is_db_type(&self, db_type: &str) -> bool {
+        self.db_type.eq_ignore_ascii_case(db_type)
+    }
+
+    pub fn column_types(&self) -> FileColumnTypes {
+        self.file_column_types
+            .as_deref()
+            .and_then(|types| serde_json::from_str(types).ok())
+            .unwrap_or_default()
+    }
+}
+
+pub fn is_file_db_type(db_type: &str) -> bool {
+    FILE_DB_TYPES
+        .iter()
+        .any(|file_type| db_type.eq_ignore_ascii_case(file_type))
+}
+
+fn validate_db_type(db_type: &str) -> Result<(), ValidationError> {
+    if DB_TYPES
+        .iter()
+        .any(|supported| db_type.eq_ignore_ascii_case(supported))
+    {
+        Ok(())
+    } else {
+        Err(ValidationError::new("db_type").with_message(
+            "db_type must be mysql, postgresql, sqlite, duckdb, csv, jsonl \
+             or parquet"
+                .into(),
+        ))
+    }
+}
+
+// code 用作DuckDB的schema与Parquet导出的目录名
+fn validate_code(code: &str) -> Result<(), ValidationError> {
+    if path_util::is_identifier(code) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("code").with_message(
+            "code may only contain letters, digits, '_', '$' and '-'".into(),
+        ))
+    }
+}
+
+fn validate_create_bo(bo: &DataSourceCreateBo) -> Result<(), ValidationError> {
+    validate_server(&bo.db_type, &bo.db_host, &bo.db_username, &bo.db_password)
+}
+
+fn validate_update_bo(bo: &DataSourceUpdateBo) -> Result<(), ValidationError> {
+    validate_server(&bo.db_type, &bo.db_host, &bo.db_username, &bo.db_password)
+}
+
+// 服务端数据源须填写主机与账号，文件型数据源可留空
+fn validate_server(
+    db_type: &str,
+    db_host: &str,
+    db_username: &str,
+    db_password: &str,
+) -> Result<(), ValidationError> {
+    if is_file_db_type(db_type) {
+        return Ok(());
+    }
+    let message = if db_host.is_empty() {
+        "db_url cannot be empty"
+    } else if db_username.is_empty() {
+        "db_username cannot be empty"
+    } else if db_password.is_empty() {
+        "db_password cannot be empty"
+    } else {
+        return Ok(());
+    };
+    Err(ValidationError::new("server").with_message(message.into()))
+}
+
+fn validate_file_compression(
+    file_compression: &str,
+) -> Result<(), ValidationError> {
+    match file_compression {
+        "" | "auto" | "none" | "gzip" | "zstd" => Ok(()),
+        _ => Err(ValidationError::new("file_compression").with_message(
+            "file_compression must be auto, none, gzip or zstd".into(),
+        )),
+    }
+}
+
+// 类型会拼接到SQL中，按封闭的语法校验：类型名取自 FILE_COLUMN_TYPES，
+// DECIMAL等可带 (精度[, 小数位])，末尾可带若干 [] 表示列表
+pub fn is_file_column_type(ty: &str) -> bool {
+    let mut ty = ty.trim();
+    while let Some(element) = ty.strip_suffix("[]") {
+        ty = element.trim_end();
+    }
+    let (name, params) = match ty.split_once('(') {
+        Some((name, rest)) => match rest.strip_suffix(')') {
+            Some(params) => (name, Some(params)),
+            None => return false,
+        },
+        None => (ty, None),
+    };
+    let name = name
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_ascii_uppercase();
+    if !FILE_COLUMN_TYPES.contains(&name.as_str()) {
+        return false;
+    }
+    let Some(params) = params else {
+        return true;
+    };
+    let params = params.split(',').map(str::trim).collect::<Vec<_>>();
+    PARAMETERIZED_FILE_COLUMN_TYPES.contains(&name.as_str())
+        && params.len() <= 2
+        && params.iter().all(|param| {
+            (1..=3).contains(&param.len())
+                && param.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+fn validate_file_column_types(
+    file_column_types: &str,
+) -> Result<(), ValidationError> {
+    if file_column_types.trim().is_empty() {
+        return Ok(());
+    }
+    let error = || {
+        ValidationError::new("file_column_types").with_message(
+            "file_column_types must be a JSON object of table -> column -> type"
+                .into(),
+        )
+    };
+    let types: FileColumnTypes =
+        serde_json::from_str(file_column_types).map_err(|_| error())?;
+    let valid = types
+        .values()
+        .flat_map(|columns| columns.values())
+        .all(|ty| is_file_column_type(ty));
+    if valid { Ok(()) } else { Err(error()) }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_column_types() {
+        for ty in [
+            "INTEGER",
+            "varchar",
+            " DECIMAL(18, 4) ",
+            "numeric(10)",
+            "VARCHAR(255)",
+            "timestamp   with time zone",
+            "BIGINT[]",
+            "DECIMAL(10,2)[][]",
+        ] {
+            assert!(is_file_column_type(ty), "{}", ty);
+        }
+        for ty in [
+            "",
+            "INTEGER(10)",
+            "DECIMAL()",
+            "DECIMAL(1,2,3)",
+            "DECIMAL(a)",
+            "DECIMAL(1000)",
+            "DECIMAL(10",
+            "DECIMAL(10,2) x",
+            "MAP(VARCHAR, INTEGER)",
+            "VARCHAR) AS x, read_text('/etc/passwd'",
+            "STRUCT(a INTEGER)",
+            "INTEGER[",
+        ] {
+            assert!(!is_file_column_type(ty), "{}", ty);
+        }
+    }
+}
@@ -9,7 +9,9 @@ use crate::{
     app::AppState,
     biz::datasource::handler::data_source_handler::{
         create_data_source, delete_data_source, get_data_source_by_id,
-        list_data_source, test_data_source, true_delete_data_source,
+        get_data_source_table_columns, list_data_source,
+        list_data_source_schemas, list_data_source_tables,
+        preview_data_source_table, test_data_source, true_delete_data_source,
         update_data_source,
     },
 };
@@ -28,4 +30,14 @@ pub fn data_source_route() -> Router<Arc<AppState>> {
         )
         .route("/datasource/delete/{id}", delete(true_delete_data_source))
         .route("/datasource/test/{id}", get(test_data_source))
+        .route("/datasource/{id}/schemas", get(list_data_source_schemas))
+        .route("/datasource/{id}/tables", get(list_data_source_tables))
+        .route(
+            "/datasource/{id}/tables/{table}/columns",
+            get(get_data_source_table_columns),
+        )
+        .route(
+            "/datasource/{id}/tables/{table}/preview",
+            get(preview_data_source_table),
+        )
 }
@@ -0,0 +1,413 @@
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    time::Duration,
+};
+
+use serde_json::Value;
+
+use crate::{
+    biz::datasource::{
+        model::{
+            catalog::{
+                CatalogColumnVo, CatalogForeignKeyVo, CatalogIndexVo,
+                CatalogTableVo, ResultColumnVo,
+            },
+            data_source::{
+                DB_TYPE_CSV, DB_TYPE_DUCKDB, DB_TYPE_JSONL, DB_TYPE_MYSQL,
+                DB_TYPE_PARQUET, DB_TYPE_POSTGRESQL, DB_TYPE_SQLITE,
+                DataSourceDetailVo,
+            },
+        },
+        service::{
+            duckdb_connector::DuckDbConnector, file_connector::FileConnector,
+            mysql_connector::MySqlConnector,
+            postgres_connector::PostgresConnector,
+            sqlite_connector::SqliteConnector,
+        },
+    },
+    error::error::AppError,
+    util::path_util,
+};
+
+// 各类数据源的统一访问接口，按 db_type 选择实现。
+// 实现持有一个独立连接，方法均会阻塞当前线程，需在 spawn_blocking 中调用
+pub trait Connector: Send {
+    // 执行一次简单查询，校验连接与权限
+    fn test(&mut self) -> Result<(), AppError>;
+
+    // 未指定schema时使用的schema：MySQL为数据源的库名，
+    // PostgreSQL为search_path中第一个存在的schema
+    fn default_schema(&mut self) -> Result<String, AppError>;
+
+    // 用户schema，不含系统schema
+    fn list_schemas(&mut self) -> Result<Vec<String>, AppError>;
+
+    // schema下的表与视图，按表名排序
+    fn list_tables(
+        &mut self,
+        schema: &str,
+    ) -> Result<Vec<CatalogTableVo>, AppError>;
+
+    // 表的列定义，按列序号排序；表不存在时返回空
+    fn list_columns(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogColumnVo>, AppError>;
+
+    // 表的索引，含主键与唯一约束，按索引名排序
+    fn list_indexes(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogIndexVo>, AppError>;
+
+    // 表的外键，按约束名排序
+    fn list_foreign_keys(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogForeignKeyVo>, AppError>;
+
+    // 限制此后每条查询的执行时间，超时的查询被中止并返回错误
+    fn set_statement_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), AppError>;
+
+    // 预览表数据的查询：取前 limit 行，sample 为 true 时随机抽取 limit 行。
+    // 默认实现按标准SQL引用标识符并对全表随机排序，只适用于SQLite等本地文件；
+    // 服务端数据库需覆盖，按估算行数先抽样再排序，见 sample_probability
+    fn preview_sql(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+        limit: usize,
+        sample: bool,
+    ) -> Result<String, AppError> {
+        Ok(format!(
+            "SELECT * FROM {}.{}{} LIMIT {}",
+            quote_ident(schema),
+            quote_ident(table_name),
+            if sample { " ORDER BY random()" } else { "" },
+            limit
+        ))
+    }
+
+    // 执行查询并逐行回调，值按列类型转换为JSON：整数与浮点数为数字，
+    // 精确小数、日期时间为字符串，二进制为Base64。回调返回 false 时停止读取
+    fn stream_rows(
+        &mut self,
+        sql: &str,
+        on_row: &mut dyn FnMut(Vec<Value>) -> Result<bool, AppError>,
+    ) -> Result<Vec<ResultColumnVo>, AppError>;
+}
+
+// 按数据源类型建立连接，文件型数据源的路径须位于 file_root 之下
+pub fn open(
+    data_source: &DataSourceDetailVo,
+    file_root: &Path,
+) -> Result<Box<dyn Connector>, AppError> {
+    if data_source.is_db_type(DB_TYPE_MYSQL) {
+        Ok(Box::new(MySqlConnector::open(data_source)?))
+    } else if data_source.is_db_type(DB_TYPE_POSTGRESQL) {
+        Ok(Box::new(PostgresConnector::open(data_source)?))
+    } else if data_source.is_db_type(DB_TYPE_SQLITE) {
+        Ok(Box::new(SqliteConnector::open(data_source, file_root)?))
+    } else if data_source.is_db_type(DB_TYPE_DUCKDB) {
+        Ok(Box::new(DuckDbConnector::open(data_source, file_root)?))
+    } else if data_source.is_db_type(DB_TYPE_CSV)
+        || data_source.is_db_type(DB_TYPE_JSONL)
+        || data_source.is_db_type(DB_TYPE_PARQUET)
+    {
+        Ok(Box::new(FileConnector::open(data_source, file_root)?))
+    } else {
+        Err(AppError::DataSourceError(format!(
+            "不支持的数据源类型: {}",
+            data_source.db_type
+        )))
+    }
+}
+
+// 文件型数据源的本地文件路径，文件须已存在
+pub fn database_file(
+    data_source: &DataSourceDetailVo,
+    file_root: &Path,
+) -> Result<PathBuf, AppError> {
+    let path = file_path(file_root, &data_source.db_name)?;
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(AppError::DataSourceError(format!(
+            "数据库文件不存在: {}",
+            data_source.db_name
+        )))
+    }
+}
+
+// 按 file_root 解析文件型数据源的路径（相对路径相对于 file_root），
+// 拒绝含 .. 或规范化后（含符号链接）位于 file_root 之外的路径
+pub fn file_path(file_root: &Path, path: &str) -> Result<PathBuf, AppError> {
+    let resolved = file_root.join(path);
+    let allowed = !resolved
+        .components()
+        .any(|component| component == Component::ParentDir)
+        && path_util::is_within(file_root, &resolved).unwrap_or(false);
+    if allowed {
+        Ok(resolved)
+    } else {
+        Err(AppError::DataSourceError(format!(
+            "路径不在允许访问的目录 {} 下: {}",
+            file_root.display(),
+            path
+        )))
+    }
+}
+
+// 合并按索引名、索引内顺序排列的 (索引名, 唯一, 主键, 列) 行
+pub fn collect_indexes(
+    rows: impl IntoIterator<Item = (String, bool, bool, String)>,
+) -> Vec<CatalogIndexVo> {
+    let mut indexes: Vec<CatalogIndexVo> = Vec::new();
+    for (index_name, unique, primary, column) in rows {
+        match indexes.last_mut() {
+            Some(index) if index.index_name == index_name => {
+                index.columns.push(column)
+            }
+            _ => indexes.push(CatalogIndexVo {
+                index_name,
+                columns: vec![column],
+                unique,
+                primary,
+            }),
+        }
+    }
+    indexes
+}
+
+// 合并按约束名、列顺序排列的 (约束名, 列, 引用schema, 引用表, 引用列) 行
+pub fn collect_foreign_keys(
+    rows: impl IntoIterator<Item = (String, String, String, String, String)>,
+) -> Vec<CatalogForeignKeyVo> {
+    let mut foreign_keys: Vec<CatalogForeignKeyVo> = Vec::new();
+    for (constraint_name, column, schema, table, referenced_column) in rows {
+        match foreign_keys.last_mut() {
+            Some(foreign_key)
+                if foreign_key.constraint_name == constraint_name =>
+            {
+                foreign_key.columns.push(column);
+                foreign_key.referenced_columns.push(referenced_column);
+            }
+            _ => foreign_keys.push(CatalogForeignKeyVo {
+                constraint_name,
+                columns: vec![column],
+                referenced_schema: schema,
+                referenced_table: table,
+                referenced_columns: vec![referenced_column],
+            }),
+        }
+    }
+    foreign_keys
+}
+
+// 浮点数转换为JSON数字，NaN与无穷大无法表示为数字，按字符串返回
+pub fn float_value(value: f64) -> Value {
+    serde_json::Number::from_f64(value)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+// 按标准SQL以双引号引用标识符
+pub fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+// 随机抽样预览时按估算行数计算的抽样概率：先按此概率逐行抽样，
+// 再对抽中的行随机排序取 limit 行，只排序少量行。概率放大一倍以抵消估算偏小。
+// 估算行数未知（0）或不超过 limit 的两倍时返回None，直接对全表随机排序
+pub fn sample_probability(limit: usize, estimated_rows: u64) -> Option<f64> {
+    if estimated_rows == 0 {
+        return None;
+    }
+    let probability = limit as f64 * 2.0 / estimated_rows as f64;
+    (probability < 1.0).then_some(probability)
+}
+
+// 查询超时看门狗：超时后在后台线程调用 interrupt 中止查询，
+// 查询先结束时随看门狗析构而取消。用于没有服务端超时设置的嵌入式数据库
+pub struct Watchdog {
+    _cancel: Option<Sender<()>>,
+}
+
+impl Watchdog {
+    pub fn start(
+        timeout: Option<Duration>,
+        interrupt: impl FnOnce() + Send + 'static,
+    ) -> Self {
+        let Some(timeout) = timeout else {
+            return Self { _cancel: None };
+        };
+        let (cancel, cancelled) = mpsc::channel::<()>();
+        std::thread::spawn(move || {
+            if let Err(RecvTimeoutError::Timeout) =
+                cancelled.recv_timeout(timeout)
+            {
+                interrupt();
+            }
+        });
+        Self {
+            _cancel: Some(cancel),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::common::model::entity::BaseEntity;
+
+    // 文件型数据源的测试数据，db_name 为相对 file_root 的路径
+    pub(crate) fn file_data_source(
+        db_type: &str,
+        db_name: &str,
+        column_types: Option<&str>,
+    ) -> DataSourceDetailVo {
+        DataSourceDetailVo {
+            data_source_id: 1,
+            code: String::from("files"),
+            name: String::from("files"),
+            remark: String::new(),
+            db_type: db_type.to_string(),
+            db_host: String::new(),
+            db_port: 0,
+            db_name: db_name.to_string(),
+            db_username: String::new(),
+            db_password: String::new(),
+            file_delimiter: None,
+            file_header: None,
+            file_encoding: None,
+            file_date_format: None,
+            file_compression: None,
+            file_column_types: column_types.map(String::from),
+            base_entity: BaseEntity::new(0),
+        }
+    }
+
+    pub(crate) fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "{}_{}_{}",
+            name,
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    pub(crate) fn row_count(
+        connector: &mut dyn Connector,
+        sql: &str,
+    ) -> Result<usize, AppError> {
+        let mut rows = 0;
+        connector.stream_rows(sql, &mut |_| {
+            rows += 1;
+            Ok(true)
+        })?;
+        Ok(rows)
+    }
+
+    #[test]
+    fn sample_probabilities() {
+        assert_eq!(sample_probability(100, 0), None);
+        assert_eq!(sample_probability(100, 1), None);
+        assert_eq!(sample_probability(100, 150), None);
+        assert_eq!(sample_probability(100, 200), None);
+        assert_eq!(sample_probability(100, 400), Some(0.5));
+        assert_eq!(sample_probability(100, 1_000_000), Some(0.0002));
+        let tiny = sample_probability(1, u64::MAX).unwrap();
+        assert!(tiny > 0.0 && tiny < 1e-18);
+        // 拼接到SQL中的小数不使用科学计数法
+        assert!(!tiny.to_string().contains('e'));
+    }
+
+    #[test]
+    fn duckdb_files_cannot_reach_other_files() {
+        let root = temp_root("duckdb_connector");
+        let outside = root.with_extension("secret");
+        fs::write(&outside, "secret\n").unwrap();
+        {
+            let conn = duckdb::Connection::open(root.join("warehouse.duckdb"))
+                .unwrap();
+            conn.execute_batch(&format!(
+                "CREATE TABLE t AS SELECT 1 AS id; \
+                 CREATE VIEW leak AS SELECT * FROM read_text('{}')",
+                outside.display()
+            ))
+            .unwrap();
+        }
+        let mut connector = open(
+            &file_data_source(DB_TYPE_DUCKDB, "warehouse.duckdb", None),
+            &root,
+        )
+        .unwrap();
+        assert_eq!(
+            row_count(connector.as_mut(), "SELECT * FROM t").unwrap(),
+            1
+        );
+        for sql in ["SELECT * FROM leak", "SET enable_external_access = true"] {
+            assert!(row_count(connector.as_mut(), sql).is_err(), "{}", sql);
+        }
+        fs::remove_dir_all(&root).unwrap();
+        fs::remove_file(&outside).unwrap();
+    }
+
+    #[test]
+    fn sqlite_files_cannot_attach() {
+        let root = temp_root("sqlite_connector");
+        {
+            let conn =
+                rusqlite::Connection::open(root.join("app.sqlite")).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE t (id INTEGER); INSERT INTO t VALUES (1)",
+            )
+            .unwrap();
+        }
+        let mut connector =
+            open(&file_data_source(DB_TYPE_SQLITE, "app.sqlite", None), &root)
+                .unwrap();
+        assert_eq!(
+            row_count(connector.as_mut(), "SELECT * FROM t").unwrap(),
+            1
+        );
+        let attach = format!(
+            "ATTACH '{}' AS other",
+            root.join("other.sqlite").display()
+        );
+        assert!(row_count(connector.as_mut(), &attach).is_err());
+        assert!(
+            row_count(connector.as_mut(), "INSERT INTO t VALUES (2)").is_err()
+        );
+        assert!(!root.join("other.sqlite").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn file_paths_stay_under_root() {
+        let root = temp_root("connector");
+        assert_eq!(
+            file_path(&root, "sales/orders.db").unwrap(),
+            root.join("sales/orders.db")
+        );
+        assert!(file_path(&root, "logs/*/*.csv").is_ok());
+        assert!(
+            file_path(&root, &root.join("a.parquet").to_string_lossy()).is_ok()
+        );
+        assert!(file_path(&root, "/etc/passwd").is_err());
+        assert!(file_path(&root, "../outside.db").is_err());
+        assert!(file_path(&root, "logs/*/../../../*.csv").is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,28 +1,52 @@
+use std::{path::PathBuf, time::Duration};
+
 use r2d2::Pool;
-use r2d2_mysql::{MySqlConnectionManager, mysql::OptsBuilder};
+use r2d2_mysql::{
+    MySqlConnectionManager,
+    mysql::{Conn, Error as MyError, OptsBuilder},
+};
+use redis::Commands;
+use serde::{Serialize, de::DeserializeOwned};
 
 use crate::{
     app::Infrastructure,
     biz::datasource::{
-        model::data_source::{
-            DataSource, DataSourceCreateBo, DataSourceDetailVo,
-            DataSourceListVo, DataSourceUpdateBo,
+        model::{
+            catalog::{
+                CatalogTableSchemaVo, CatalogTableVo, TablePreviewBo,
+                TablePreviewVo,
+            },
+            data_source::{
+                DB_TYPE_MYSQL, DataSource, DataSourceCreateBo,
+                DataSourceDetailVo, DataSourceListVo, DataSourceUpdateBo,
+            },
         },
         repository::data_source_repo::DataSourceRepository,
+        service::connector::{self, Connector},
     },
+    config::config::DataSourceConfig,
+    error::error::AppError,
     sys::user::model::user::User,
-    util::crypto_util::decrypt_password,
 };
 
 #[derive(Clone)]
 pub struct DataSourceService {
     data_source_repo: DataSourceRepository,
+    redis_pool: Pool<redis::Client>,
+    config: DataSourceConfig,
+}
+
+// 数据源目录信息（表列表、表结构）的缓存键前缀
+fn catalog_key_prefix(data_source_id: i64) -> String {
+    format!("datasource::catalog::{}::", data_source_id)
 }
 
 impl DataSourceService {
     pub fn new(infra: &Infrastructure) -> Self {
         Self {
             data_source_repo: DataSourceRepository::new(infra.batis.clone()),
+            redis_pool: infra.pool.redis_pool.clone(),
+            config: infra.config.datasource.clone(),
         }
     }
 
@@ -93,7 +117,11 @@ impl DataSourceService {
             .update_by_id(&entity, data_source_id)
             .await
         {
-            Ok(_) => true,
+            Ok(_) => {
+                // 连接信息可能已变更，缓存的目录信息作废
+                self.evict_catalog(*data_source_id);
+                true
+            }
             Err(e) => {
                 tracing::error!("更新失败: {:?}", e);
                 false
@@ -120,7 +148,10 @@ impl DataSourceService {
 
         // 更新实体（软删除）
         match self.data_source_repo.update_by_id(&entity, &id).await {
-            Ok(_) => true,
+            Ok(_) => {
+                self.evict_catalog(id);
+                true
+            }
             Err(e) => {
                 tracing::error!("删除失败: {:?}", e);
                 false
@@ -130,7 +161,10 @@ impl DataSourceService {
 
     pub async fn true_delete_by_id(&self, id: i64) -> bool {
         match self.data_source_repo.delete_by_id(&id).await {
-            Ok(_) => true,
+            Ok(_) => {
+                self.evict_catalog(id);
+                true
+            }
             Err(e) => {
                 tracing::error!("删除失败: {:?}", e);
                 false
@@ -139,53 +173,241 @@ impl DataSourceService {
     }
 
     pub async fn test_data_source(&self, id: i64) -> (bool, String) {
-        let entity = self.get_data_source_by_id(id).await;
-        match entity {
-            Some(data_source) => {
-                // 解密数据库密码
-                let decrypted_password =
-                    match decrypt_password(&data_source.db_password) {
-                        Ok(password) => password,
-                        Err(e) => {
-                            tracing::error!("密码解密失败: {}", e);
-                            return (false, format!("密码解密失败: {}", e));
-                        }
-                    };
-
-                // 创建MySQL连接选项用于r2d2连接池
-                let opts = OptsBuilder::new()
-                    .ip_or_hostname(Some(data_source.db_host.clone()))
-                    .tcp_port(data_source.db_port)
-                    .user(Some(data_source.db_username.clone()))
-                    .pass(Some(decrypted_password))
-                    .db_name(Some(data_source.db_name.clone()));
-
-                // 创建连接管理器
-                let rb_manager = MySqlConnectionManager::new(opts);
-                // 创建r2d2连接池
-                match Pool::new(rb_manager) {
-                    Ok(_) => {
-                        tracing::info!(
-                            "Successfully created r2d2 database connection pool for: {}",
-                            data_source.db_name
-                        );
-                        (
-                            true,
-                            String::from(
-                                "Connection pool created successfully",
-                            ),
-                        )
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to create r2d2 database connection pool: {}",
-                            e
-                        );
-                        (false, String::from("Connection pool creation failed"))
-                    }
-                }
+        // 按数据源类型建立连接并执行一次简单查询
+        match self.with_connector(id, |connector| connector.test()).await {
+            Ok(()) => {
+                tracing::info!("Successfully connected to data source: {}", id);
+                (true, String::from("Connection test succeeded"))
+            }
+            Err(e) => {
+                tracing::error!("Failed to connect to data source: {}", e);
+                (false, format!("Connection test failed: {}", e))
+            }
+        }
+    }
+
+    pub async fn list_schemas(&self, id: i64) -> Result<Vec<String>, AppError> {
+        self.with_connector(id, |connector| connector.list_schemas())
+            .await
+    }
+
+    pub async fn list_tables(
+        &self,
+        id: i64,
+        schema: Option<String>,
+        refresh: bool,
+    ) -> Result<Vec<CatalogTableVo>, AppError> {
+        let key = format!(
+            "{}tables::{}",
+            catalog_key_prefix(id),
+            schema.as_deref().unwrap_or_default()
+        );
+        self.cached(id, key, refresh, move |connector| {
+            let schema = match schema {
+                Some(schema) => schema,
+                None => connector.default_schema()?,
+            };
+            connector.list_tables(&schema)
+        })
+        .await
+    }
+
+    pub async fn get_table_schema(
+        &self,
+        id: i64,
+        schema: Option<String>,
+        table_name: String,
+        refresh: bool,
+    ) -> Result<CatalogTableSchemaVo, AppError> {
+        let key = format!(
+            "{}table::{}::{}",
+            catalog_key_prefix(id),
+            schema.as_deref().unwrap_or_default(),
+            table_name
+        );
+        self.cached(id, key, refresh, move |connector| {
+            let schema = match schema {
+                Some(schema) => schema,
+                None => connector.default_schema()?,
+            };
+            let columns = connector.list_columns(&schema, &table_name)?;
+            if columns.is_empty() {
+                return Err(AppError::BusinessError("表不存在"));
+            }
+            let indexes = connector.list_indexes(&schema, &table_name)?;
+            let foreign_keys =
+                connector.list_foreign_keys(&schema, &table_name)?;
+            Ok(CatalogTableSchemaVo::new(
+                schema,
+                table_name,
+                columns,
+                indexes,
+                foreign_keys,
+            ))
+        })
+        .await
+    }
+
+    // 预览表数据，行数受配置上限约束，查询超时后在数据库端中止
+    pub async fn preview_table(
+        &self,
+        id: i64,
+        table_name: String,
+        bo: TablePreviewBo,
+    ) -> Result<TablePreviewVo, AppError> {
+        let limit = bo.limit(self.config.preview_max_rows);
+        let timeout = Duration::from_secs(self.config.preview_timeout_secs);
+        self.with_connector(id, move |connector| {
+            let schema = match bo.schema {
+                Some(schema) => schema,
+                None => connector.default_schema()?,
+            };
+            connector.set_statement_timeout(timeout)?;
+            let sql = connector.preview_sql(
+                &schema,
+                &table_name,
+                limit,
+                bo.sample,
+            )?;
+            let mut rows = Vec::new();
+            let columns = connector.stream_rows(&sql, &mut |row| {
+                rows.push(row);
+                Ok(rows.len() < limit)
+            })?;
+            Ok(TablePreviewVo { columns, rows })
+        })
+        .await
+    }
+
+    // 优先读取Redis中缓存的目录信息，未命中或要求刷新时从数据源读取并写回缓存。
+    // 缓存读写失败只记录日志，不影响查询
+    async fn cached<T, F>(
+        &self,
+        id: i64,
+        key: String,
+        refresh: bool,
+        load: F,
+    ) -> Result<T, AppError>
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+        F: FnOnce(&mut dyn Connector) -> Result<T, AppError> + Send + 'static,
+    {
+        if !refresh {
+            let cached =
+                self.redis_pool.get().map_err(AppError::from).and_then(
+                    |mut conn| Ok(conn.get::<_, Option<String>>(&key)?),
+                );
+            match cached {
+                Ok(Some(json)) => match serde_json::from_str(&json) {
+                    Ok(value) => return Ok(value),
+                    Err(e) => tracing::warn!("目录缓存无法解析: {:?}", e),
+                },
+                Ok(None) => {}
+                Err(e) => tracing::warn!("读取目录缓存失败: {:?}", e),
             }
-            None => (false, String::from("Data source not found")),
         }
+
+        let value = self.with_connector(id, load).await?;
+        let stored = serde_json::to_string(&value)
+            .map_err(|e| AppError::DataSourceError(e.to_string()))
+            .and_then(|json| {
+                let mut conn = self.redis_pool.get()?;
+                Ok(conn.set_ex::<_, _, ()>(
+                    &key,
+                    json,
+                    self.config.catalog_cache_ttl_secs,
+                )?)
+            });
+        if let Err(e) = stored {
+            tracing::warn!("写入目录缓存失败: {:?}", e);
+        }
+        Ok(value)
+    }
+
+    // 清除数据源的全部目录缓存
+    fn evict_catalog(&self, id: i64) {
+        let evicted = self.redis_pool.get().map_err(AppError::from).and_then(
+            |mut conn| {
+                let keys: Vec<String> = conn
+                    .scan_match::<_, String>(format!(
+                        "{}*",
+                        catalog_key_prefix(id)
+                    ))?
+                    .collect();
+                if !keys.is_empty() {
+                    conn.del::<_, ()>(keys)?;
+                }
+                Ok(())
+            },
+        );
+        if let Err(e) = evicted {
+            tracing::warn!("清除目录缓存失败: {:?}", e);
+        }
+    }
+
+    // 在阻塞线程中打开数据源连接并执行操作，连接随操作结束关闭
+    pub async fn with_connector<T, F>(
+        &self,
+        id: i64,
+        f: F,
+    ) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut dyn Connector) -> Result<T, AppError> + Send + 'static,
+    {
+        let data_source = self
+            .get_data_source_by_id(id)
+            .await
+            .ok_or(AppError::BusinessError("数据源不存在"))?;
+        let file_root = PathBuf::from(&self.config.file_root);
+        tokio::task::spawn_blocking(move || {
+            f(connector::open(&data_source, &file_root)?.as_mut())
+        })
+        .await
+        .map_err(|e| AppError::DataSourceError(e.to_string()))?
+    }
+
+    // 根据数据源详情（已解密密码）创建MySQL r2d2连接池，建池时会校验连接是否可用。
+    // 数据传输的抽取逻辑基于MySQL，其他类型的数据源在此拒绝
+    pub fn create_pool(
+        data_source: &DataSourceDetailVo,
+        max_size: u32,
+    ) -> Result<Pool<MySqlConnectionManager>, AppError> {
+        Self::check_transferable(data_source)?;
+        // 创建连接管理器
+        let rb_manager = MySqlConnectionManager::new(Self::opts(data_source));
+        Ok(Pool::builder().max_size(max_size).build(rb_manager)?)
+    }
+
+    // 传输引擎（全量、增量、CDC、对账、回填）基于MySQL协议抽取，PostgreSQL等其他
+    // 类型的数据源只支持浏览目录与预览数据。创建任务时即校验，避免执行时才失败
+    pub fn check_transferable(
+        data_source: &DataSourceDetailVo,
+    ) -> Result<(), AppError> {
+        if !data_source.is_db_type(DB_TYPE_MYSQL) {
+            return Err(AppError::DataSourceError(format!(
+                "数据传输暂仅支持MySQL数据源，{} 数据源只支持浏览表结构与预览数据",
+                data_source.db_type
+            )));
+        }
+        Ok(())
+    }
+
+    // 创建不经连接池的独立连接，用于binlog复制等会独占连接的场景
+    pub fn connect(
+        data_source: &DataSourceDetailVo,
+        read_timeout: Option<Duration>,
+    ) -> Result<Conn, MyError> {
+        Conn::new(Self::opts(data_source).read_timeout(read_timeout))
+    }
+
+    // 数据源的MySQL连接选项
+    fn opts(data_source: &DataSourceDetailVo) -> OptsBuilder {
+        OptsBuilder::new()
+            .ip_or_hostname(Some(data_source.db_host.clone()))
+            .tcp_port(data_source.db_port)
+            .user(Some(data_source.db_username.clone()))
+            .pass(Some(data_source.db_password.clone()))
+            .db_name(Some(data_source.db_name.clone()))
     }
 }
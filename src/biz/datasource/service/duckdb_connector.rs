@@ -0,0 +1,388 @@
+use std::{path::Path, time::Duration};
+
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta};
+use duckdb::{AccessMode, Config, Connection, types::Value as DuckValue};
+use serde_json::{Map, Value};
+
+use crate::{
+    biz::datasource::{
+        model::{
+            catalog::{
+                CatalogColumnVo, CatalogForeignKeyVo, CatalogIndexVo,
+                CatalogTableVo, ResultColumnVo,
+            },
+            data_source::DataSourceDetailVo,
+        },
+        service::connector::{
+            Connector, Watchdog, collect_foreign_keys, database_file,
+            float_value, quote_ident,
+        },
+    },
+    error::error::AppError,
+};
+
+// 以只读方式打开DuckDB文件，其他进程持有写锁时打开会失败。
+// 关闭外部访问与扩展自动加载，文件中的视图无法读取主机上的其他文件
+pub struct DuckDbConnector {
+    conn: Connection,
+    statement_timeout: Option<Duration>,
+}
+
+impl DuckDbConnector {
+    pub fn open(
+        data_source: &DataSourceDetailVo,
+        file_root: &Path,
+    ) -> Result<Self, AppError> {
+        let config = Config::default()
+            .access_mode(AccessMode::ReadOnly)?
+            .enable_external_access(false)?
+            .enable_autoload_extension(false)?
+            .with("autoinstall_known_extensions", "false")?
+            .with("lock_configuration", "true")?;
+        let conn = Connection::open_with_flags(
+            database_file(data_source, file_root)?,
+            config,
+        )?;
+        Ok(Self::new(conn))
+    }
+
+    // 基于已有连接，用于在内存库中以视图方式读取文件
+    pub fn new(conn: Connection) -> Self {
+        Self {
+            conn,
+            statement_timeout: None,
+        }
+    }
+}
+
+impl Connector for DuckDbConnector {
+    fn test(&mut self) -> Result<(), AppError> {
+        self.conn.execute_batch("SELECT 1")?;
+        Ok(())
+    }
+
+    fn default_schema(&mut self) -> Result<String, AppError> {
+        Ok(self
+            .conn
+            .query_row("SELECT current_schema()", [], |row| row.get(0))?)
+    }
+
+    fn list_schemas(&mut self) -> Result<Vec<String>, AppError> {
+        let mut statement = self.conn.prepare(
+            "SELECT schema_name FROM information_schema.schemata \
+             WHERE catalog_name = current_database() \
+             AND schema_name NOT IN ('information_schema', 'pg_catalog') \
+             ORDER BY schema_name",
+        )?;
+        let schemas = statement
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(schemas)
+    }
+
+    fn list_tables(
+        &mut self,
+        schema: &str,
+    ) -> Result<Vec<CatalogTableVo>, AppError> {
+        let mut statement = self.conn.prepare(
+            "SELECT t.table_name, t.table_type, \
+             COALESCE(t.table_comment, ''), d.estimated_size \
+             FROM information_schema.tables t LEFT JOIN duckdb_tables() d \
+             ON d.database_name = t.table_catalog \
+             AND d.schema_name = t.table_schema \
+             AND d.table_name = t.table_name \
+             WHERE t.table_catalog = current_database() \
+             AND t.table_schema = ? ORDER BY t.table_name",
+        )?;
+        let tables = statement
+            .query_map([schema], |row| {
+                Ok(CatalogTableVo {
+                    schema: String::from(schema),
+                    table_name: row.get(0)?,
+                    table_type: row.get(1)?,
+                    comment: row.get(2)?,
+                    engine: None,
+                    estimated_rows: row.get(3)?,
+                    data_size: None,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(tables)
+    }
+
+    fn list_columns(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogColumnVo>, AppError> {
+        let mut statement = self.conn.prepare(
+            "SELECT c.column_name, c.data_type, c.is_nullable = 'YES', \
+             EXISTS (SELECT 1 FROM duckdb_constraints() k \
+             WHERE k.database_name = c.table_catalog \
+             AND k.schema_name = c.table_schema \
+             AND k.table_name = c.table_name \
+             AND k.constraint_type = 'PRIMARY KEY' \
+             AND list_contains(k.constraint_column_names, c.column_name)), \
+             COALESCE(c.column_comment, ''), c.ordinal_position, \
+             c.column_default \
+             FROM information_schema.columns c \
+             WHERE c.table_catalog = current_database() \
+             AND c.table_schema = ? AND c.table_name = ? \
+             ORDER BY c.ordinal_position",
+        )?;
+        let columns = statement
+            .query_map([schema, table_name], |row| {
+                Ok(CatalogColumnVo {
+                    column_name: row.get(0)?,
+                    data_type: row.get(1)?,
+                    nullable: row.get(2)?,
+                    default_value: row.get(6)?,
+                    primary_key: row.get(3)?,
+                    comment: row.get(4)?,
+                    ordinal_position: row.get(5)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(columns)
+    }
+
+    // 主键与唯一约束取自 duckdb_constraints()，其余索引取自 duckdb_indexes()
+    fn list_indexes(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogIndexVo>, AppError> {
+        let mut statement = self.conn.prepare(
+            "SELECT constraint_name, true, constraint_type = 'PRIMARY KEY', \
+             constraint_column_names FROM duckdb_constraints() \
+             WHERE database_name = current_database() \
+             AND schema_name = ? AND table_name = ? \
+             AND constraint_type IN ('PRIMARY KEY', 'UNIQUE') \
+             UNION ALL \
+             SELECT index_name, is_unique, is_primary, expressions \
+             FROM duckdb_indexes() \
+             WHERE database_name = current_database() \
+             AND schema_name = ? AND table_name = ? \
+             ORDER BY 1",
+        )?;
+        let indexes = statement
+            .query_map([schema, table_name, schema, table_name], |row| {
+                Ok(CatalogIndexVo {
+                    index_name: row.get(0)?,
+                    unique: row.get(1)?,
+                    primary: row.get(2)?,
+                    columns: string_list(row.get(3)?),
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(indexes)
+    }
+
+    // DuckDB的外键只能引用同一schema下的表
+    fn list_foreign_keys(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogForeignKeyVo>, AppError> {
+        let mut statement = self.conn.prepare(
+            "SELECT constraint_name, constraint_column_names, \
+             referenced_table, referenced_column_names \
+             FROM duckdb_constraints() \
+             WHERE database_name = current_database() \
+             AND schema_name = ? AND table_name = ? \
+             AND constraint_type = 'FOREIGN KEY' ORDER BY constraint_name",
+        )?;
+        let rows = statement
+            .query_map([schema, table_name], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    string_list(row.get(1)?),
+                    row.get::<_, String>(2)?,
+                    string_list(row.get(3)?),
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(collect_foreign_keys(rows.into_iter().flat_map(
+            |(name, columns, table, referenced)| {
+                columns.into_iter().zip(referenced).map(
+                    move |(column, referenced_column)| {
+                        (
+                            name.clone(),
+                            column,
+                            String::from(schema),
+                            table.clone(),
+                            referenced_column,
+                        )
+                    },
+                )
+            },
+        )))
+    }
+
+    // DuckDB没有语句超时设置，执行查询时由看门狗线程中断
+    fn set_statement_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), AppError> {
+        self.statement_timeout = Some(timeout);
+        Ok(())
+    }
+
+    // 随机抽样使用蓄水池抽样，只需扫描一遍表
+    fn preview_sql(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+        limit: usize,
+        sample: bool,
+    ) -> Result<String, AppError> {
+        let table =
+            format!("{}.{}", quote_ident(schema), quote_ident(table_name));
+        if sample {
+            Ok(format!(
+                "SELECT * FROM {} USING SAMPLE reservoir({} ROWS)",
+                table, limit
+            ))
+        } else {
+            Ok(format!("SELECT * FROM {} LIMIT {}", table, limit))
+        }
+    }
+
+    // 列类型通过 DESCRIBE 取得，与DuckDB中的类型名一致
+    fn stream_rows(
+        &mut self,
+        sql: &str,
+        on_row: &mut dyn FnMut(Vec<Value>) -> Result<bool, AppError>,
+    ) -> Result<Vec<ResultColumnVo>, AppError> {
+        let interrupt = self.conn.interrupt_handle();
+        let _watchdog = Watchdog::start(self.statement_timeout, move || {
+            interrupt.interrupt()
+        });
+        let mut describe = self.conn.prepare(&format!("DESCRIBE {}", sql))?;
+        let columns: Vec<ResultColumnVo> = describe
+            .query_map([], |row| {
+                Ok(ResultColumnVo {
+                    name: row.get(0)?,
+                    data_type: row.get(1)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let mut statement = self.conn.prepare(sql)?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let values = (0..columns.len())
+                .map(|index| row.get::<_, DuckValue>(index).map(json_value))
+                .collect::<Result<_, _>>()?;
+            if !on_row(values)? {
+                break;
+            }
+        }
+        Ok(columns)
+    }
+}
+
+// 字符串列表，duckdb_indexes() 的 expressions 在部分版本中为列表的文本形式
+fn string_list(value: DuckValue) -> Vec<String> {
+    match value {
+        DuckValue::List(values) | DuckValue::Array(values) => values
+            .into_iter()
+            .filter_map(|value| match value {
+                DuckValue::Text(text) => Some(text),
+                _ => None,
+            })
+            .collect(),
+        DuckValue::Text(text) => text
+            .trim_matches(|c| c == '[' || c == ']')
+            .split(", ")
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// 超出 i64/u64 范围的整数与精确小数按字符串返回，避免精度丢失
+fn json_value(value: DuckValue) -> Value {
+    match value {
+        DuckValue::Null => Value::Null,
+        DuckValue::Boolean(v) => Value::Bool(v),
+        DuckValue::TinyInt(v) => Value::from(v),
+        DuckValue::SmallInt(v) => Value::from(v),
+        DuckValue::Int(v) => Value::from(v),
+        DuckValue::BigInt(v) => Value::from(v),
+        DuckValue::HugeInt(v) => Value::String(v.to_string()),
+        DuckValue::UTinyInt(v) => Value::from(v),
+        DuckValue::USmallInt(v) => Value::from(v),
+        DuckValue::UInt(v) => Value::from(v),
+        DuckValue::UBigInt(v) => Value::from(v),
+        DuckValue::Float(v) => float_value(v as f64),
+        DuckValue::Double(v) => float_value(v),
+        DuckValue::Decimal(v) => Value::String(v.to_string()),
+        DuckValue::Timestamp(unit, v) => {
+            match DateTime::from_timestamp_micros(unit.to_micros(v)) {
+                Some(datetime) => Value::String(
+                    datetime
+                        .naive_utc()
+                        .format("%Y-%m-%d %H:%M:%S%.f")
+                        .to_string(),
+                ),
+                None => Value::Null,
+            }
+        }
+        DuckValue::Text(v) | DuckValue::Enum(v) => Value::String(v),
+        DuckValue::Blob(v) => {
+            Value::String(general_purpose::STANDARD.encode(v))
+        }
+        DuckValue::Date32(days) => {
+            match NaiveDate::default()
+                .checked_add_signed(TimeDelta::days(days as i64))
+            {
+                Some(date) => {
+                    Value::String(date.format("%Y-%m-%d").to_string())
+                }
+                None => Value::Null,
+            }
+        }
+        DuckValue::Time64(unit, v) => {
+            let micros = unit.to_micros(v);
+            let time = NaiveTime::MIN
+                + TimeDelta::microseconds(micros.rem_euclid(86_400_000_000));
+            Value::String(time.format("%H:%M:%S%.f").to_string())
+        }
+        // 按ISO 8601时长格式返回，如 P1M2DT3.5S
+        DuckValue::Interval {
+            months,
+            days,
+            nanos,
+        } => Value::String(format!(
+            "P{}M{}DT{}S",
+            months,
+            days,
+            nanos as f64 / 1_000_000_000.0
+        )),
+        DuckValue::List(values) | DuckValue::Array(values) => {
+            Value::Array(values.into_iter().map(json_value).collect())
+        }
+        DuckValue::Struct(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(name, value)| (name.clone(), json_value(value.clone())))
+                .collect::<Map<_, _>>(),
+        ),
+        // MAP的键可以是任意类型，按键值对数组返回
+        DuckValue::Map(entries) => Value::Array(
+            entries
+                .iter()
+                .map(|(key, value)| {
+                    Value::Array(vec![
+                        json_value(key.clone()),
+                        json_value(value.clone()),
+                    ])
+                })
+                .collect(),
+        ),
+        DuckValue::Union(value) => json_value(*value),
+    }
+}
@@ -0,0 +1,512 @@
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use duckdb::Connection;
+use serde_json::Value;
+
+use crate::{
+    biz::{
+        datasource::{
+            model::{
+                catalog::{
+                    CatalogColumnVo, CatalogForeignKeyVo, CatalogIndexVo,
+                    CatalogTableVo, ResultColumnVo,
+                },
+                data_source::{
+                    DB_TYPE_CSV, DB_TYPE_JSONL, DB_TYPE_PARQUET,
+                    DataSourceDetailVo, is_file_column_type,
+                },
+            },
+            service::{
+                connector::{Connector, file_path},
+                duckdb_connector::DuckDbConnector,
+            },
+        },
+        transtask::service::trans_engine::{duck_ident, duck_string},
+    },
+    error::error::AppError,
+    util::path_util,
+};
+
+// 文件表所在的schema，即内存库的默认schema
+const FILE_SCHEMA: &str = "main";
+
+// CSV、JSON Lines文件可带的压缩扩展名
+const COMPRESSION_EXTENSIONS: &[&str] = &["gz", "zst"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum FileFormat {
+    Csv,
+    JsonLines,
+    Parquet,
+}
+
+impl FileFormat {
+    fn of(data_source: &DataSourceDetailVo) -> Option<Self> {
+        if data_source.is_db_type(DB_TYPE_CSV) {
+            Some(Self::Csv)
+        } else if data_source.is_db_type(DB_TYPE_JSONL) {
+            Some(Self::JsonLines)
+        } else if data_source.is_db_type(DB_TYPE_PARQUET) {
+            Some(Self::Parquet)
+        } else {
+            None
+        }
+    }
+
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Csv => &["csv", "tsv", "txt"],
+            Self::JsonLines => &["jsonl", "ndjson", "json"],
+            Self::Parquet => &["parquet"],
+        }
+    }
+}
+
+// 同一组文件，对应一张表
+struct FileTable {
+    name: String,
+    files: Vec<String>,
+}
+
+// 在内存DuckDB中为每组文件创建视图，通过 read_csv/read_json/read_parquet 读取，
+// 列定义与查询均由视图完成。打开时会读取各组文件以推断列类型
+pub struct FileConnector {
+    inner: DuckDbConnector,
+    tables: Vec<FileTable>,
+}
+
+impl FileConnector {
+    pub fn open(
+        data_source: &DataSourceDetailVo,
+        file_root: &Path,
+    ) -> Result<Self, AppError> {
+        let format = FileFormat::of(data_source).ok_or_else(|| {
+            AppError::DataSourceError(format!(
+                "不支持的文件类型: {}",
+                data_source.db_type
+            ))
+        })?;
+        let (root, pattern) =
+            location(&file_path(file_root, &data_source.db_name)?)?;
+        let conn = Connection::open_in_memory()?;
+        let files = conn
+            .prepare(&format!(
+                "SELECT file FROM glob({}) ORDER BY file",
+                duck_string(&pattern)
+            ))?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            // 排除经符号链接指向 file_root 之外的文件
+            .filter(|file| {
+                path_util::is_within(file_root, Path::new(file))
+                    .unwrap_or(false)
+            })
+            .collect();
+        let tables = group_files(format, &root, files);
+        // 视图只能读取上面解析出的文件：关闭外部访问并仅放行这些文件，
+        // 锁定配置后视图定义、类型覆盖中的文件函数都无法访问其他路径
+        let allowed_paths = tables
+            .iter()
+            .flat_map(|table| &table.files)
+            .map(|file| duck_string(file))
+            .collect::<Vec<_>>();
+        if !allowed_paths.is_empty() {
+            conn.execute_batch(&format!(
+                "SET allowed_paths = [{}]",
+                allowed_paths.join(", ")
+            ))?;
+        }
+        conn.execute_batch(
+            "SET enable_external_access = false; \
+             SET autoload_known_extensions = false; \
+             SET autoinstall_known_extensions = false; \
+             SET lock_configuration = true",
+        )?;
+
+        // 已保存的类型覆盖可能早于校验规则，拼接到SQL前再次校验
+        let column_types = data_source.column_types();
+        if let Some(ty) = column_types
+            .values()
+            .flat_map(|columns| columns.values())
+            .find(|ty| !is_file_column_type(ty))
+        {
+            return Err(AppError::DataSourceError(format!(
+                "不支持的列类型: {}",
+                ty
+            )));
+        }
+        for table in &tables {
+            let select = select_sql(
+                format,
+                data_source,
+                table,
+                column_types.get(&table.name),
+            );
+            conn.execute_batch(&format!(
+                "CREATE VIEW {} AS {}",
+                duck_ident(&table.name),
+                select
+            ))
+            .map_err(|e| {
+                AppError::DataSourceError(format!(
+                    "读取表 {} 的文件失败: {}",
+                    table.name, e
+                ))
+            })?;
+        }
+        Ok(Self {
+            inner: DuckDbConnector::new(conn),
+            tables,
+        })
+    }
+}
+
+impl Connector for FileConnector {
+    fn test(&mut self) -> Result<(), AppError> {
+        self.inner.test()?;
+        if self.tables.is_empty() {
+            return Err(AppError::DataSourceError(String::from(
+                "未找到匹配的文件",
+            )));
+        }
+        Ok(())
+    }
+
+    fn default_schema(&mut self) -> Result<String, AppError> {
+        Ok(String::from(FILE_SCHEMA))
+    }
+
+    fn list_schemas(&mut self) -> Result<Vec<String>, AppError> {
+        Ok(vec![String::from(FILE_SCHEMA)])
+    }
+
+    fn list_tables(
+        &mut self,
+        schema: &str,
+    ) -> Result<Vec<CatalogTableVo>, AppError> {
+        if schema != FILE_SCHEMA {
+            return Ok(Vec::new());
+        }
+        Ok(self
+            .tables
+            .iter()
+            .map(|table| CatalogTableVo {
+                schema: String::from(FILE_SCHEMA),
+                table_name: table.name.clone(),
+                table_type: String::from("FILE"),
+                comment: format!("{} file(s)", table.files.len()),
+                engine: None,
+                estimated_rows: None,
+                // 各文件大小之和，压缩文件为压缩后的大小
+                data_size: Some(
+                    table
+                        .files
+                        .iter()
+                        .filter_map(|file| std::fs::metadata(file).ok())
+                        .map(|metadata| metadata.len() as i64)
+                        .sum(),
+                ),
+            })
+            .collect())
+    }
+
+    fn list_columns(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogColumnVo>, AppError> {
+        self.inner.list_columns(schema, table_name)
+    }
+
+    fn list_indexes(
+        &mut self,
+        _schema: &str,
+        _table_name: &str,
+    ) -> Result<Vec<CatalogIndexVo>, AppError> {
+        Ok(Vec::new())
+    }
+
+    fn list_foreign_keys(
+        &mut self,
+        _schema: &str,
+        _table_name: &str,
+    ) -> Result<Vec<CatalogForeignKeyVo>, AppError> {
+        Ok(Vec::new())
+    }
+
+    fn set_statement_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), AppError> {
+        self.inner.set_statement_timeout(timeout)
+    }
+
+    fn preview_sql(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+        limit: usize,
+        sample: bool,
+    ) -> Result<String, AppError> {
+        self.inner.preview_sql(schema, table_name, limit, sample)
+    }
+
+    fn stream_rows(
+        &mut self,
+        sql: &str,
+        on_row: &mut dyn FnMut(Vec<Value>) -> Result<bool, AppError>,
+    ) -> Result<Vec<ResultColumnVo>, AppError> {
+        self.inner.stream_rows(sql, on_row)
+    }
+}
+
+// 解析数据源路径，返回用于计算表名的根目录与glob模式。
+// 路径可以是目录（递归匹配其下所有文件）、glob或单个文件
+fn location(location: &Path) -> Result<(PathBuf, String), AppError> {
+    let path = location.to_string_lossy();
+    if path.contains(['*', '?', '[']) {
+        // 根目录为第一个含通配符的路径段之前的部分
+        let root = location
+            .components()
+            .take_while(|component| {
+                !component
+                    .as_os_str()
+                    .to_string_lossy()
+                    .contains(['*', '?', '['])
+            })
+            .collect();
+        return Ok((root, path.into_owned()));
+    }
+    if location.is_dir() {
+        Ok((
+            location.to_path_buf(),
+            location.join("**").join("*").to_string_lossy().into_owned(),
+        ))
+    } else if location.is_file() {
+        Ok((
+            location.parent().map(Path::to_path_buf).unwrap_or_default(),
+            path.into_owned(),
+        ))
+    } else {
+        Err(AppError::DataSourceError(format!("路径不存在: {}", path)))
+    }
+}
+
+// 按文件分组：根目录下子目录中的文件（含Hive分区目录）按第一级子目录名成表，
+// 根目录下的文件按去掉末尾数字、日期后缀后的文件名成表，如 orders_20240101.csv 归入 orders
+fn group_files(
+    format: FileFormat,
+    root: &Path,
+    files: Vec<String>,
+) -> Vec<FileTable> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for file in files {
+        let path = Path::new(&file);
+        let Some(stem) = file_stem(format, path) else {
+            continue;
+        };
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let mut components = relative
+            .components()
+            .filter(|component| matches!(component, Component::Normal(_)));
+        let first = components.next();
+        let name = match (first, components.next()) {
+            (Some(directory), Some(_)) => {
+                directory.as_os_str().to_string_lossy().into_owned()
+            }
+            _ => group_name(&stem),
+        };
+        groups.entry(name).or_default().push(file);
+    }
+    groups
+        .into_iter()
+        .map(|(name, files)| FileTable { name, files })
+        .collect()
+}
+
+// 去掉压缩与格式扩展名后的文件名，扩展名不属于该格式时返回 None
+fn file_stem(format: FileFormat, path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy().into_owned();
+    let name = match strip_extension(&name, COMPRESSION_EXTENSIONS) {
+        Some(stem) if format != FileFormat::Parquet => stem,
+        _ => &name,
+    };
+    strip_extension(name, format.extensions()).map(String::from)
+}
+
+fn strip_extension<'a>(name: &'a str, extensions: &[&str]) -> Option<&'a str> {
+    let (stem, extension) = name.rsplit_once('.')?;
+    extensions
+        .iter()
+        .any(|ext| extension.eq_ignore_ascii_case(ext))
+        .then_some(stem)
+}
+
+fn group_name(stem: &str) -> String {
+    let name = stem.trim_end_matches(|c: char| {
+        c.is_ascii_digit() || matches!(c, '_' | '-' | '.')
+    });
+    String::from(if name.is_empty() { stem } else { name })
+}
+
+// 视图的查询语句。CSV的列类型覆盖通过 types 参数在读取时生效，
+// 其他格式在读取后转换
+fn select_sql(
+    format: FileFormat,
+    data_source: &DataSourceDetailVo,
+    table: &FileTable,
+    column_types: Option<&BTreeMap<String, String>>,
+) -> String {
+    let files = table
+        .files
+        .iter()
+        .map(|file| duck_string(file))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut options = vec![String::from("union_by_name = true")];
+    let text_option = |name: &str, value: &Option<String>| {
+        value
+            .as_ref()
+            .map(|value| format!("{} = {}", name, duck_string(value)))
+    };
+    let column_types = column_types.filter(|types| !types.is_empty());
+    let function = match format {
+        FileFormat::Csv => {
+            if let Some(header) = data_source.file_header {
+                options.push(format!("header = {}", header));
+            }
+            options.extend(
+                [
+                    text_option("delim", &data_source.file_delimiter),
+                    text_option("encoding", &data_source.file_encoding),
+                    text_option("dateformat", &data_source.file_date_format),
+                    text_option("compression", &data_source.file_compression),
+                ]
+                .into_iter()
+                .flatten(),
+            );
+            if let Some(types) = column_types {
+                options.push(format!(
+                    "types = {{{}}}",
+                    types
+                        .iter()
+                        .map(|(column, ty)| {
+                            format!(
+                                "{}: {}",
+                                duck_string(column),
+                                duck_string(ty)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+            "read_csv"
+        }
+        FileFormat::JsonLines => {
+            options.push(String::from("format = 'newline_delimited'"));
+            options.extend(
+                [
+                    text_option("dateformat", &data_source.file_date_format),
+                    text_option("compression", &data_source.file_compression),
+                ]
+                .into_iter()
+                .flatten(),
+            );
+            "read_json"
+        }
+        FileFormat::Parquet => {
+            options.push(String::from("hive_partitioning = true"));
+            "read_parquet"
+        }
+    };
+    let replace = match column_types {
+        Some(types) if format != FileFormat::Csv => format!(
+            " REPLACE ({})",
+            types
+                .iter()
+                .map(|(column, ty)| {
+                    let column = duck_ident(column);
+                    format!("CAST({} AS {}) AS {}", column, ty, column)
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        _ => String::new(),
+    };
+    format!(
+        "SELECT *{} FROM {}([{}], {})",
+        replace,
+        function,
+        files,
+        options.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::biz::datasource::service::connector::tests::{
+        file_data_source as data_source, row_count, temp_root,
+    };
+
+    #[test]
+    fn views_read_only_resolved_files() {
+        let root = temp_root("file_connector");
+        fs::create_dir_all(root.join("events")).unwrap();
+        fs::write(root.join("orders.csv"), "id,amount\n1,2.5\n2,3.5\n")
+            .unwrap();
+        fs::write(
+            root.join("events/part1.jsonl"),
+            "{\"id\": 1, \"amount\": \"2.50\"}\n",
+        )
+        .unwrap();
+        let outside = root.with_extension("secret");
+        fs::write(&outside, "secret\n").unwrap();
+
+        let mut csv =
+            FileConnector::open(&data_source("csv", "", None), &root).unwrap();
+        assert_eq!(row_count(&mut csv, "SELECT * FROM orders").unwrap(), 2);
+        for sql in [
+            format!(
+                "SELECT * FROM read_text({})",
+                duck_string(&outside.to_string_lossy())
+            ),
+            format!(
+                "SELECT * FROM read_csv({})",
+                duck_string(&outside.to_string_lossy())
+            ),
+            String::from("SET enable_external_access = true"),
+        ] {
+            assert!(row_count(&mut csv, &sql).is_err(), "{}", sql);
+        }
+
+        let mut jsonl = FileConnector::open(
+            &data_source(
+                "jsonl",
+                "",
+                Some(r#"{"events": {"amount": "DECIMAL(10, 2)"}}"#),
+            ),
+            &root,
+        )
+        .unwrap();
+        assert_eq!(row_count(&mut jsonl, "SELECT * FROM events").unwrap(), 1);
+
+        let injected = data_source(
+            "jsonl",
+            "",
+            Some(r#"{"events": {"amount": "VARCHAR) AS amount, (SELECT 1"}}"#),
+        );
+        assert!(FileConnector::open(&injected, &root).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+        fs::remove_file(&outside).unwrap();
+    }
+}
@@ -1 +1,7 @@
+pub mod connector;
 pub mod data_source_service;
+pub mod duckdb_connector;
+pub mod file_connector;
+pub mod mysql_connector;
+pub mod postgres_connector;
+pub mod sqlite_connector;
@@ -0,0 +1,404 @@
+use std::time::Duration;
+
+use base64::{Engine as _, engine::general_purpose};
+use r2d2_mysql::mysql::{
+    Column, Conn, Value as MyValue,
+    consts::{ColumnFlags, ColumnType},
+    prelude::Queryable,
+};
+use serde_json::Value;
+
+use crate::{
+    biz::datasource::{
+        model::{
+            catalog::{
+                CatalogColumnVo, CatalogForeignKeyVo, CatalogIndexVo,
+                CatalogTableVo, ResultColumnVo,
+            },
+            data_source::DataSourceDetailVo,
+        },
+        service::{
+            connector::{
+                Connector, collect_foreign_keys, collect_indexes, float_value,
+                sample_probability,
+            },
+            data_source_service::DataSourceService,
+        },
+    },
+    error::error::AppError,
+};
+
+// MySQL自带的系统库
+const SYSTEM_SCHEMAS: &[&str] =
+    &["information_schema", "mysql", "performance_schema", "sys"];
+
+// MySQL binary 字符集编号，用于区分二进制串与文本
+const BINARY_CHARSET: u16 = 63;
+
+pub struct MySqlConnector {
+    conn: Conn,
+    db_name: String,
+}
+
+impl MySqlConnector {
+    pub fn open(data_source: &DataSourceDetailVo) -> Result<Self, AppError> {
+        Ok(Self {
+            conn: DataSourceService::connect(data_source, None)?,
+            db_name: data_source.db_name.clone(),
+        })
+    }
+}
+
+impl Connector for MySqlConnector {
+    fn test(&mut self) -> Result<(), AppError> {
+        self.conn.query_drop("SELECT 1")?;
+        Ok(())
+    }
+
+    fn default_schema(&mut self) -> Result<String, AppError> {
+        Ok(self.db_name.clone())
+    }
+
+    fn list_schemas(&mut self) -> Result<Vec<String>, AppError> {
+        let schemas = self.conn.query::<String, _>(
+            "SELECT SCHEMA_NAME FROM information_schema.SCHEMATA \
+             ORDER BY SCHEMA_NAME",
+        )?;
+        Ok(schemas
+            .into_iter()
+            .filter(|schema| {
+                !SYSTEM_SCHEMAS.contains(&schema.to_lowercase().as_str())
+            })
+            .collect())
+    }
+
+    fn list_tables(
+        &mut self,
+        schema: &str,
+    ) -> Result<Vec<CatalogTableVo>, AppError> {
+        let tables = self.conn.exec_map(
+            "SELECT TABLE_NAME, TABLE_TYPE, TABLE_COMMENT, ENGINE, \
+             TABLE_ROWS, DATA_LENGTH FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = ? ORDER BY TABLE_NAME",
+            (schema,),
+            |(table_name, table_type, comment, engine, rows, size): (
+                String,
+                String,
+                Option<String>,
+                Option<String>,
+                Option<i64>,
+                Option<i64>,
+            )| CatalogTableVo {
+                schema: String::from(schema),
+                table_name,
+                table_type,
+                comment: comment.unwrap_or_default(),
+                engine,
+                estimated_rows: rows,
+                data_size: size,
+            },
+        )?;
+        Ok(tables)
+    }
+
+    fn list_columns(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogColumnVo>, AppError> {
+        let columns = self.conn.exec_map(
+            "SELECT COLUMN_NAME, COLUMN_TYPE, IS_NULLABLE, COLUMN_DEFAULT, \
+             COLUMN_KEY, COLUMN_COMMENT, ORDINAL_POSITION \
+             FROM information_schema.COLUMNS \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION",
+            (schema, table_name),
+            |(
+                column_name,
+                data_type,
+                nullable,
+                default_value,
+                column_key,
+                comment,
+                ordinal_position,
+            ): (
+                String,
+                String,
+                String,
+                Option<String>,
+                String,
+                Option<String>,
+                i64,
+            )| {
+                CatalogColumnVo {
+                    column_name,
+                    data_type,
+                    nullable: nullable == "YES",
+                    default_value,
+                    primary_key: column_key == "PRI",
+                    comment: comment.unwrap_or_default(),
+                    ordinal_position,
+                }
+            },
+        )?;
+        Ok(columns)
+    }
+
+    // 函数索引的列名为空
+    fn list_indexes(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogIndexVo>, AppError> {
+        let rows = self.conn.exec_map(
+            "SELECT INDEX_NAME, NON_UNIQUE, COLUMN_NAME \
+             FROM information_schema.STATISTICS \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? \
+             ORDER BY INDEX_NAME, SEQ_IN_INDEX",
+            (schema, table_name),
+            |(index_name, non_unique, column): (
+                String,
+                i64,
+                Option<String>,
+            )| {
+                let primary = index_name == "PRIMARY";
+                (
+                    index_name,
+                    non_unique == 0,
+                    primary,
+                    column.unwrap_or_default(),
+                )
+            },
+        )?;
+        Ok(collect_indexes(rows))
+    }
+
+    fn list_foreign_keys(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogForeignKeyVo>, AppError> {
+        let rows = self.conn.exec_map(
+            "SELECT CONSTRAINT_NAME, COLUMN_NAME, REFERENCED_TABLE_SCHEMA, \
+             REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME \
+             FROM information_schema.KEY_COLUMN_USAGE \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? \
+             AND REFERENCED_TABLE_NAME IS NOT NULL \
+             ORDER BY CONSTRAINT_NAME, ORDINAL_POSITION",
+            (schema, table_name),
+            |row: (String, String, String, String, String)| row,
+        )?;
+        Ok(collect_foreign_keys(rows))
+    }
+
+    // MAX_EXECUTION_TIME 只对只读的 SELECT 生效
+    fn set_statement_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), AppError> {
+        self.conn.query_drop(format!(
+            "SET SESSION MAX_EXECUTION_TIME = {}",
+            timeout.as_millis()
+        ))?;
+        Ok(())
+    }
+
+    // 随机抽样按 information_schema 中的估算行数先以 RAND() < p 逐行抽样，
+    // 再对抽中的行随机排序，避免对全表排序。估算行数不可用（视图、统计信息为0）
+    // 或表较小时对全表随机排序，由查询超时兜底
+    fn preview_sql(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+        limit: usize,
+        sample: bool,
+    ) -> Result<String, AppError> {
+        let table =
+            format!("{}.{}", mysql_ident(schema), mysql_ident(table_name));
+        if !sample {
+            return Ok(format!("SELECT * FROM {} LIMIT {}", table, limit));
+        }
+        let rows: Option<Option<u64>> = self.conn.exec_first(
+            "SELECT TABLE_ROWS FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+            (schema, table_name),
+        )?;
+        let filter = rows
+            .flatten()
+            .and_then(|rows| sample_probability(limit, rows))
+            .map(|probability| format!(" WHERE RAND() < {}", probability))
+            .unwrap_or_default();
+        Ok(format!(
+            "SELECT * FROM {}{} ORDER BY RAND() LIMIT {}",
+            table, filter, limit
+        ))
+    }
+
+    // 使用二进制协议执行以取得带类型的值。提前结束时，剩余的行由驱动读取后丢弃
+    fn stream_rows(
+        &mut self,
+        sql: &str,
+        on_row: &mut dyn FnMut(Vec<Value>) -> Result<bool, AppError>,
+    ) -> Result<Vec<ResultColumnVo>, AppError> {
+        let mut result = self.conn.exec_iter(sql, ())?;
+        let columns = result.columns().as_ref().to_vec();
+        for row in result.by_ref() {
+            let values = row?
+                .unwrap()
+                .into_iter()
+                .zip(&columns)
+                .map(|(value, column)| json_value(column, value))
+                .collect();
+            if !on_row(values)? {
+                break;
+            }
+        }
+        Ok(columns
+            .iter()
+            .map(|column| ResultColumnVo {
+                name: column.name_str().into_owned(),
+                data_type: type_name(column),
+            })
+            .collect())
+    }
+}
+
+fn mysql_ident(ident: &str) -> String {
+    format!("`{}`", ident.replace('`', "``"))
+}
+
+// 数值列的字符集同样为 binary，仅字符串与BLOB类型按二进制处理
+fn is_binary(column: &Column) -> bool {
+    column.character_set() == BINARY_CHARSET
+        && matches!(
+            column.column_type(),
+            ColumnType::MYSQL_TYPE_TINY_BLOB
+                | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+                | ColumnType::MYSQL_TYPE_LONG_BLOB
+                | ColumnType::MYSQL_TYPE_BLOB
+                | ColumnType::MYSQL_TYPE_VARCHAR
+                | ColumnType::MYSQL_TYPE_VAR_STRING
+                | ColumnType::MYSQL_TYPE_STRING
+                | ColumnType::MYSQL_TYPE_GEOMETRY
+        )
+}
+
+// 由结果集列元数据得到类型名
+fn type_name(column: &Column) -> String {
+    let binary = is_binary(column);
+    let name = match column.column_type() {
+        ColumnType::MYSQL_TYPE_TINY => "tinyint",
+        ColumnType::MYSQL_TYPE_SHORT => "smallint",
+        ColumnType::MYSQL_TYPE_INT24 => "mediumint",
+        ColumnType::MYSQL_TYPE_LONG => "int",
+        ColumnType::MYSQL_TYPE_LONGLONG => "bigint",
+        ColumnType::MYSQL_TYPE_DECIMAL | ColumnType::MYSQL_TYPE_NEWDECIMAL => {
+            "decimal"
+        }
+        ColumnType::MYSQL_TYPE_FLOAT => "float",
+        ColumnType::MYSQL_TYPE_DOUBLE => "double",
+        ColumnType::MYSQL_TYPE_YEAR => "year",
+        ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE => "date",
+        ColumnType::MYSQL_TYPE_DATETIME | ColumnType::MYSQL_TYPE_DATETIME2 => {
+            "datetime"
+        }
+        ColumnType::MYSQL_TYPE_TIMESTAMP
+        | ColumnType::MYSQL_TYPE_TIMESTAMP2 => "timestamp",
+        ColumnType::MYSQL_TYPE_TIME | ColumnType::MYSQL_TYPE_TIME2 => "time",
+        ColumnType::MYSQL_TYPE_BIT => "bit",
+        ColumnType::MYSQL_TYPE_JSON => "json",
+        ColumnType::MYSQL_TYPE_GEOMETRY => "geometry",
+        ColumnType::MYSQL_TYPE_TINY_BLOB
+        | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+        | ColumnType::MYSQL_TYPE_LONG_BLOB
+        | ColumnType::MYSQL_TYPE_BLOB => {
+            if binary {
+                "blob"
+            } else {
+                "text"
+            }
+        }
+        ColumnType::MYSQL_TYPE_STRING => {
+            if binary {
+                "binary"
+            } else {
+                "char"
+            }
+        }
+        _ if binary => "varbinary",
+        _ => "varchar",
+    };
+    if column.flags().contains(ColumnFlags::UNSIGNED_FLAG) {
+        format!("{} unsigned", name)
+    } else {
+        String::from(name)
+    }
+}
+
+// 将二进制协议返回的值转换为JSON，零日期等非法日期按原样输出
+fn json_value(column: &Column, value: MyValue) -> Value {
+    match value {
+        MyValue::NULL => Value::Null,
+        MyValue::Int(v) => Value::from(v),
+        MyValue::UInt(v) => Value::from(v),
+        MyValue::Float(v) => float_value(v as f64),
+        MyValue::Double(v) => float_value(v),
+        // BIT按大端无符号整数返回
+        MyValue::Bytes(v)
+            if column.column_type() == ColumnType::MYSQL_TYPE_BIT =>
+        {
+            Value::from(
+                v.iter().fold(0u64, |bits, byte| (bits << 8) | *byte as u64),
+            )
+        }
+        MyValue::Bytes(v)
+            if column.column_type() == ColumnType::MYSQL_TYPE_JSON =>
+        {
+            serde_json::from_slice(&v).unwrap_or_else(|_| {
+                Value::String(String::from_utf8_lossy(&v).into_owned())
+            })
+        }
+        MyValue::Bytes(v) if is_binary(column) => {
+            Value::String(general_purpose::STANDARD.encode(v))
+        }
+        MyValue::Bytes(v) => {
+            Value::String(String::from_utf8_lossy(&v).into_owned())
+        }
+        MyValue::Date(year, month, day, hour, minute, second, micros) => {
+            let date = format!("{:04}-{:02}-{:02}", year, month, day);
+            if matches!(
+                column.column_type(),
+                ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE
+            ) {
+                Value::String(date)
+            } else {
+                Value::String(format!(
+                    "{} {:02}:{:02}:{:02}{}",
+                    date,
+                    hour,
+                    minute,
+                    second,
+                    fraction(micros)
+                ))
+            }
+        }
+        MyValue::Time(negative, days, hours, minutes, seconds, micros) => {
+            Value::String(format!(
+                "{}{:02}:{:02}:{:02}{}",
+                if negative { "-" } else { "" },
+                days * 24 + hours as u32,
+                minutes,
+                seconds,
+                fraction(micros)
+            ))
+        }
+    }
+}
+
+// 秒的小数部分，为0时省略
+fn fraction(micros: u32) -> String {
+    if micros == 0 {
+        String::new()
+    } else {
+        format!(".{:06}", micros)
+    }
+}
@@ -0,0 +1,497 @@
+use std::{error::Error, time::Duration};
+
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use postgres::{
+    Client, Config, NoTls,
+    fallible_iterator::FallibleIterator,
+    types::{FromSql, Kind, ToSql, Type},
+};
+use serde_json::Value;
+
+use crate::{
+    biz::datasource::{
+        model::{
+            catalog::{
+                CatalogColumnVo, CatalogForeignKeyVo, CatalogIndexVo,
+                CatalogTableVo, ResultColumnVo,
+            },
+            data_source::DataSourceDetailVo,
+        },
+        service::connector::{
+            Connector, collect_foreign_keys, collect_indexes, float_value,
+            quote_ident, sample_probability,
+        },
+    },
+    error::error::AppError,
+};
+
+// 建立连接的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// search_path中没有可用schema时的默认schema
+const DEFAULT_SCHEMA: &str = "public";
+
+type DecodeError = Box<dyn Error + Sync + Send>;
+
+// 基于同步客户端，与MySQL连接一样需在 spawn_blocking 中使用。暂不支持TLS
+pub struct PostgresConnector {
+    client: Client,
+}
+
+impl PostgresConnector {
+    pub fn open(data_source: &DataSourceDetailVo) -> Result<Self, AppError> {
+        let client = Config::new()
+            .host(&data_source.db_host)
+            .port(data_source.db_port)
+            .user(&data_source.db_username)
+            .password(&data_source.db_password)
+            .dbname(&data_source.db_name)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .connect(NoTls)?;
+        Ok(Self { client })
+    }
+}
+
+impl Connector for PostgresConnector {
+    fn test(&mut self) -> Result<(), AppError> {
+        self.client.batch_execute("SELECT 1")?;
+        Ok(())
+    }
+
+    fn default_schema(&mut self) -> Result<String, AppError> {
+        let schema: Option<String> = self
+            .client
+            .query_one("SELECT current_schema()", &[])?
+            .get(0);
+        Ok(schema.unwrap_or_else(|| String::from(DEFAULT_SCHEMA)))
+    }
+
+    fn list_schemas(&mut self) -> Result<Vec<String>, AppError> {
+        let rows = self.client.query(
+            "SELECT nspname FROM pg_catalog.pg_namespace \
+             WHERE nspname <> 'information_schema' AND nspname NOT LIKE 'pg\\_%' \
+             ORDER BY nspname",
+            &[],
+        )?;
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    // 不含分区表的子分区。估算行数取自最近一次 ANALYZE，未分析过的表为空
+    fn list_tables(
+        &mut self,
+        schema: &str,
+    ) -> Result<Vec<CatalogTableVo>, AppError> {
+        let rows = self.client.query(
+            "SELECT c.relname, c.relkind::text, \
+             COALESCE(obj_description(c.oid, 'pg_class'), ''), \
+             CASE WHEN c.relkind IN ('r', 'p', 'm') AND c.reltuples >= 0 \
+             THEN c.reltuples::bigint END, \
+             CASE WHEN c.relkind IN ('r', 'm') THEN pg_table_size(c.oid) END \
+             FROM pg_catalog.pg_class c \
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = $1 AND c.relkind IN ('r', 'p', 'v', 'm', 'f') \
+             AND NOT c.relispartition ORDER BY c.relname",
+            &[&schema],
+        )?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let relkind: String = row.get(1);
+                CatalogTableVo {
+                    schema: String::from(schema),
+                    table_name: row.get(0),
+                    table_type: String::from(match relkind.as_str() {
+                        "v" => "VIEW",
+                        "m" => "MATERIALIZED VIEW",
+                        "f" => "FOREIGN TABLE",
+                        _ => "BASE TABLE",
+                    }),
+                    comment: row.get(2),
+                    engine: None,
+                    estimated_rows: row.get(3),
+                    data_size: row.get(4),
+                }
+            })
+            .collect())
+    }
+
+    // 已删除的列仍占用 attnum，列序号按剩余列重新编号
+    fn list_columns(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogColumnVo>, AppError> {
+        let rows = self.client.query(
+            "SELECT a.attname, format_type(a.atttypid, a.atttypmod), \
+             NOT a.attnotnull, pg_get_expr(d.adbin, d.adrelid), \
+             COALESCE(a.attnum = ANY(i.indkey), false), \
+             COALESCE(col_description(a.attrelid, a.attnum), ''), \
+             row_number() OVER (ORDER BY a.attnum) \
+             FROM pg_catalog.pg_attribute a \
+             JOIN pg_catalog.pg_class c ON c.oid = a.attrelid \
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+             LEFT JOIN pg_catalog.pg_index i \
+             ON i.indrelid = c.oid AND i.indisprimary \
+             LEFT JOIN pg_catalog.pg_attrdef d \
+             ON d.adrelid = a.attrelid AND d.adnum = a.attnum \
+             WHERE n.nspname = $1 AND c.relname = $2 \
+             AND a.attnum > 0 AND NOT a.attisdropped ORDER BY a.attnum",
+            &[&schema, &table_name],
+        )?;
+        Ok(rows
+            .iter()
+            .map(|row| CatalogColumnVo {
+                column_name: row.get(0),
+                data_type: row.get(1),
+                nullable: row.get(2),
+                default_value: row.get(3),
+                primary_key: row.get(4),
+                comment: row.get(5),
+                ordinal_position: row.get(6),
+            })
+            .collect())
+    }
+
+    // 索引列取 pg_get_indexdef，普通列为列名，表达式索引为表达式文本；
+    // 不含 INCLUDE 的非键列
+    fn list_indexes(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogIndexVo>, AppError> {
+        let rows = self.client.query(
+            "SELECT ic.relname, i.indisunique, i.indisprimary, \
+             pg_get_indexdef(i.indexrelid, k.k, true) \
+             FROM pg_catalog.pg_index i \
+             JOIN pg_catalog.pg_class ic ON ic.oid = i.indexrelid \
+             JOIN pg_catalog.pg_class c ON c.oid = i.indrelid \
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+             CROSS JOIN LATERAL generate_series(1, i.indnkeyatts) AS k(k) \
+             WHERE n.nspname = $1 AND c.relname = $2 \
+             ORDER BY ic.relname, k.k",
+            &[&schema, &table_name],
+        )?;
+        Ok(collect_indexes(rows.iter().map(|row| {
+            (row.get(0), row.get(1), row.get(2), row.get(3))
+        })))
+    }
+
+    fn list_foreign_keys(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogForeignKeyVo>, AppError> {
+        let rows = self.client.query(
+            "SELECT con.conname::text, a.attname::text, fn.nspname::text, \
+             fc.relname::text, fa.attname::text \
+             FROM pg_catalog.pg_constraint con \
+             JOIN pg_catalog.pg_class c ON c.oid = con.conrelid \
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+             JOIN pg_catalog.pg_class fc ON fc.oid = con.confrelid \
+             JOIN pg_catalog.pg_namespace fn ON fn.oid = fc.relnamespace \
+             CROSS JOIN LATERAL unnest(con.conkey, con.confkey) \
+             WITH ORDINALITY AS k(attnum, fattnum, ord) \
+             JOIN pg_catalog.pg_attribute a \
+             ON a.attrelid = con.conrelid AND a.attnum = k.attnum \
+             JOIN pg_catalog.pg_attribute fa \
+             ON fa.attrelid = con.confrelid AND fa.attnum = k.fattnum \
+             WHERE con.contype = 'f' AND n.nspname = $1 AND c.relname = $2 \
+             ORDER BY con.conname, k.ord",
+            &[&schema, &table_name],
+        )?;
+        Ok(collect_foreign_keys(rows.iter().map(|row| {
+            (row.get(0), row.get(1), row.get(2), row.get(3), row.get(4))
+        })))
+    }
+
+    fn set_statement_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), AppError> {
+        self.client.batch_execute(&format!(
+            "SET statement_timeout = {}",
+            timeout.as_millis()
+        ))?;
+        Ok(())
+    }
+
+    // 随机抽样按 pg_class.reltuples 估算行数，以 TABLESAMPLE BERNOULLI 逐行抽样后
+    // 再对抽中的行随机排序，避免对全表排序。视图、从未 ANALYZE 的表（reltuples 为-1）
+    // 或较小的表对全表随机排序，由查询超时兜底
+    fn preview_sql(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+        limit: usize,
+        sample: bool,
+    ) -> Result<String, AppError> {
+        let table =
+            format!("{}.{}", quote_ident(schema), quote_ident(table_name));
+        if !sample {
+            return Ok(format!("SELECT * FROM {} LIMIT {}", table, limit));
+        }
+        let rows: Option<f32> = self
+            .client
+            .query_opt(
+                "SELECT c.reltuples FROM pg_catalog.pg_class c \
+                 JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+                 WHERE n.nspname = $1 AND c.relname = $2 \
+                 AND c.relkind IN ('r', 'm')",
+                &[&schema, &table_name],
+            )?
+            .map(|row| row.get(0));
+        let sample = rows
+            .filter(|rows| *rows >= 1.0)
+            .and_then(|rows| sample_probability(limit, rows as u64))
+            .map(|probability| {
+                format!(" TABLESAMPLE BERNOULLI ({})", probability * 100.0)
+            })
+            .unwrap_or_default();
+        Ok(format!(
+            "SELECT * FROM {}{} ORDER BY random() LIMIT {}",
+            table, sample, limit
+        ))
+    }
+
+    fn stream_rows(
+        &mut self,
+        sql: &str,
+        on_row: &mut dyn FnMut(Vec<Value>) -> Result<bool, AppError>,
+    ) -> Result<Vec<ResultColumnVo>, AppError> {
+        let statement = self.client.prepare(sql)?;
+        let columns = statement
+            .columns()
+            .iter()
+            .map(|column| ResultColumnVo {
+                name: String::from(column.name()),
+                data_type: String::from(column.type_().name()),
+            })
+            .collect();
+        let mut rows = self
+            .client
+            .query_raw(&statement, std::iter::empty::<&dyn ToSql>())?;
+        while let Some(row) = rows.next()? {
+            let mut values = Vec::with_capacity(row.len());
+            for (index, column) in row.columns().iter().enumerate() {
+                let value = match row.try_get::<_, Option<RawValue>>(index)? {
+                    Some(raw) => {
+                        json_value(column.type_(), raw.0).map_err(|e| {
+                            AppError::DataSourceError(format!(
+                                "列 {} 的值无法解析: {}",
+                                column.name(),
+                                e
+                            ))
+                        })?
+                    }
+                    None => Value::Null,
+                };
+                values.push(value);
+            }
+            if !on_row(values)? {
+                break;
+            }
+        }
+        Ok(columns)
+    }
+}
+
+// 列值的二进制表示，按列类型自行解析
+struct RawValue<'a>(&'a [u8]);
+
+impl<'a> FromSql<'a> for RawValue<'a> {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, DecodeError> {
+        Ok(RawValue(raw))
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+}
+
+// 将二进制格式的值转换为JSON。几何、网络地址、区间等不支持的类型返回 null，
+// 可在SQL中转换为 text 后读取
+fn json_value(ty: &Type, raw: &[u8]) -> Result<Value, DecodeError> {
+    let value = match *ty {
+        Type::BOOL => Value::Bool(bool::from_sql(ty, raw)?),
+        Type::INT2 => Value::from(i16::from_sql(ty, raw)?),
+        Type::INT4 => Value::from(i32::from_sql(ty, raw)?),
+        Type::INT8 => Value::from(i64::from_sql(ty, raw)?),
+        Type::OID => Value::from(u32::from_sql(ty, raw)?),
+        Type::FLOAT4 => float_value(f32::from_sql(ty, raw)? as f64),
+        Type::FLOAT8 => float_value(f64::from_sql(ty, raw)?),
+        Type::NUMERIC => Value::String(numeric_string(raw)?),
+        Type::JSON | Type::JSONB => Value::from_sql(ty, raw)?,
+        Type::DATE => Value::String(
+            NaiveDate::from_sql(ty, raw)?.format("%Y-%m-%d").to_string(),
+        ),
+        Type::TIME => Value::String(
+            NaiveTime::from_sql(ty, raw)?
+                .format("%H:%M:%S%.f")
+                .to_string(),
+        ),
+        Type::TIMESTAMP => Value::String(
+            NaiveDateTime::from_sql(ty, raw)?
+                .format("%Y-%m-%d %H:%M:%S%.f")
+                .to_string(),
+        ),
+        Type::TIMESTAMPTZ => {
+            Value::String(DateTime::<Utc>::from_sql(ty, raw)?.to_rfc3339())
+        }
+        Type::UUID => Value::String(uuid_string(raw)?),
+        Type::BYTEA => Value::String(general_purpose::STANDARD.encode(raw)),
+        _ if <String as FromSql>::accepts(ty) => {
+            Value::String(String::from_sql(ty, raw)?)
+        }
+        _ => match ty.kind() {
+            // 枚举值的二进制格式即为文本
+            Kind::Enum(_) => {
+                Value::String(String::from(std::str::from_utf8(raw)?))
+            }
+            Kind::Array(member) => Value::Array(
+                Vec::<Option<RawValue>>::from_sql(ty, raw)?
+                    .into_iter()
+                    .map(|value| match value {
+                        Some(value) => json_value(member, value.0),
+                        None => Ok(Value::Null),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => Value::Null,
+        },
+    };
+    Ok(value)
+}
+
+// NUMERIC的二进制格式：位数、权重、符号、小数位数，随后为万进制的各位
+fn numeric_string(raw: &[u8]) -> Result<String, DecodeError> {
+    let read = |offset: usize| {
+        raw.get(offset..offset + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+            .ok_or("invalid numeric")
+    };
+    let ndigits = read(0)? as usize;
+    let weight = read(2)? as i16 as i64;
+    let sign = read(4)?;
+    let scale = read(6)? as usize;
+    match sign {
+        0xC000 => return Ok(String::from("NaN")),
+        0xD000 => return Ok(String::from("Infinity")),
+        0xF000 => return Ok(String::from("-Infinity")),
+        _ => {}
+    }
+    let digits = (0..ndigits)
+        .map(|index| read(8 + index * 2))
+        .collect::<Result<Vec<_>, _>>()?;
+    // 第 index 位的权重为 weight - index
+    let digit = |index: i64| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| digits.get(index).copied())
+            .unwrap_or(0)
+    };
+
+    let mut number = String::new();
+    if sign == 0x4000 {
+        number.push('-');
+    }
+    if weight < 0 {
+        number.push('0');
+    } else {
+        number.push_str(&digit(0).to_string());
+        for index in 1..=weight {
+            number.push_str(&format!("{:04}", digit(index)));
+        }
+    }
+    if scale > 0 {
+        let mut fraction = String::new();
+        let mut index = weight + 1;
+        while fraction.len() < scale {
+            fraction.push_str(&format!("{:04}", digit(index)));
+            index += 1;
+        }
+        fraction.truncate(scale);
+        number.push('.');
+        number.push_str(&fraction);
+    }
+    Ok(number)
+}
+
+fn uuid_string(raw: &[u8]) -> Result<String, DecodeError> {
+    if raw.len() != 16 {
+        return Err("invalid uuid".into());
+    }
+    let hex = raw
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 十六进制表示的字节，取自PostgreSQL的 numeric_send / uuid_send
+    fn bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn numeric(hex: &str) -> String {
+        numeric_string(&bytes(hex)).unwrap()
+    }
+
+    #[test]
+    fn numeric_zero_and_scale_padding() {
+        assert_eq!(numeric("0000000000000000"), "0");
+        assert_eq!(numeric("0000000000000002"), "0.00");
+        assert_eq!(numeric("00010000000000020064"), "100.00");
+        assert_eq!(numeric("0002000000000002000103e8"), "1.10");
+    }
+
+    #[test]
+    fn numeric_weights() {
+        assert_eq!(numeric("0003000100000003000109291a7c"), "12345.678");
+        assert_eq!(numeric("00010001000000000001"), "10000");
+        assert_eq!(numeric("0002ffff0000000700010924"), "0.0001234");
+        assert_eq!(numeric("0001fffe000000080005"), "0.00000005");
+    }
+
+    #[test]
+    fn numeric_sign() {
+        assert_eq!(numeric("000200004000000100011388"), "-1.5");
+        assert_eq!(numeric("0001ffff400000011388"), "-0.5");
+    }
+
+    #[test]
+    fn numeric_special_values() {
+        assert_eq!(numeric("00000000c0000000"), "NaN");
+        assert_eq!(numeric("00000000d0000020"), "Infinity");
+        assert_eq!(numeric("00000000f0000020"), "-Infinity");
+    }
+
+    #[test]
+    fn numeric_truncated_input() {
+        assert!(numeric_string(&bytes("000100")).is_err());
+        assert!(numeric_string(&bytes("0002000000000000")).is_err());
+    }
+
+    #[test]
+    fn uuids() {
+        assert_eq!(
+            uuid_string(&bytes("123e4567e89b12d3a456426614174000")).unwrap(),
+            "123e4567-e89b-12d3-a456-426614174000"
+        );
+        assert!(uuid_string(&bytes("123e4567e89b12d3a4564266141740")).is_err());
+    }
+}
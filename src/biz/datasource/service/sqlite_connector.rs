@@ -0,0 +1,238 @@
+use std::{path::Path, time::Duration};
+
+use base64::{Engine as _, engine::general_purpose};
+use rusqlite::{
+    Connection, OpenFlags, config::DbConfig, limits::Limit, types::ValueRef,
+};
+use serde_json::Value;
+
+use crate::{
+    biz::datasource::{
+        model::{
+            catalog::{
+                CatalogColumnVo, CatalogForeignKeyVo, CatalogIndexVo,
+                CatalogTableVo, ResultColumnVo,
+            },
+            data_source::DataSourceDetailVo,
+        },
+        service::connector::{
+            Connector, Watchdog, collect_foreign_keys, collect_indexes,
+            database_file, float_value,
+        },
+    },
+    error::error::AppError,
+};
+
+// SQLite主库的schema名
+const MAIN_SCHEMA: &str = "main";
+
+// 以只读方式打开SQLite文件，不会创建或修改文件。禁止 ATTACH，
+// 查询无法访问数据源文件以外的数据库
+pub struct SqliteConnector {
+    conn: Connection,
+    statement_timeout: Option<Duration>,
+}
+
+impl SqliteConnector {
+    pub fn open(
+        data_source: &DataSourceDetailVo,
+        file_root: &Path,
+    ) -> Result<Self, AppError> {
+        let conn = Connection::open_with_flags(
+            database_file(data_source, file_root)?,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0)?;
+        conn.set_db_config(DbConfig::SQLITE_DBCONFIG_DEFENSIVE, true)?;
+        Ok(Self {
+            conn,
+            statement_timeout: None,
+        })
+    }
+}
+
+impl Connector for SqliteConnector {
+    // 打开连接时不会读取文件内容，读取一次系统表以校验文件格式
+    fn test(&mut self) -> Result<(), AppError> {
+        self.conn.query_row(
+            "SELECT count(*) FROM sqlite_master",
+            [],
+            |row| row.get::<_, i64>(0),
+        )?;
+        Ok(())
+    }
+
+    fn default_schema(&mut self) -> Result<String, AppError> {
+        Ok(String::from(MAIN_SCHEMA))
+    }
+
+    // 主库及已附加的库，不含临时库
+    fn list_schemas(&mut self) -> Result<Vec<String>, AppError> {
+        let mut statement = self.conn.prepare(
+            "SELECT name FROM pragma_database_list \
+             WHERE name <> 'temp' ORDER BY seq",
+        )?;
+        let schemas = statement
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(schemas)
+    }
+
+    fn list_tables(
+        &mut self,
+        schema: &str,
+    ) -> Result<Vec<CatalogTableVo>, AppError> {
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT name, type FROM {}.sqlite_master \
+             WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' \
+             ORDER BY name",
+            sqlite_ident(schema)
+        ))?;
+        let tables = statement
+            .query_map([], |row| {
+                let table_type: String = row.get(1)?;
+                Ok(CatalogTableVo {
+                    schema: String::from(schema),
+                    table_name: row.get(0)?,
+                    table_type: String::from(if table_type == "view" {
+                        "VIEW"
+                    } else {
+                        "BASE TABLE"
+                    }),
+                    comment: String::new(),
+                    engine: None,
+                    estimated_rows: None,
+                    data_size: None,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(tables)
+    }
+
+    // SQLite的列类型为建表时声明的类型，未声明时为空
+    fn list_columns(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogColumnVo>, AppError> {
+        let mut statement = self.conn.prepare(
+            "SELECT name, type, \"notnull\", dflt_value, pk, cid \
+             FROM pragma_table_info(?1, ?2) ORDER BY cid",
+        )?;
+        let columns = statement
+            .query_map((table_name, schema), |row| {
+                Ok(CatalogColumnVo {
+                    column_name: row.get(0)?,
+                    data_type: row.get(1)?,
+                    nullable: !row.get::<_, bool>(2)?,
+                    default_value: row.get(3)?,
+                    primary_key: row.get::<_, i64>(4)? > 0,
+                    comment: String::new(),
+                    ordinal_position: row.get::<_, i64>(5)? + 1,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(columns)
+    }
+
+    // INTEGER PRIMARY KEY 列是rowid的别名，不产生索引，此时由列定义得到主键
+    fn list_indexes(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogIndexVo>, AppError> {
+        let mut statement = self.conn.prepare(
+            "SELECT il.name, il.\"unique\", il.origin = 'pk', \
+             COALESCE(ii.name, '') \
+             FROM pragma_index_list(?1, ?2) il \
+             JOIN pragma_index_info(il.name, ?2) ii \
+             ORDER BY il.name, ii.seqno",
+        )?;
+        let rows = statement
+            .query_map((table_name, schema), |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(collect_indexes(rows))
+    }
+
+    // SQLite的外键没有名称，以外键序号代替；未指定引用列时引用对方主键，列名为空
+    fn list_foreign_keys(
+        &mut self,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<Vec<CatalogForeignKeyVo>, AppError> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, \"from\", \"table\", COALESCE(\"to\", '') \
+             FROM pragma_foreign_key_list(?1, ?2) ORDER BY id, seq",
+        )?;
+        let rows = statement
+            .query_map((table_name, schema), |row| {
+                Ok((
+                    row.get::<_, i64>(0)?.to_string(),
+                    row.get(1)?,
+                    String::from(schema),
+                    row.get(2)?,
+                    row.get(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(collect_foreign_keys(rows))
+    }
+
+    // SQLite没有语句超时设置，执行查询时由看门狗线程中断
+    fn set_statement_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), AppError> {
+        self.statement_timeout = Some(timeout);
+        Ok(())
+    }
+
+    // SQLite按值确定类型，同一列的值可能类型不同，按每个值的实际类型转换
+    fn stream_rows(
+        &mut self,
+        sql: &str,
+        on_row: &mut dyn FnMut(Vec<Value>) -> Result<bool, AppError>,
+    ) -> Result<Vec<ResultColumnVo>, AppError> {
+        let interrupt = self.conn.get_interrupt_handle();
+        let _watchdog = Watchdog::start(self.statement_timeout, move || {
+            interrupt.interrupt()
+        });
+        let mut statement = self.conn.prepare(sql)?;
+        let columns: Vec<ResultColumnVo> = statement
+            .columns()
+            .iter()
+            .map(|column| ResultColumnVo {
+                name: String::from(column.name()),
+                data_type: String::from(column.decl_type().unwrap_or_default()),
+            })
+            .collect();
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let values = (0..columns.len())
+                .map(|index| row.get_ref(index).map(json_value))
+                .collect::<Result<_, _>>()?;
+            if !on_row(values)? {
+                break;
+            }
+        }
+        Ok(columns)
+    }
+}
+
+fn json_value(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(v) => Value::from(v),
+        ValueRef::Real(v) => float_value(v),
+        ValueRef::Text(v) => {
+            Value::String(String::from_utf8_lossy(v).into_owned())
+        }
+        ValueRef::Blob(v) => Value::String(general_purpose::STANDARD.encode(v)),
+    }
+}
+
+fn sqlite_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
@@ -1 +1,5 @@
+pub mod trans_dag_handler;
+pub mod trans_task_column_handler;
 pub mod trans_task_handler;
+pub mod trans_task_quality_handler;
+pub mod trans_task_run_handler;
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+
+use crate::{
+    app::AppState,
+    biz::transtask::model::{
+        trans_dag_run::{
+            TransDagNodeVo, TransDagRunBo, TransDagRunVo, TransDagVo,
+        },
+        trans_task_dependency::TransTaskDependencyBo,
+    },
+    common::vo::response::R,
+    middleware::extractors::CurrentUser,
+};
+
+pub async fn list_trans_task_dependency(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> R<Vec<TransDagNodeVo>> {
+    let result = state.services.trans_dag_service.list_dependencies(id).await;
+
+    match result {
+        Ok(dependencies) => R::ok_with_data(dependencies),
+        Err(e) => {
+            tracing::error!("查询任务依赖失败: {:?}", e);
+            R::error_with_message(String::from("查询失败"))
+        }
+    }
+}
+
+pub async fn save_trans_task_dependency(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(current_user): CurrentUser,
+    Path(id): Path<i64>,
+    Json(bo): Json<TransTaskDependencyBo>,
+) -> R<Vec<TransDagNodeVo>> {
+    let result = state
+        .services
+        .trans_dag_service
+        .save_dependencies(id, bo, &current_user)
+        .await;
+
+    match result {
+        Ok(dependencies) => R::ok_with_data(dependencies),
+        Err(e) => {
+            tracing::error!("保存任务依赖失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn get_trans_dag(
+    State(state): State<Arc<AppState>>,
+) -> R<TransDagVo> {
+    let result = state.services.trans_dag_service.graph().await;
+
+    match result {
+        Ok(dag) => R::ok_with_data(dag),
+        Err(e) => {
+            tracing::error!("查询依赖图失败: {:?}", e);
+            R::error_with_message(String::from("查询失败"))
+        }
+    }
+}
+
+pub async fn run_trans_dag(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(current_user): CurrentUser,
+    Json(bo): Json<TransDagRunBo>,
+) -> R<TransDagRunVo> {
+    let result = state
+        .services
+        .trans_dag_service
+        .run(bo, &current_user)
+        .await;
+
+    match result {
+        Ok(dag_run) => R::ok_with_data(dag_run),
+        Err(e) => {
+            tracing::error!("执行DAG失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use validator::Validate;
+
+use crate::{
+    app::AppState,
+    biz::transtask::{
+        model::trans_task_column::{TransTaskColumnBo, TransTaskColumnVo},
+        service::type_mapping::TypeMapping,
+    },
+    common::vo::response::R,
+    middleware::extractors::CurrentUser,
+};
+
+pub async fn list_trans_task_column(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> R<Vec<TransTaskColumnVo>> {
+    let result = state
+        .services
+        .trans_task_column_service
+        .list_by_trans_task_id(id)
+        .await;
+
+    match result {
+        Ok(columns) => R::ok_with_data(columns),
+        Err(e) => {
+            tracing::error!("查询列映射失败: {:?}", e);
+            R::error_with_message(String::from("查询失败"))
+        }
+    }
+}
+
+pub async fn save_trans_task_column(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(current_user): CurrentUser,
+    Path(id): Path<i64>,
+    Json(bos): Json<Vec<TransTaskColumnBo>>,
+) -> R<Vec<TransTaskColumnVo>> {
+    for bo in &bos {
+        if let Err(e) = bo.validate() {
+            return R::error_with_message(e.to_string());
+        }
+    }
+
+    let result = state
+        .services
+        .trans_task_column_service
+        .save(id, bos, &current_user)
+        .await;
+
+    match result {
+        Ok(columns) => R::ok_with_data(columns),
+        Err(e) => {
+            tracing::error!("保存列映射失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn generate_trans_task_column(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(current_user): CurrentUser,
+    Path(id): Path<i64>,
+) -> R<Vec<TransTaskColumnVo>> {
+    let result = state
+        .services
+        .trans_task_column_service
+        .generate(id, &current_user)
+        .await;
+
+    match result {
+        Ok(columns) => R::ok_with_data(columns),
+        Err(e) => {
+            tracing::error!("生成列映射失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn list_type_mapping(
+    State(state): State<Arc<AppState>>,
+) -> R<Vec<TypeMapping>> {
+    R::ok_with_data(
+        state
+            .services
+            .trans_task_column_service
+            .type_mappings()
+            .to_vec(),
+    )
+}
@@ -2,15 +2,25 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    response::sse::{Event, KeepAlive, Sse},
 };
+use futures::{Stream, stream};
 use validator::Validate;
 
 use crate::{
     app::AppState,
-    biz::transtask::model::trans_task::{
-        TransTaskCreateBo, TransTaskDetailVo, TransTaskListVo,
-        TransTaskUpdateBo,
+    biz::transtask::model::{
+        trans_backfill::TransTaskBackfillBo,
+        trans_plan::TransPlanVo,
+        trans_progress::TransProgressVo,
+        trans_reconcile::{TransTaskReconcileBo, TransTaskReconcileVo},
+        trans_task::{
+            TransTaskBulkCreateBo, TransTaskBulkCreateVo, TransTaskCreateBo,
+            TransTaskDetailVo, TransTaskListVo, TransTaskNextRunsBo,
+            TransTaskUpdateBo,
+        },
+        trans_task_run::TransTaskRunListVo,
     },
     common::vo::response::R,
     middleware::extractors::CurrentUser,
@@ -52,6 +62,19 @@ pub async fn create_trans_task(
     if let Err(e) = &bo.validate() {
         return R::error_with_message(e.to_string());
     }
+    if let Err(e) = state
+        .services
+        .trans_task_service
+        .validate_source(
+            bo.data_source_id.unwrap(),
+            &bo.table_name,
+            bo.source_filter.as_deref(),
+            bo.source_sql.as_deref(),
+        )
+        .await
+    {
+        return R::error_with_message(e.to_string());
+    }
 
     let result = state
         .services
@@ -66,6 +89,30 @@ pub async fn create_trans_task(
     }
 }
 
+pub async fn bulk_create_trans_task(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(current_user): CurrentUser,
+    Json(bo): Json<TransTaskBulkCreateBo>,
+) -> R<TransTaskBulkCreateVo> {
+    if let Err(e) = &bo.validate() {
+        return R::error_with_message(e.to_string());
+    }
+
+    let result = state
+        .services
+        .trans_task_service
+        .bulk_create(bo, &current_user)
+        .await;
+
+    match result {
+        Ok(vo) => R::ok_with_data(vo),
+        Err(e) => {
+            tracing::error!("批量创建传输任务失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
 pub async fn update_trans_task(
     State(state): State<Arc<AppState>>,
     CurrentUser(current_user): CurrentUser,
@@ -74,6 +121,19 @@ pub async fn update_trans_task(
     if let Err(e) = &bo.validate() {
         return R::error_with_message(e.to_string());
     }
+    if let Err(e) = state
+        .services
+        .trans_task_service
+        .validate_source(
+            bo.data_source_id.unwrap(),
+            &bo.table_name,
+            bo.source_filter.as_deref(),
+            bo.source_sql.as_deref(),
+        )
+        .await
+    {
+        return R::error_with_message(e.to_string());
+    }
 
     let result = state
         .services
@@ -122,3 +182,165 @@ pub async fn true_delete_trans_task(
         R::error_with_message(String::from("删除失败"))
     }
 }
+
+pub async fn run_trans_task(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(current_user): CurrentUser,
+    Path(id): Path<i64>,
+) -> R<TransTaskRunListVo> {
+    let result = state
+        .services
+        .trans_task_service
+        .run_by_id(id, &current_user)
+        .await;
+
+    match result {
+        Ok(trans_task_run) => R::ok_with_data(trans_task_run),
+        Err(e) => {
+            tracing::error!("执行传输任务失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn backfill_trans_task(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(current_user): CurrentUser,
+    Path(id): Path<i64>,
+    Json(bo): Json<TransTaskBackfillBo>,
+) -> R<TransTaskRunListVo> {
+    if let Err(e) = &bo.validate() {
+        return R::error_with_message(e.to_string());
+    }
+
+    let result = state
+        .services
+        .trans_task_service
+        .backfill(id, bo, current_user.get_user_id())
+        .await;
+
+    match result {
+        Ok(trans_task_run) => R::ok_with_data(trans_task_run),
+        Err(e) => {
+            tracing::error!("回填传输任务失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn next_runs_trans_task(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Query(bo): Query<TransTaskNextRunsBo>,
+) -> R<Vec<String>> {
+    let result = state
+        .services
+        .trans_task_service
+        .next_runs(id, bo.count())
+        .await;
+
+    match result {
+        Ok(next_runs) => R::ok_with_data(next_runs),
+        Err(e) => {
+            tracing::error!("预览触发时间失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+// 以SSE推送任务的执行进度，执行结束时推送 finished 事件后关闭
+pub async fn progress_trans_task(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let receiver = state.services.trans_task_service.subscribe_progress(id);
+    let stream = stream::unfold(receiver, |mut receiver| async move {
+        let progress: TransProgressVo = receiver.recv().await?;
+        let event = Event::default()
+            .event(if progress.is_finished() {
+                "finished"
+            } else {
+                "progress"
+            })
+            .json_data(&progress);
+        Some((event, receiver))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+pub async fn cancel_trans_task(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> R<()> {
+    match state.services.trans_task_service.cancel(id).await {
+        Ok(_) => R::ok(),
+        Err(e) => {
+            tracing::error!("取消传输任务失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn pause_trans_task(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> R<()> {
+    match state.services.trans_task_service.pause(id).await {
+        Ok(_) => R::ok(),
+        Err(e) => {
+            tracing::error!("暂停传输任务失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn resume_trans_task(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(current_user): CurrentUser,
+    Path(id): Path<i64>,
+) -> R<TransTaskRunListVo> {
+    let result = state
+        .services
+        .trans_task_service
+        .resume(id, &current_user)
+        .await;
+
+    match result {
+        Ok(trans_task_run) => R::ok_with_data(trans_task_run),
+        Err(e) => {
+            tracing::error!("恢复传输任务失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn plan_trans_task(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> R<TransPlanVo> {
+    let result = state.services.trans_task_service.plan(id).await;
+
+    match result {
+        Ok(plan) => R::ok_with_data(plan),
+        Err(e) => {
+            tracing::error!("预览传输任务失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn reconcile_trans_task(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Json(bo): Json<TransTaskReconcileBo>,
+) -> R<TransTaskReconcileVo> {
+    let result = state.services.trans_task_service.reconcile(id, bo).await;
+
+    match result {
+        Ok(report) => R::ok_with_data(report),
+        Err(e) => {
+            tracing::error!("传输任务对账失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use rbatis::plugin::page::Page;
+use validator::Validate;
+
+use crate::{
+    app::AppState,
+    biz::transtask::model::{
+        trans_task_quality_result::TransTaskQualityResultVo,
+        trans_task_quality_rule::{
+            TransTaskQualityRuleBo, TransTaskQualityRuleVo,
+        },
+    },
+    common::{model::page::PageBo, vo::response::R},
+    middleware::extractors::CurrentUser,
+};
+
+pub async fn list_trans_task_quality_rule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> R<Vec<TransTaskQualityRuleVo>> {
+    let result = state
+        .services
+        .trans_task_quality_service
+        .list_rules_by_trans_task_id(id)
+        .await;
+
+    match result {
+        Ok(rules) => R::ok_with_data(rules),
+        Err(e) => {
+            tracing::error!("查询质量规则失败: {:?}", e);
+            R::error_with_message(String::from("查询失败"))
+        }
+    }
+}
+
+pub async fn save_trans_task_quality_rule(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(current_user): CurrentUser,
+    Path(id): Path<i64>,
+    Json(bos): Json<Vec<TransTaskQualityRuleBo>>,
+) -> R<Vec<TransTaskQualityRuleVo>> {
+    for bo in &bos {
+        if let Err(e) = bo.validate() {
+            return R::error_with_message(e.to_string());
+        }
+    }
+
+    let result = state
+        .services
+        .trans_task_quality_service
+        .save_rules(id, bos, &current_user)
+        .await;
+
+    match result {
+        Ok(rules) => R::ok_with_data(rules),
+        Err(e) => {
+            tracing::error!("保存质量规则失败: {:?}", e);
+            R::error_with_message(e.to_string())
+        }
+    }
+}
+
+pub async fn page_trans_task_quality_result(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Query(page): Query<PageBo>,
+) -> R<Page<TransTaskQualityResultVo>> {
+    let result = state
+        .services
+        .trans_task_quality_service
+        .page_results_by_trans_task_id(id, &page)
+        .await;
+
+    R::ok_with_data(result)
+}
+
+pub async fn list_trans_task_quality_result_by_run(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> R<Vec<TransTaskQualityResultVo>> {
+    let result = state
+        .services
+        .trans_task_quality_service
+        .list_results_by_trans_task_run_id(id)
+        .await;
+
+    match result {
+        Ok(results) => R::ok_with_data(results),
+        Err(e) => {
+            tracing::error!("查询质量检查结果失败: {:?}", e);
+            R::error_with_message(String::from("查询失败"))
+        }
+    }
+}
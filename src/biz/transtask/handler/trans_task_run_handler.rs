@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use rbatis::plugin::page::Page;
+
+use crate::{
+    app::AppState,
+    biz::transtask::model::trans_task_run::TransTaskRunListVo,
+    common::{model::page::PageBo, vo::response::R},
+};
+
+pub async fn page_trans_task_run(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Query(page): Query<PageBo>,
+) -> R<Page<TransTaskRunListVo>> {
+    let result = state
+        .services
+        .trans_task_run_service
+        .page_by_trans_task_id(id, &page)
+        .await;
+
+    R::ok_with_data(result)
+}
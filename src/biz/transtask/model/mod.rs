@@ -1 +1,11 @@
+pub mod trans_backfill;
+pub mod trans_dag_run;
+pub mod trans_plan;
+pub mod trans_progress;
+pub mod trans_reconcile;
 pub mod trans_task;
+pub mod trans_task_column;
+pub mod trans_task_dependency;
+pub mod trans_task_quality_result;
+pub mod trans_task_quality_rule;
+pub mod trans_task_run;
@@ -0,0 +1,87 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+// 单次回填最多拆分的分片数
+const MAX_SLICES: usize = 1_000;
+
+// 按水位列回填的区间 [from, to)，按 slice_days 天拆分为分片依次执行，默认每天一片。
+// 时间为 YYYY-MM-DD 或 YYYY-MM-DD HH:MM:SS 格式，与源端水位列同为本地时间
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_backfill_bo"))]
+pub struct TransTaskBackfillBo {
+    pub from: String,
+    pub to: String,
+    pub slice_days: Option<i64>,
+}
+
+impl TransTaskBackfillBo {
+    // 整个回填区间
+    pub fn window(&self) -> Result<BackfillWindow, String> {
+        let start = parse_time(&self.from)?;
+        let end = parse_time(&self.to)?;
+        if start >= end {
+            return Err(String::from("from must be earlier than to"));
+        }
+        Ok(BackfillWindow { start, end })
+    }
+
+    // 按分片长度拆分的各分片，最后一片截止于区间终点
+    pub fn slices(&self) -> Result<Vec<BackfillWindow>, String> {
+        let window = self.window()?;
+        let slice_days = self.slice_days.unwrap_or(1);
+        if slice_days < 1 {
+            return Err(String::from("slice_days must be at least 1"));
+        }
+        let step = Duration::days(slice_days);
+        let mut slices = Vec::new();
+        let mut start = window.start;
+        while start < window.end {
+            if slices.len() >= MAX_SLICES {
+                return Err(format!(
+                    "backfill cannot be split into more than {} slices",
+                    MAX_SLICES
+                ));
+            }
+            let end = (start + step).min(window.end);
+            slices.push(BackfillWindow { start, end });
+            start = end;
+        }
+        Ok(slices)
+    }
+}
+
+// 水位列区间，包含起点、不包含终点
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackfillWindow {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl BackfillWindow {
+    pub fn start_string(&self) -> String {
+        self.start.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+
+    pub fn end_string(&self) -> String {
+        self.end.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+}
+
+fn parse_time(value: &str) -> Result<NaiveDateTime, String> {
+    let value = value.trim();
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.and_time(Default::default()))
+        })
+        .map_err(|_| format!("invalid backfill time: {}", value))
+}
+
+fn validate_backfill_bo(
+    bo: &TransTaskBackfillBo,
+) -> Result<(), ValidationError> {
+    bo.slices()
+        .map_err(|e| ValidationError::new("backfill").with_message(e.into()))?;
+    Ok(())
+}
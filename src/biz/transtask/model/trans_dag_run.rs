@@ -0,0 +1,177 @@
+use rbdc::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::common::model::entity::BaseEntity;
+
+// DAG执行状态
+pub const DAG_RUN_STATUS_RUNNING: &str = "RUNNING";
+pub const DAG_RUN_STATUS_SUCCESS: &str = "SUCCESS";
+// 至少一个任务失败，其下游任务被跳过
+pub const DAG_RUN_STATUS_FAILED: &str = "FAILED";
+
+// DAG中单个任务的执行状态
+pub const DAG_NODE_STATUS_PENDING: &str = "PENDING";
+pub const DAG_NODE_STATUS_RUNNING: &str = "RUNNING";
+pub const DAG_NODE_STATUS_SUCCESS: &str = "SUCCESS";
+pub const DAG_NODE_STATUS_FAILED: &str = "FAILED";
+// 上游任务失败，未执行
+pub const DAG_NODE_STATUS_SKIPPED: &str = "SKIPPED";
+
+// 一次DAG执行
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransDagRun {
+    pub trans_dag_run_id: Option<i64>,
+    pub status: String,
+    pub task_count: i32,
+    pub start_time: DateTime,
+    pub end_time: Option<DateTime>,
+    pub duration_ms: i64,
+    #[serde(flatten)]
+    pub base_entity: BaseEntity,
+}
+
+impl TransDagRun {
+    pub fn start(task_count: usize, operator_id: i64) -> Self {
+        Self {
+            trans_dag_run_id: None,
+            status: String::from(DAG_RUN_STATUS_RUNNING),
+            task_count: task_count as i32,
+            start_time: DateTime::now(),
+            end_time: None,
+            duration_ms: 0,
+            base_entity: BaseEntity::new(operator_id),
+        }
+    }
+
+    pub fn finish(&mut self, status: &str) {
+        let end_time = DateTime::now();
+        self.duration_ms = end_time.unix_timestamp_millis()
+            - self.start_time.unix_timestamp_millis();
+        self.status = String::from(status);
+        self.end_time = Some(end_time);
+        self.base_entity.update(self.base_entity.created_by);
+    }
+
+    pub fn to_vo(&self, nodes: Vec<TransDagRunNodeVo>) -> TransDagRunVo {
+        TransDagRunVo {
+            trans_dag_run_id: self.trans_dag_run_id.unwrap(),
+            status: self.status.clone(),
+            task_count: self.task_count,
+            start_time: self.start_time.clone(),
+            end_time: self.end_time.clone(),
+            duration_ms: self.duration_ms,
+            nodes,
+        }
+    }
+}
+
+// DAG执行中的单个任务
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransDagRunNode {
+    pub trans_dag_run_node_id: Option<i64>,
+    pub trans_dag_run_id: i64,
+    pub trans_task_id: i64,
+    pub status: String,
+    // 对应的任务执行记录，未执行时为空
+    pub trans_task_run_id: Option<i64>,
+    pub error_message: Option<String>,
+    pub start_time: Option<DateTime>,
+    pub end_time: Option<DateTime>,
+    #[serde(flatten)]
+    pub base_entity: BaseEntity,
+}
+
+impl TransDagRunNode {
+    pub fn pending(
+        trans_dag_run_id: i64,
+        trans_task_id: i64,
+        operator_id: i64,
+    ) -> Self {
+        Self {
+            trans_dag_run_node_id: None,
+            trans_dag_run_id,
+            trans_task_id,
+            status: String::from(DAG_NODE_STATUS_PENDING),
+            trans_task_run_id: None,
+            error_message: None,
+            start_time: None,
+            end_time: None,
+            base_entity: BaseEntity::new(operator_id),
+        }
+    }
+
+    pub fn start(&mut self) {
+        self.status = String::from(DAG_NODE_STATUS_RUNNING);
+        self.start_time = Some(DateTime::now());
+        self.base_entity.update(self.base_entity.created_by);
+    }
+
+    pub fn finish(&mut self, status: &str, error_message: Option<String>) {
+        self.status = String::from(status);
+        self.error_message = error_message;
+        self.end_time = Some(DateTime::now());
+        self.base_entity.update(self.base_entity.created_by);
+    }
+
+    pub fn to_vo(&self) -> TransDagRunNodeVo {
+        TransDagRunNodeVo {
+            trans_task_id: self.trans_task_id,
+            status: self.status.clone(),
+            trans_task_run_id: self.trans_task_run_id,
+            error_message: self.error_message.clone(),
+            start_time: self.start_time.clone(),
+            end_time: self.end_time.clone(),
+        }
+    }
+}
+
+// 发起DAG执行：指定任务时执行这些任务及其全部上游任务，否则执行全部任务
+#[derive(Clone, Debug, Deserialize)]
+pub struct TransDagRunBo {
+    pub trans_task_ids: Option<Vec<i64>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransDagRunVo {
+    pub trans_dag_run_id: i64,
+    pub status: String,
+    pub task_count: i32,
+    pub start_time: DateTime,
+    pub end_time: Option<DateTime>,
+    pub duration_ms: i64,
+    pub nodes: Vec<TransDagRunNodeVo>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransDagRunNodeVo {
+    pub trans_task_id: i64,
+    pub status: String,
+    pub trans_task_run_id: Option<i64>,
+    pub error_message: Option<String>,
+    pub start_time: Option<DateTime>,
+    pub end_time: Option<DateTime>,
+}
+
+// 依赖图中的任务
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransDagNodeVo {
+    pub trans_task_id: i64,
+    pub table_name: String,
+    pub table_comment: String,
+    pub run_state: String,
+}
+
+// 依赖边，由上游任务指向下游任务
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransDagEdgeVo {
+    pub upstream_task_id: i64,
+    pub trans_task_id: i64,
+}
+
+// 依赖图及最近一次DAG执行的状态
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransDagVo {
+    pub nodes: Vec<TransDagNodeVo>,
+    pub edges: Vec<TransDagEdgeVo>,
+    pub latest_run: Option<TransDagRunVo>,
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+// 预览中的一列：源列与映射后的目标列
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransPlanColumnVo {
+    pub source_name: String,
+    pub source_type: String,
+    pub target_name: String,
+    pub target_type: String,
+    pub default_value: Option<String>,
+}
+
+// 并行读取的主键区间，下界包含、上界不包含，为空表示不限
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransPlanChunkVo {
+    pub lower: Option<String>,
+    pub upper: Option<String>,
+}
+
+// 传输预览：按任务当前配置解析出的执行方式，不读取也不写入数据
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransPlanVo {
+    pub trans_task_id: i64,
+    pub sync_mode: String,
+    pub load_strategy: String,
+    // 执行方式的说明
+    pub strategy: String,
+    // 源端抽取语句及按顺序绑定的参数
+    pub source_sql: String,
+    pub source_params: Vec<String>,
+    pub estimated_rows: Option<u64>,
+    pub target_table: String,
+    pub target_exists: bool,
+    // 将在DuckDB中执行的建表语句
+    pub ddl: Vec<String>,
+    pub columns: Vec<TransPlanColumnVo>,
+    pub primary_key: Vec<String>,
+    pub parallelism: usize,
+    pub chunks: Vec<TransPlanChunkVo>,
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use crate::biz::transtask::model::trans_task_run::{
+    RUN_STATUS_RUNNING, TransTaskRun,
+};
+
+// 传输进度事件，执行中周期性推送，结束时推送一次最终状态
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransProgressVo {
+    pub trans_task_id: i64,
+    pub trans_task_run_id: i64,
+    pub status: String,
+    pub rows_read: u64,
+    pub rows_written: u64,
+    // 已写入DuckDB的批次数
+    pub batches: u64,
+    pub elapsed_ms: u64,
+    pub rows_per_sec: f64,
+    // 源表待传输行数的估算值，无法估算时为空
+    pub total_rows: Option<u64>,
+    pub eta_secs: Option<u64>,
+    pub error_message: Option<String>,
+}
+
+impl TransProgressVo {
+    // 根据已结束的执行记录生成最终事件
+    pub fn finished(run: &TransTaskRun, batches: u64) -> Self {
+        let elapsed_ms = run.duration_ms.max(0) as u64;
+        Self {
+            trans_task_id: run.trans_task_id,
+            trans_task_run_id: run.trans_task_run_id.unwrap_or_default(),
+            status: run.status.clone(),
+            rows_read: run.rows_read as u64,
+            rows_written: run.rows_written as u64,
+            batches,
+            elapsed_ms,
+            rows_per_sec: rows_per_sec(run.rows_written as u64, elapsed_ms),
+            total_rows: None,
+            eta_secs: Some(0),
+            error_message: run.error_message.clone(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.status != RUN_STATUS_RUNNING
+    }
+}
+
+pub fn rows_per_sec(rows: u64, elapsed_ms: u64) -> f64 {
+    if elapsed_ms == 0 {
+        0.0
+    } else {
+        rows as f64 * 1000.0 / elapsed_ms as f64
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+// 每个对账区间默认的源端行数
+const DEFAULT_CHUNK_ROWS: u64 = 100_000;
+
+// 对账参数
+#[derive(Clone, Debug, Deserialize)]
+pub struct TransTaskReconcileBo {
+    // 是否重新复制不一致的区间
+    #[serde(default)]
+    pub repair: bool,
+    pub chunk_rows: Option<u64>,
+}
+
+impl TransTaskReconcileBo {
+    // 默认每区间10万行，范围1千至1千万
+    pub fn chunk_rows(&self) -> u64 {
+        self.chunk_rows
+            .unwrap_or(DEFAULT_CHUNK_ROWS)
+            .clamp(1_000, 10_000_000)
+    }
+}
+
+// 单个主键区间的对账结果，下界包含、上界不包含，为空表示不限
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReconcileRangeVo {
+    pub lower: Option<String>,
+    pub upper: Option<String>,
+    pub source_rows: u64,
+    pub target_rows: u64,
+    pub source_checksum: String,
+    pub target_checksum: String,
+    // 已按源端数据重新复制
+    pub repaired: bool,
+}
+
+// 源端与目标表的对账报告，仅列出不一致的区间
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskReconcileVo {
+    pub trans_task_id: i64,
+    pub source_rows: u64,
+    pub target_rows: u64,
+    pub chunks: usize,
+    pub matched: bool,
+    pub mismatched_ranges: Vec<ReconcileRangeVo>,
+    pub repaired_rows: u64,
+    pub elapsed_ms: u64,
+}
@@ -1,8 +1,35 @@
+use std::time::Duration;
+
 use rbdc::DateTime;
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    common::model::entity::BaseEntity,
+    sys::user::model::user::User,
+    util::{cron_util::CronSchedule, path_util},
+};
+
+// 同步模式：全量覆盖、全量追加、按水位列增量追加、读取binlog变更（CDC）
+pub const SYNC_MODE_FULL: &str = "FULL";
+pub const SYNC_MODE_APPEND: &str = "APPEND";
+pub const SYNC_MODE_INCREMENTAL: &str = "INCREMENTAL";
+pub const SYNC_MODE_CDC: &str = "CDC";
+
+// 任务当前执行状态
+pub const RUN_STATE_IDLE: &str = "IDLE";
+pub const RUN_STATE_RUNNING: &str = "RUNNING";
+pub const RUN_STATE_PAUSED: &str = "PAUSED";
+pub const RUN_STATE_CANCELLED: &str = "CANCELLED";
 
-use crate::{common::model::entity::BaseEntity, sys::user::model::user::User};
+// 加载策略：直接追加写入、按源表主键合并（存在则替换，不存在则插入）
+pub const LOAD_STRATEGY_APPEND: &str = "APPEND";
+pub const LOAD_STRATEGY_MERGE: &str = "MERGE";
+
+// Parquet导出方式：不导出、每次导出整张目标表、每次导出本次写入的数据
+pub const PARQUET_EXPORT_NONE: &str = "NONE";
+pub const PARQUET_EXPORT_TABLE: &str = "TABLE";
+pub const PARQUET_EXPORT_DELTA: &str = "DELTA";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TransTask {
@@ -11,8 +38,41 @@ pub struct TransTask {
     pub table_name: String,
     pub table_comment: String,
     pub remark: String,
-    pub row_count: u16,
+    pub row_count: i64,
     pub last_trans_time: DateTime,
+    pub sync_mode: String,
+    pub watermark_column: Option<String>,
+    pub watermark_value: Option<String>,
+    // CDC模式已应用到的binlog位置，源端开启GTID时同时记录已执行的GTID集合
+    pub binlog_file: Option<String>,
+    pub binlog_position: Option<i64>,
+    pub gtid_set: Option<String>,
+    pub load_strategy: String,
+    pub propagate_deletes: bool,
+    // 按主键区间并行读取源表的线程数，1为单线程
+    pub parallelism: i32,
+    // 抽取来源：源表上的过滤条件（不含WHERE）或完整的自定义SELECT，二者只能配置其一，
+    // 可使用 :last_watermark、:run_date 命名参数
+    pub source_filter: Option<String>,
+    pub source_sql: Option<String>,
+    // Parquet导出方式、Hive风格分区列、压缩算法（snappy/zstd）与行组大小
+    pub parquet_export: String,
+    pub parquet_partition_by: Option<String>,
+    pub parquet_compression: String,
+    pub parquet_row_group_size: i64,
+    pub schedule_cron: Option<String>,
+    pub schedule_timezone: Option<String>,
+    pub last_fire_time: Option<DateTime>,
+    // 最近一次回写结果的分布式锁fencing token
+    pub fence_token: i64,
+    pub run_state: String,
+    // 重试策略：最多尝试次数（含首次）、首次重试等待与最大等待毫秒数
+    pub retry_max_attempts: i32,
+    pub retry_base_delay_ms: i64,
+    pub retry_max_delay_ms: i64,
+    // 最近一次执行的尝试次数与最后一次失败的错误信息
+    pub last_attempt_count: i32,
+    pub last_error: Option<String>,
     #[serde(flatten)]
     pub base_entity: BaseEntity,
 }
@@ -27,19 +87,96 @@ impl TransTask {
             remark: bo.remark,
             row_count: 0,
             last_trans_time: DateTime::now(),
+            sync_mode: bo.sync_mode,
+            watermark_column: bo.watermark_column,
+            watermark_value: None,
+            binlog_file: None,
+            binlog_position: None,
+            gtid_set: None,
+            load_strategy: bo.load_strategy,
+            propagate_deletes: bo.propagate_deletes,
+            parallelism: bo.parallelism,
+            source_filter: non_empty(bo.source_filter),
+            source_sql: non_empty(bo.source_sql),
+            parquet_export: bo.parquet_export,
+            parquet_partition_by: non_empty(bo.parquet_partition_by),
+            parquet_compression: bo.parquet_compression,
+            parquet_row_group_size: bo.parquet_row_group_size,
+            schedule_cron: bo.schedule_cron,
+            schedule_timezone: bo.schedule_timezone,
+            last_fire_time: None,
+            fence_token: 0,
+            run_state: String::from(RUN_STATE_IDLE),
+            retry_max_attempts: bo.retry_max_attempts,
+            retry_base_delay_ms: bo.retry_base_delay_ms,
+            retry_max_delay_ms: bo.retry_max_delay_ms,
+            last_attempt_count: 0,
+            last_error: None,
             base_entity: BaseEntity::new(user.get_user_id()),
         }
     }
 
     pub fn from_update_bo(self: &mut Self, bo: TransTaskUpdateBo, user: &User) {
+        // 源表或同步模式变更后原binlog位置失效，下次CDC执行重新快照
+        if self.data_source_id != bo.data_source_id.unwrap()
+            || self.table_name != bo.table_name
+            || self.sync_mode != bo.sync_mode
+        {
+            self.binlog_file = None;
+            self.binlog_position = None;
+            self.gtid_set = None;
+        }
         self.trans_task_id = bo.trans_task_id;
         self.data_source_id = bo.data_source_id.unwrap();
         self.table_name = bo.table_name;
         self.table_comment = bo.table_comment;
         self.remark = bo.remark;
+        // 水位列变更后原高水位失效，下次增量从头开始
+        if self.watermark_column != bo.watermark_column {
+            self.watermark_value = None;
+        }
+        self.sync_mode = bo.sync_mode;
+        self.watermark_column = bo.watermark_column;
+        self.load_strategy = bo.load_strategy;
+        self.propagate_deletes = bo.propagate_deletes;
+        self.parallelism = bo.parallelism;
+        self.source_filter = non_empty(bo.source_filter);
+        self.source_sql = non_empty(bo.source_sql);
+        self.parquet_export = bo.parquet_export;
+        self.parquet_partition_by = non_empty(bo.parquet_partition_by);
+        self.parquet_compression = bo.parquet_compression;
+        self.parquet_row_group_size = bo.parquet_row_group_size;
+        // 调度表达式变更后从当前时间重新开始计算触发
+        if self.schedule_cron != bo.schedule_cron
+            || self.schedule_timezone != bo.schedule_timezone
+        {
+            self.last_fire_time = None;
+        }
+        self.schedule_cron = bo.schedule_cron;
+        self.schedule_timezone = bo.schedule_timezone;
+        self.retry_max_attempts = bo.retry_max_attempts;
+        self.retry_base_delay_ms = bo.retry_base_delay_ms;
+        self.retry_max_delay_ms = bo.retry_max_delay_ms;
         self.base_entity.update(user.get_user_id());
     }
 
+    // 第attempt次失败后的重试等待：按2的幂次增长，不超过最大等待，
+    // 并在后一半区间内随机抖动，避免多个任务同时重试
+    pub fn retry_delay(&self, attempt: i32) -> Duration {
+        let delay = backoff_delay_ms(
+            self.retry_base_delay_ms,
+            self.retry_max_delay_ms,
+            attempt,
+        );
+        let half = delay / 2;
+        Duration::from_millis(half + rand::random_range(0..=half))
+    }
+
+    // 解析任务的调度表达式，未配置调度时返回None
+    pub fn cron_schedule(&self) -> Result<Option<CronSchedule>, String> {
+        parse_schedule(&self.schedule_cron, &self.schedule_timezone)
+    }
+
     pub fn to_list_vo(&self) -> TransTaskListVo {
         TransTaskListVo {
             trans_task_id: self.trans_task_id.unwrap(),
@@ -49,6 +186,30 @@ impl TransTask {
             remark: self.remark.clone(),
             row_count: self.row_count,
             last_trans_time: self.last_trans_time.clone(),
+            sync_mode: self.sync_mode.clone(),
+            watermark_column: self.watermark_column.clone(),
+            watermark_value: self.watermark_value.clone(),
+            binlog_file: self.binlog_file.clone(),
+            binlog_position: self.binlog_position,
+            gtid_set: self.gtid_set.clone(),
+            load_strategy: self.load_strategy.clone(),
+            propagate_deletes: self.propagate_deletes,
+            parallelism: self.parallelism,
+            source_filter: self.source_filter.clone(),
+            source_sql: self.source_sql.clone(),
+            parquet_export: self.parquet_export.clone(),
+            parquet_partition_by: self.parquet_partition_by.clone(),
+            parquet_compression: self.parquet_compression.clone(),
+            parquet_row_group_size: self.parquet_row_group_size,
+            schedule_cron: self.schedule_cron.clone(),
+            schedule_timezone: self.schedule_timezone.clone(),
+            last_fire_time: self.last_fire_time.clone(),
+            run_state: self.run_state.clone(),
+            retry_max_attempts: self.retry_max_attempts,
+            retry_base_delay_ms: self.retry_base_delay_ms,
+            retry_max_delay_ms: self.retry_max_delay_ms,
+            last_attempt_count: self.last_attempt_count,
+            last_error: self.last_error.clone(),
             base_entity: self.base_entity.clone(),
         }
     }
@@ -62,33 +223,171 @@ impl TransTask {
             remark: self.remark.clone(),
             row_count: self.row_count,
             last_trans_time: self.last_trans_time.clone(),
+            sync_mode: self.sync_mode.clone(),
+            watermark_column: self.watermark_column.clone(),
+            watermark_value: self.watermark_value.clone(),
+            binlog_file: self.binlog_file.clone(),
+            binlog_position: self.binlog_position,
+            gtid_set: self.gtid_set.clone(),
+            load_strategy: self.load_strategy.clone(),
+            propagate_deletes: self.propagate_deletes,
+            parallelism: self.parallelism,
+            source_filter: self.source_filter.clone(),
+            source_sql: self.source_sql.clone(),
+            parquet_export: self.parquet_export.clone(),
+            parquet_partition_by: self.parquet_partition_by.clone(),
+            parquet_compression: self.parquet_compression.clone(),
+            parquet_row_group_size: self.parquet_row_group_size,
+            schedule_cron: self.schedule_cron.clone(),
+            schedule_timezone: self.schedule_timezone.clone(),
+            last_fire_time: self.last_fire_time.clone(),
+            run_state: self.run_state.clone(),
+            retry_max_attempts: self.retry_max_attempts,
+            retry_base_delay_ms: self.retry_base_delay_ms,
+            retry_max_delay_ms: self.retry_max_delay_ms,
+            last_attempt_count: self.last_attempt_count,
+            last_error: self.last_error.clone(),
             base_entity: self.base_entity.clone(),
         }
     }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_create_bo"))]
 pub struct TransTaskCreateBo {
     #[validate(required)]
     pub data_source_id: Option<i64>,
-    #[validate(length(min = 1, message = "table_name cannot be empty"))]
+    #[validate(
+        length(min = 1, message = "table_name cannot be empty"),
+        custom(function = "validate_table_name")
+    )]
     pub table_name: String,
     #[validate(length(min = 1, message = "table_comment cannot be empty"))]
     pub table_comment: String,
     pub remark: String,
+    #[serde(default = "default_sync_mode")]
+    #[validate(custom(function = "validate_sync_mode"))]
+    pub sync_mode: String,
+    pub watermark_column: Option<String>,
+    #[serde(default = "default_load_strategy")]
+    #[validate(custom(function = "validate_load_strategy"))]
+    pub load_strategy: String,
+    #[serde(default)]
+    pub propagate_deletes: bool,
+    #[serde(default = "default_parallelism")]
+    #[validate(range(
+        min = 1,
+        max = 64,
+        message = "parallelism must be between 1 and 64"
+    ))]
+    pub parallelism: i32,
+    pub source_filter: Option<String>,
+    pub source_sql: Option<String>,
+    #[serde(default = "default_parquet_export")]
+    #[validate(custom(function = "validate_parquet_export"))]
+    pub parquet_export: String,
+    pub parquet_partition_by: Option<String>,
+    #[serde(default = "default_parquet_compression")]
+    #[validate(custom(function = "validate_parquet_compression"))]
+    pub parquet_compression: String,
+    #[serde(default = "default_parquet_row_group_size")]
+    #[validate(range(
+        min = 1024,
+        max = 10_000_000,
+        message = "parquet_row_group_size must be between 1024 and 10000000"
+    ))]
+    pub parquet_row_group_size: i64,
+    pub schedule_cron: Option<String>,
+    pub schedule_timezone: Option<String>,
+    #[serde(default = "default_retry_max_attempts")]
+    #[validate(range(
+        min = 1,
+        max = 10,
+        message = "retry_max_attempts must be between 1 and 10"
+    ))]
+    pub retry_max_attempts: i32,
+    #[serde(default = "default_retry_base_delay_ms")]
+    #[validate(range(
+        min = 0,
+        message = "retry_base_delay_ms cannot be negative"
+    ))]
+    pub retry_base_delay_ms: i64,
+    #[serde(default = "default_retry_max_delay_ms")]
+    #[validate(range(
+        min = 0,
+        message = "retry_max_delay_ms cannot be negative"
+    ))]
+    pub retry_max_delay_ms: i64,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_update_bo"))]
 pub struct TransTaskUpdateBo {
     #[validate(required)]
     pub trans_task_id: Option<i64>,
     #[validate(required)]
     pub data_source_id: Option<i64>,
-    #[validate(length(min = 1, message = "table_name cannot be empty"))]
+    #[validate(
+        length(min = 1, message = "table_name cannot be empty"),
+        custom(function = "validate_table_name")
+    )]
     pub table_name: String,
     #[validate(length(min = 1, message = "table_comment cannot be empty"))]
     pub table_comment: String,
     pub remark: String,
+    #[serde(default = "default_sync_mode")]
+    #[validate(custom(function = "validate_sync_mode"))]
+    pub sync_mode: String,
+    pub watermark_column: Option<String>,
+    #[serde(default = "default_load_strategy")]
+    #[validate(custom(function = "validate_load_strategy"))]
+    pub load_strategy: String,
+    #[serde(default)]
+    pub propagate_deletes: bool,
+    #[serde(default = "default_parallelism")]
+    #[validate(range(
+        min = 1,
+        max = 64,
+        message = "parallelism must be between 1 and 64"
+    ))]
+    pub parallelism: i32,
+    pub source_filter: Option<String>,
+    pub source_sql: Option<String>,
+    #[serde(default = "default_parquet_export")]
+    #[validate(custom(function = "validate_parquet_export"))]
+    pub parquet_export: String,
+    pub parquet_partition_by: Option<String>,
+    #[serde(default = "default_parquet_compression")]
+    #[validate(custom(function = "validate_parquet_compression"))]
+    pub parquet_compression: String,
+    #[serde(default = "default_parquet_row_group_size")]
+    #[validate(range(
+        min = 1024,
+        max = 10_000_000,
+        message = "parquet_row_group_size must be between 1024 and 10000000"
+    ))]
+    pub parquet_row_group_size: i64,
+    pub schedule_cron: Option<String>,
+    pub schedule_timezone: Option<String>,
+    #[serde(default = "default_retry_max_attempts")]
+    #[validate(range(
+        min = 1,
+        max = 10,
+        message = "retry_max_attempts must be between 1 and 10"
+    ))]
+    pub retry_max_attempts: i32,
+    #[serde(default = "default_retry_base_delay_ms")]
+    #[validate(range(
+        min = 0,
+        message = "retry_base_delay_ms cannot be negative"
+    ))]
+    pub retry_base_delay_ms: i64,
+    #[serde(default = "default_retry_max_delay_ms")]
+    #[validate(range(
+        min = 0,
+        message = "retry_max_delay_ms cannot be negative"
+    ))]
+    pub retry_max_delay_ms: i64,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate)]
@@ -98,8 +397,32 @@ pub struct TransTaskListVo {
     pub table_name: String,
     pub table_comment: String,
     pub remark: String,
-    pub row_count: u16,
+    pub row_count: i64,
     pub last_trans_time: DateTime,
+    pub sync_mode: String,
+    pub watermark_column: Option<String>,
+    pub watermark_value: Option<String>,
+    pub binlog_file: Option<String>,
+    pub binlog_position: Option<i64>,
+    pub gtid_set: Option<String>,
+    pub load_strategy: String,
+    pub propagate_deletes: bool,
+    pub parallelism: i32,
+    pub source_filter: Option<String>,
+    pub source_sql: Option<String>,
+    pub parquet_export: String,
+    pub parquet_partition_by: Option<String>,
+    pub parquet_compression: String,
+    pub parquet_row_group_size: i64,
+    pub schedule_cron: Option<String>,
+    pub schedule_timezone: Option<String>,
+    pub last_fire_time: Option<DateTime>,
+    pub run_state: String,
+    pub retry_max_attempts: i32,
+    pub retry_base_delay_ms: i64,
+    pub retry_max_delay_ms: i64,
+    pub last_attempt_count: i32,
+    pub last_error: Option<String>,
     #[serde(flatten)]
     pub base_entity: BaseEntity,
 }
@@ -111,8 +434,502 @@ pub struct TransTaskDetailVo {
     pub table_name: String,
     pub table_comment: String,
     pub remark: String,
-    pub row_count: u16,
+    pub row_count: i64,
     pub last_trans_time: DateTime,
+    pub sync_mode: String,
+    pub watermark_column: Option<String>,
+    pub watermark_value: Option<String>,
+    pub binlog_file: Option<String>,
+    pub binlog_position: Option<i64>,
+    pub gtid_set: Option<String>,
+    pub load_strategy: String,
+    pub propagate_deletes: bool,
+    pub parallelism: i32,
+    pub source_filter: Option<String>,
+    pub source_sql: Option<String>,
+    pub parquet_export: String,
+    pub parquet_partition_by: Option<String>,
+    pub parquet_compression: String,
+    pub parquet_row_group_size: i64,
+    pub schedule_cron: Option<String>,
+    pub schedule_timezone: Option<String>,
+    pub last_fire_time: Option<DateTime>,
+    pub run_state: String,
+    pub retry_max_attempts: i32,
+    pub retry_base_delay_ms: i64,
+    pub retry_max_delay_ms: i64,
+    pub last_attempt_count: i32,
+    pub last_error: Option<String>,
     #[serde(flatten)]
     pub base_entity: BaseEntity,
 }
+
+// 预览触发时间的查询参数
+#[derive(Clone, Debug, Deserialize)]
+pub struct TransTaskNextRunsBo {
+    pub count: Option<usize>,
+}
+
+impl TransTaskNextRunsBo {
+    // 默认预览5次，最多100次
+    pub fn count(&self) -> usize {
+        self.count.unwrap_or(5).clamp(1, 100)
+    }
+}
+
+// 按数据源批量创建任务：按表名的通配符（* 与 ?，不区分大小写）选择表，
+// include 为空时选择全部表，其余配置由全部任务共用
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_bulk_create_bo"))]
+pub struct TransTaskBulkCreateBo {
+    #[validate(required)]
+    pub data_source_id: Option<i64>,
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub remark: String,
+    #[serde(default = "default_sync_mode")]
+    #[validate(custom(function = "validate_sync_mode"))]
+    pub sync_mode: String,
+    pub watermark_column: Option<String>,
+    #[serde(default = "default_load_strategy")]
+    #[validate(custom(function = "validate_load_strategy"))]
+    pub load_strategy: String,
+    #[serde(default)]
+    pub propagate_deletes: bool,
+    #[serde(default = "default_parallelism")]
+    #[validate(range(
+        min = 1,
+        max = 64,
+        message = "parallelism must be between 1 and 64"
+    ))]
+    pub parallelism: i32,
+    #[serde(default = "default_parquet_export")]
+    #[validate(custom(function = "validate_parquet_export"))]
+    pub parquet_export: String,
+    pub parquet_partition_by: Option<String>,
+    #[serde(default = "default_parquet_compression")]
+    #[validate(custom(function = "validate_parquet_compression"))]
+    pub parquet_compression: String,
+    #[serde(default = "default_parquet_row_group_size")]
+    #[validate(range(
+        min = 1024,
+        max = 10_000_000,
+        message = "parquet_row_group_size must be between 1024 and 10000000"
+    ))]
+    pub parquet_row_group_size: i64,
+    pub schedule_cron: Option<String>,
+    pub schedule_timezone: Option<String>,
+    #[serde(default = "default_retry_max_attempts")]
+    #[validate(range(
+        min = 1,
+        max = 10,
+        message = "retry_max_attempts must be between 1 and 10"
+    ))]
+    pub retry_max_attempts: i32,
+    #[serde(default = "default_retry_base_delay_ms")]
+    #[validate(range(
+        min = 0,
+        message = "retry_base_delay_ms cannot be negative"
+    ))]
+    pub retry_base_delay_ms: i64,
+    #[serde(default = "default_retry_max_delay_ms")]
+    #[validate(range(
+        min = 0,
+        message = "retry_max_delay_ms cannot be negative"
+    ))]
+    pub retry_max_delay_ms: i64,
+}
+
+impl TransTaskBulkCreateBo {
+    // 表名匹配任一 include（为空时视为匹配）且不匹配任何 exclude
+    pub fn matches(&self, table_name: &str) -> bool {
+        let matches_any = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| glob_match(pattern.trim(), table_name))
+        };
+        (self.include.is_empty() || matches_any(&self.include))
+            && !matches_any(&self.exclude)
+    }
+
+    // 生成单张表的创建参数，表注释为空时使用表名
+    pub fn to_create_bo(
+        &self,
+        table_name: &str,
+        table_comment: &str,
+    ) -> TransTaskCreateBo {
+        let table_comment = match table_comment.trim() {
+            "" => table_name,
+            comment => comment,
+        };
+        TransTaskCreateBo {
+            data_source_id: self.data_source_id,
+            table_name: table_name.to_string(),
+            table_comment: table_comment.to_string(),
+            remark: self.remark.clone(),
+            sync_mode: self.sync_mode.clone(),
+            watermark_column: self.watermark_column.clone(),
+            load_strategy: self.load_strategy.clone(),
+            propagate_deletes: self.propagate_deletes,
+            parallelism: self.parallelism,
+            source_filter: None,
+            source_sql: None,
+            parquet_export: self.parquet_export.clone(),
+            parquet_partition_by: self.parquet_partition_by.clone(),
+            parquet_compression: self.parquet_compression.clone(),
+            parquet_row_group_size: self.parquet_row_group_size,
+            schedule_cron: self.schedule_cron.clone(),
+            schedule_timezone: self.schedule_timezone.clone(),
+            retry_max_attempts: self.retry_max_attempts,
+            retry_base_delay_ms: self.retry_base_delay_ms,
+            retry_max_delay_ms: self.retry_max_delay_ms,
+        }
+    }
+}
+
+// 批量创建中的一张表，跳过时附带原因
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskBulkTableVo {
+    pub table_name: String,
+    pub table_comment: String,
+    pub skip_reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskBulkCreateVo {
+    pub created: Vec<TransTaskBulkTableVo>,
+    pub skipped: Vec<TransTaskBulkTableVo>,
+}
+
+fn default_sync_mode() -> String {
+    String::from(SYNC_MODE_FULL)
+}
+
+// 表名用作DuckDB的表名与Parquet导出的目录名
+pub fn validate_table_name(table_name: &str) -> Result<(), ValidationError> {
+    if path_util::is_identifier(table_name) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("table_name").with_message(
+            "table_name may only contain letters, digits, '_', '$' and '-'"
+                .into(),
+        ))
+    }
+}
+
+fn validate_sync_mode(sync_mode: &str) -> Result<(), ValidationError> {
+    match sync_mode {
+        SYNC_MODE_FULL
+        | SYNC_MODE_APPEND
+        | SYNC_MODE_INCREMENTAL
+        | SYNC_MODE_CDC => Ok(()),
+        _ => Err(ValidationError::new("sync_mode").with_message(
+            "sync_mode must be FULL, APPEND, INCREMENTAL or CDC".into(),
+        )),
+    }
+}
+
+fn non_empty(sql: Option<String>) -> Option<String> {
+    sql.filter(|sql| !sql.trim().is_empty())
+}
+
+fn default_parallelism() -> i32 {
+    1
+}
+
+fn default_retry_max_attempts() -> i32 {
+    3
+}
+
+fn default_retry_base_delay_ms() -> i64 {
+    1_000
+}
+
+fn default_retry_max_delay_ms() -> i64 {
+    60_000
+}
+
+fn default_parquet_export() -> String {
+    String::from(PARQUET_EXPORT_NONE)
+}
+
+fn validate_parquet_export(
+    parquet_export: &str,
+) -> Result<(), ValidationError> {
+    match parquet_export {
+        PARQUET_EXPORT_NONE | PARQUET_EXPORT_TABLE | PARQUET_EXPORT_DELTA => {
+            Ok(())
+        }
+        _ => Err(ValidationError::new("parquet_export").with_message(
+            "parquet_export must be NONE, TABLE or DELTA".into(),
+        )),
+    }
+}
+
+fn default_parquet_compression() -> String {
+    String::from("snappy")
+}
+
+fn validate_parquet_compression(
+    parquet_compression: &str,
+) -> Result<(), ValidationError> {
+    match parquet_compression {
+        "snappy" | "zstd" => Ok(()),
+        _ => Err(ValidationError::new("parquet_compression")
+            .with_message("parquet_compression must be snappy or zstd".into())),
+    }
+}
+
+fn default_parquet_row_group_size() -> i64 {
+    122_880
+}
+
+fn default_load_strategy() -> String {
+    String::from(LOAD_STRATEGY_APPEND)
+}
+
+fn validate_load_strategy(load_strategy: &str) -> Result<(), ValidationError> {
+    match load_strategy {
+        LOAD_STRATEGY_APPEND | LOAD_STRATEGY_MERGE => Ok(()),
+        _ => Err(ValidationError::new("load_strategy")
+            .with_message("load_strategy must be APPEND or MERGE".into())),
+    }
+}
+
+// 增量模式必须指定水位列（自增ID或更新时间列）
+fn validate_watermark(
+    sync_mode: &str,
+    watermark_column: &Option<String>,
+) -> Result<(), ValidationError> {
+    let has_column = watermark_column
+        .as_ref()
+        .is_some_and(|column| !column.is_empty());
+    if sync_mode == SYNC_MODE_INCREMENTAL && !has_column {
+        return Err(ValidationError::new("watermark_column").with_message(
+            "watermark_column is required in INCREMENTAL mode".into(),
+        ));
+    }
+    Ok(())
+}
+
+// 删除同步依赖主键比对，只能与合并策略一起使用
+fn validate_propagate_deletes(
+    load_strategy: &str,
+    propagate_deletes: bool,
+) -> Result<(), ValidationError> {
+    if propagate_deletes && load_strategy != LOAD_STRATEGY_MERGE {
+        return Err(ValidationError::new("propagate_deletes").with_message(
+            "propagate_deletes requires the MERGE load strategy".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn parse_schedule(
+    schedule_cron: &Option<String>,
+    schedule_timezone: &Option<String>,
+) -> Result<Option<CronSchedule>, String> {
+    match schedule_cron.as_deref() {
+        Some(cron) if !cron.trim().is_empty() => CronSchedule::parse(
+            cron,
+            schedule_timezone.as_deref().unwrap_or_default(),
+        )
+        .map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn validate_schedule(
+    schedule_cron: &Option<String>,
+    schedule_timezone: &Option<String>,
+) -> Result<(), ValidationError> {
+    parse_schedule(schedule_cron, schedule_timezone).map_err(|e| {
+        ValidationError::new("schedule_cron").with_message(e.into())
+    })?;
+    Ok(())
+}
+
+fn validate_retry_delay(
+    retry_base_delay_ms: i64,
+    retry_max_delay_ms: i64,
+) -> Result<(), ValidationError> {
+    if retry_max_delay_ms < retry_base_delay_ms {
+        return Err(ValidationError::new("retry_max_delay_ms").with_message(
+            "retry_max_delay_ms cannot be less than retry_base_delay_ms".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_source(
+    source_filter: &Option<String>,
+    source_sql: &Option<String>,
+) -> Result<(), ValidationError> {
+    let configured = |sql: &Option<String>| {
+        sql.as_deref().is_some_and(|sql| !sql.trim().is_empty())
+    };
+    if configured(source_filter) && configured(source_sql) {
+        return Err(ValidationError::new("source_sql").with_message(
+            "source_filter and source_sql cannot both be set".into(),
+        ));
+    }
+    Ok(())
+}
+
+// CDC按源表的binlog事件同步，不能使用抽取SQL，也没有按批次的增量可导出
+fn validate_cdc(
+    sync_mode: &str,
+    source_filter: &Option<String>,
+    source_sql: &Option<String>,
+    parquet_export: &str,
+) -> Result<(), ValidationError> {
+    if sync_mode != SYNC_MODE_CDC {
+        return Ok(());
+    }
+    if non_empty(source_filter.clone()).is_some()
+        || non_empty(source_sql.clone()).is_some()
+    {
+        return Err(ValidationError::new("sync_mode").with_message(
+            "source_filter and source_sql are not supported in CDC mode".into(),
+        ));
+    }
+    if parquet_export == PARQUET_EXPORT_DELTA {
+        return Err(ValidationError::new("parquet_export").with_message(
+            "parquet_export DELTA is not supported in CDC mode".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_create_bo(bo: &TransTaskCreateBo) -> Result<(), ValidationError> {
+    validate_watermark(&bo.sync_mode, &bo.watermark_column)?;
+    validate_source(&bo.source_filter, &bo.source_sql)?;
+    validate_cdc(
+        &bo.sync_mode,
+        &bo.source_filter,
+        &bo.source_sql,
+        &bo.parquet_export,
+    )?;
+    validate_propagate_deletes(&bo.load_strategy, bo.propagate_deletes)?;
+    validate_schedule(&bo.schedule_cron, &bo.schedule_timezone)?;
+    validate_retry_delay(bo.retry_base_delay_ms, bo.retry_max_delay_ms)
+}
+
+fn validate_bulk_create_bo(
+    bo: &TransTaskBulkCreateBo,
+) -> Result<(), ValidationError> {
+    validate_watermark(&bo.sync_mode, &bo.watermark_column)?;
+    validate_cdc(&bo.sync_mode, &None, &None, &bo.parquet_export)?;
+    validate_propagate_deletes(&bo.load_strategy, bo.propagate_deletes)?;
+    validate_schedule(&bo.schedule_cron, &bo.schedule_timezone)?;
+    validate_retry_delay(bo.retry_base_delay_ms, bo.retry_max_delay_ms)
+}
+
+fn validate_update_bo(bo: &TransTaskUpdateBo) -> Result<(), ValidationError> {
+    validate_watermark(&bo.sync_mode, &bo.watermark_column)?;
+    validate_source(&bo.source_filter, &bo.source_sql)?;
+    validate_cdc(
+        &bo.sync_mode,
+        &bo.source_filter,
+        &bo.source_sql,
+        &bo.parquet_export,
+    )?;
+    validate_propagate_deletes(&bo.load_strategy, bo.propagate_deletes)?;
+    validate_schedule(&bo.schedule_cron, &bo.schedule_timezone)?;
+    validate_retry_delay(bo.retry_base_delay_ms, bo.retry_max_delay_ms)
+}
+
+// 抖动前的重试等待毫秒数：base * 2^(attempt-1)，不超过 max
+fn backoff_delay_ms(base_ms: i64, max_ms: i64, attempt: i32) -> u64 {
+    let exponent = attempt.saturating_sub(1).clamp(0, 30) as u32;
+    (base_ms.max(0) as u64)
+        .saturating_mul(1 << exponent)
+        .min(max_ms.max(0) as u64)
+}
+
+// 通配符匹配，* 匹配任意个字符，? 匹配单个字符，不区分大小写
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    // 最近一个 * 的位置及其当前匹配到的文本位置，失配时回溯
+    let mut star = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_and_is_capped() {
+        assert_eq!(backoff_delay_ms(1_000, 60_000, 1), 1_000);
+        assert_eq!(backoff_delay_ms(1_000, 60_000, 2), 2_000);
+        assert_eq!(backoff_delay_ms(1_000, 60_000, 4), 8_000);
+        assert_eq!(backoff_delay_ms(1_000, 60_000, 6), 32_000);
+        assert_eq!(backoff_delay_ms(1_000, 60_000, 7), 60_000);
+        assert_eq!(backoff_delay_ms(1_000, 60_000, 100), 60_000);
+        assert_eq!(backoff_delay_ms(1_000, 500, 1), 500);
+    }
+
+    #[test]
+    fn backoff_handles_extreme_inputs() {
+        assert_eq!(backoff_delay_ms(1_000, 60_000, 0), 1_000);
+        assert_eq!(backoff_delay_ms(1_000, 60_000, i32::MIN), 1_000);
+        assert_eq!(
+            backoff_delay_ms(i64::MAX, i64::MAX, i32::MAX),
+            i64::MAX as u64
+        );
+        assert_eq!(backoff_delay_ms(-5, 60_000, 3), 0);
+        assert_eq!(backoff_delay_ms(1_000, -1, 3), 0);
+    }
+
+    #[test]
+    fn glob_wildcards() {
+        assert!(glob_match("*", "orders"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("ord*", "orders"));
+        assert!(glob_match("*ers", "orders"));
+        assert!(glob_match("o*r*s", "orders"));
+        assert!(!glob_match("ord*x", "orders"));
+        assert!(glob_match("order?", "orders"));
+        assert!(!glob_match("order?", "order"));
+        assert!(glob_match("??????", "orders"));
+        assert!(!glob_match("?????", "orders"));
+    }
+
+    #[test]
+    fn glob_empty_and_consecutive_stars() {
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "orders"));
+        assert!(glob_match("**", "orders"));
+        assert!(glob_match("o**s", "orders"));
+        assert!(glob_match("t_***_log", "t_a_b_log"));
+        assert!(!glob_match("t_**_log", "t_a_b_logs"));
+    }
+
+    #[test]
+    fn glob_ignores_case() {
+        assert!(glob_match("ORDERS", "orders"));
+        assert!(glob_match("ord*", "ORDER_ITEMS"));
+        assert!(glob_match("订单*", "订单明细"));
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::{common::model::entity::BaseEntity, sys::user::model::user::User};
+
+// 传输任务的列映射：选择源列并指定目标列名、目标类型与空值默认值。
+// 任务没有列映射时按源表全部列、默认类型映射传输
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskColumn {
+    pub trans_task_column_id: Option<i64>,
+    pub trans_task_id: i64,
+    pub source_column: String,
+    pub target_column: String,
+    pub target_type: String,
+    // 是否传输该列
+    pub included: bool,
+    // 源值为NULL（含零日期）时写入的默认值，按目标类型转换
+    pub default_value: Option<String>,
+    // 目标表中的列顺序
+    pub ordinal_position: i32,
+    #[serde(flatten)]
+    pub base_entity: BaseEntity,
+}
+
+impl TransTaskColumn {
+    pub fn from_bo(
+        trans_task_id: i64,
+        ordinal_position: i32,
+        bo: TransTaskColumnBo,
+        user: &User,
+    ) -> Self {
+        Self {
+            trans_task_column_id: None,
+            trans_task_id,
+            source_column: bo.source_column,
+            target_column: bo.target_column,
+            target_type: bo.target_type,
+            included: bo.included,
+            default_value: bo.default_value,
+            ordinal_position,
+            base_entity: BaseEntity::new(user.get_user_id()),
+        }
+    }
+
+    pub fn to_vo(&self) -> TransTaskColumnVo {
+        TransTaskColumnVo {
+            trans_task_column_id: self.trans_task_column_id.unwrap(),
+            trans_task_id: self.trans_task_id,
+            source_column: self.source_column.clone(),
+            target_column: self.target_column.clone(),
+            target_type: self.target_type.clone(),
+            included: self.included,
+            default_value: self.default_value.clone(),
+            ordinal_position: self.ordinal_position,
+            base_entity: self.base_entity.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+pub struct TransTaskColumnBo {
+    #[validate(length(min = 1, message = "source_column cannot be empty"))]
+    pub source_column: String,
+    #[validate(length(min = 1, message = "target_column cannot be empty"))]
+    pub target_column: String,
+    #[validate(custom(function = "validate_target_type"))]
+    pub target_type: String,
+    #[serde(default = "default_included")]
+    pub included: bool,
+    pub default_value: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskColumnVo {
+    pub trans_task_column_id: i64,
+    pub trans_task_id: i64,
+    pub source_column: String,
+    pub target_column: String,
+    pub target_type: String,
+    pub included: bool,
+    pub default_value: Option<String>,
+    pub ordinal_position: i32,
+    #[serde(flatten)]
+    pub base_entity: BaseEntity,
+}
+
+fn default_included() -> bool {
+    true
+}
+
+// 目标类型会拼接进建表语句，仅允许类型名、精度与数组写法中出现的字符
+fn validate_target_type(target_type: &str) -> Result<(), ValidationError> {
+    let valid = !target_type.trim().is_empty()
+        && target_type.chars().all(|c| {
+            c.is_ascii_alphanumeric()
+                || matches!(c, '_' | ' ' | '(' | ')' | ',' | '[' | ']')
+        });
+    if !valid {
+        return Err(ValidationError::new("target_type")
+            .with_message("target_type is not a valid DuckDB type".into()));
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{common::model::entity::BaseEntity, sys::user::model::user::User};
+
+// 任务依赖：上游任务全部成功后才执行下游任务，DAG执行时按此排序
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskDependency {
+    pub trans_task_dependency_id: Option<i64>,
+    pub trans_task_id: i64,
+    pub upstream_task_id: i64,
+    #[serde(flatten)]
+    pub base_entity: BaseEntity,
+}
+
+impl TransTaskDependency {
+    pub fn new(trans_task_id: i64, upstream_task_id: i64, user: &User) -> Self {
+        Self {
+            trans_task_dependency_id: None,
+            trans_task_id,
+            upstream_task_id,
+            base_entity: BaseEntity::new(user.get_user_id()),
+        }
+    }
+}
+
+// 整体保存任务的上游任务
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskDependencyBo {
+    #[serde(default)]
+    pub upstream_task_ids: Vec<i64>,
+}
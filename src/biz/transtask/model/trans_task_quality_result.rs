@@ -0,0 +1,85 @@
+use rbdc::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    biz::transtask::model::trans_task_quality_rule::TransTaskQualityRule,
+    common::model::entity::BaseEntity,
+};
+
+// 一次执行中单条质量规则的检查结果，保存规则当时的名称、类型与处理方式
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskQualityResult {
+    pub trans_task_quality_result_id: Option<i64>,
+    pub trans_task_id: i64,
+    pub trans_task_run_id: i64,
+    pub trans_task_quality_rule_id: i64,
+    pub rule_name: String,
+    pub rule_type: String,
+    pub on_failure: String,
+    pub passed: bool,
+    // 检查得到的实际值，如违反规则的行数、行数变化百分比、最新时间
+    pub observed_value: Option<String>,
+    pub message: Option<String>,
+    pub checked_time: DateTime,
+    #[serde(flatten)]
+    pub base_entity: BaseEntity,
+}
+
+impl TransTaskQualityResult {
+    pub fn new(
+        rule: &TransTaskQualityRule,
+        passed: bool,
+        observed_value: Option<String>,
+        message: Option<String>,
+    ) -> Self {
+        Self {
+            trans_task_quality_result_id: None,
+            trans_task_id: rule.trans_task_id,
+            trans_task_run_id: 0,
+            trans_task_quality_rule_id: rule
+                .trans_task_quality_rule_id
+                .unwrap_or_default(),
+            rule_name: rule.rule_name.clone(),
+            rule_type: rule.rule_type.clone(),
+            on_failure: rule.on_failure.clone(),
+            passed,
+            observed_value,
+            message,
+            checked_time: DateTime::now(),
+            base_entity: BaseEntity::new(rule.base_entity.created_by),
+        }
+    }
+
+    pub fn to_vo(&self) -> TransTaskQualityResultVo {
+        TransTaskQualityResultVo {
+            trans_task_quality_result_id: self
+                .trans_task_quality_result_id
+                .unwrap(),
+            trans_task_id: self.trans_task_id,
+            trans_task_run_id: self.trans_task_run_id,
+            trans_task_quality_rule_id: self.trans_task_quality_rule_id,
+            rule_name: self.rule_name.clone(),
+            rule_type: self.rule_type.clone(),
+            on_failure: self.on_failure.clone(),
+            passed: self.passed,
+            observed_value: self.observed_value.clone(),
+            message: self.message.clone(),
+            checked_time: self.checked_time.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskQualityResultVo {
+    pub trans_task_quality_result_id: i64,
+    pub trans_task_id: i64,
+    pub trans_task_run_id: i64,
+    pub trans_task_quality_rule_id: i64,
+    pub rule_name: String,
+    pub rule_type: String,
+    pub on_failure: String,
+    pub passed: bool,
+    pub observed_value: Option<String>,
+    pub message: Option<String>,
+    pub checked_time: DateTime,
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::{common::model::entity::BaseEntity, sys::user::model::user::User};
+
+// 规则类型及各自使用的字段：
+//   NOT_NULL         column_name 不能为NULL
+//   UNIQUE           column_name（可逗号分隔多列）组合唯一，忽略NULL
+//   ACCEPTED_VALUES  column_name 只能取 accepted_values 中的值（逗号分隔），忽略NULL
+//   RANGE            column_name 位于 [min_value, max_value] 之间，可只配置一端
+//   ROW_COUNT_DELTA  目标表行数相对上一次执行的变化百分比不超过 max_value
+//   FRESHNESS        column_name 的最大值距当前不超过 max_value 秒
+//   CUSTOM_SQL       custom_sql 返回违反断言的行数，为0时通过，{table} 替换为目标表
+pub const RULE_TYPE_NOT_NULL: &str = "NOT_NULL";
+pub const RULE_TYPE_UNIQUE: &str = "UNIQUE";
+pub const RULE_TYPE_ACCEPTED_VALUES: &str = "ACCEPTED_VALUES";
+pub const RULE_TYPE_RANGE: &str = "RANGE";
+pub const RULE_TYPE_ROW_COUNT_DELTA: &str = "ROW_COUNT_DELTA";
+pub const RULE_TYPE_FRESHNESS: &str = "FRESHNESS";
+pub const RULE_TYPE_CUSTOM_SQL: &str = "CUSTOM_SQL";
+
+// 规则未通过时的处理：仅记录、将执行标记为失败、标记失败并回滚本次写入
+pub const ON_FAILURE_WARN: &str = "WARN";
+pub const ON_FAILURE_FAIL: &str = "FAIL";
+pub const ON_FAILURE_ROLLBACK: &str = "ROLLBACK";
+
+// 传输任务的数据质量规则，每次加载完成后在DuckDB中对目标表检查
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskQualityRule {
+    pub trans_task_quality_rule_id: Option<i64>,
+    pub trans_task_id: i64,
+    pub rule_name: String,
+    pub rule_type: String,
+    pub column_name: Option<String>,
+    pub accepted_values: Option<String>,
+    pub min_value: Option<String>,
+    pub max_value: Option<String>,
+    pub custom_sql: Option<String>,
+    pub on_failure: String,
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub base_entity: BaseEntity,
+}
+
+impl TransTaskQualityRule {
+    pub fn from_bo(
+        trans_task_id: i64,
+        bo: TransTaskQualityRuleBo,
+        user: &User,
+    ) -> Self {
+        Self {
+            trans_task_quality_rule_id: None,
+            trans_task_id,
+            rule_name: bo.rule_name,
+            rule_type: bo.rule_type,
+            column_name: non_empty(bo.column_name),
+            accepted_values: non_empty(bo.accepted_values),
+            min_value: non_empty(bo.min_value),
+            max_value: non_empty(bo.max_value),
+            custom_sql: non_empty(bo.custom_sql),
+            on_failure: bo.on_failure,
+            enabled: bo.enabled,
+            base_entity: BaseEntity::new(user.get_user_id()),
+        }
+    }
+
+    pub fn to_vo(&self) -> TransTaskQualityRuleVo {
+        TransTaskQualityRuleVo {
+            trans_task_quality_rule_id: self
+                .trans_task_quality_rule_id
+                .unwrap(),
+            trans_task_id: self.trans_task_id,
+            rule_name: self.rule_name.clone(),
+            rule_type: self.rule_type.clone(),
+            column_name: self.column_name.clone(),
+            accepted_values: self.accepted_values.clone(),
+            min_value: self.min_value.clone(),
+            max_value: self.max_value.clone(),
+            custom_sql: self.custom_sql.clone(),
+            on_failure: self.on_failure.clone(),
+            enabled: self.enabled,
+            base_entity: self.base_entity.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_rule_bo"))]
+pub struct TransTaskQualityRuleBo {
+    #[validate(length(min = 1, message = "rule_name cannot be empty"))]
+    pub rule_name: String,
+    #[validate(custom(function = "validate_rule_type"))]
+    pub rule_type: String,
+    pub column_name: Option<String>,
+    pub accepted_values: Option<String>,
+    pub min_value: Option<String>,
+    pub max_value: Option<String>,
+    pub custom_sql: Option<String>,
+    #[serde(default = "default_on_failure")]
+    #[validate(custom(function = "validate_on_failure"))]
+    pub on_failure: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskQualityRuleVo {
+    pub trans_task_quality_rule_id: i64,
+    pub trans_task_id: i64,
+    pub rule_name: String,
+    pub rule_type: String,
+    pub column_name: Option<String>,
+    pub accepted_values: Option<String>,
+    pub min_value: Option<String>,
+    pub max_value: Option<String>,
+    pub custom_sql: Option<String>,
+    pub on_failure: String,
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub base_entity: BaseEntity,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn default_on_failure() -> String {
+    String::from(ON_FAILURE_WARN)
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn validate_rule_type(rule_type: &str) -> Result<(), ValidationError> {
+    match rule_type {
+        RULE_TYPE_NOT_NULL
+        | RULE_TYPE_UNIQUE
+        | RULE_TYPE_ACCEPTED_VALUES
+        | RULE_TYPE_RANGE
+        | RULE_TYPE_ROW_COUNT_DELTA
+        | RULE_TYPE_FRESHNESS
+        | RULE_TYPE_CUSTOM_SQL => Ok(()),
+        _ => Err(ValidationError::new("rule_type").with_message(
+            "rule_type must be NOT_NULL, UNIQUE, ACCEPTED_VALUES, RANGE, \
+             ROW_COUNT_DELTA, FRESHNESS or CUSTOM_SQL"
+                .into(),
+        )),
+    }
+}
+
+fn validate_on_failure(on_failure: &str) -> Result<(), ValidationError> {
+    match on_failure {
+        ON_FAILURE_WARN | ON_FAILURE_FAIL | ON_FAILURE_ROLLBACK => Ok(()),
+        _ => Err(ValidationError::new("on_failure")
+            .with_message("on_failure must be WARN, FAIL or ROLLBACK".into())),
+    }
+}
+
+fn required(
+    field: &'static str,
+    value: &Option<String>,
+) -> Result<(), ValidationError> {
+    if value.as_deref().is_none_or(|value| value.trim().is_empty()) {
+        return Err(ValidationError::new(field).with_message(
+            format!("{} is required for this rule_type", field).into(),
+        ));
+    }
+    Ok(())
+}
+
+fn number(
+    field: &'static str,
+    value: &Option<String>,
+) -> Result<(), ValidationError> {
+    required(field, value)?;
+    match value.as_deref().unwrap_or_default().trim().parse::<f64>() {
+        Ok(number) if number >= 0.0 => Ok(()),
+        _ => Err(ValidationError::new(field).with_message(
+            format!("{} must be a non-negative number", field).into(),
+        )),
+    }
+}
+
+// 按规则类型检查必填字段
+fn validate_rule_bo(
+    bo: &TransTaskQualityRuleBo,
+) -> Result<(), ValidationError> {
+    match bo.rule_type.as_str() {
+        RULE_TYPE_NOT_NULL | RULE_TYPE_UNIQUE => {
+            required("column_name", &bo.column_name)
+        }
+        RULE_TYPE_ACCEPTED_VALUES => {
+            required("column_name", &bo.column_name)?;
+            required("accepted_values", &bo.accepted_values)
+        }
+        RULE_TYPE_RANGE => {
+            required("column_name", &bo.column_name)?;
+            if non_empty(bo.min_value.clone()).is_none() {
+                required("max_value", &bo.max_value)?;
+            }
+            Ok(())
+        }
+        RULE_TYPE_ROW_COUNT_DELTA => number("max_value", &bo.max_value),
+        RULE_TYPE_FRESHNESS => {
+            required("column_name", &bo.column_name)?;
+            number("max_value", &bo.max_value)
+        }
+        RULE_TYPE_CUSTOM_SQL => {
+            required("custom_sql", &bo.custom_sql)?;
+            let sql = bo.custom_sql.as_deref().unwrap_or_default().trim();
+            let head = sql
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_uppercase();
+            // 在本地数仓中执行，只允许单条查询语句
+            if (head != "SELECT" && head != "WITH")
+                || sql.trim_end_matches(';').contains(';')
+            {
+                return Err(ValidationError::new("custom_sql").with_message(
+                    "custom_sql must be a single SELECT statement".into(),
+                ));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,164 @@
+use rbdc::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    biz::transtask::model::trans_backfill::BackfillWindow,
+    common::model::entity::BaseEntity,
+};
+
+// 执行状态
+pub const RUN_STATUS_RUNNING: &str = "RUNNING";
+pub const RUN_STATUS_SUCCESS: &str = "SUCCESS";
+pub const RUN_STATUS_FAILED: &str = "FAILED";
+// 暂停：增量模式保留已提交的部分数据，其余模式回滚
+pub const RUN_STATUS_PAUSED: &str = "PAUSED";
+// 取消：回滚本次写入
+pub const RUN_STATUS_CANCELLED: &str = "CANCELLED";
+// 调度触发时上一次执行尚未结束，本次跳过
+pub const RUN_STATUS_SKIPPED: &str = "SKIPPED";
+// 调度器停机期间错过的触发
+pub const RUN_STATUS_MISSED: &str = "MISSED";
+
+// 执行类型：常规执行、按水位列区间回填（一次回填记录为父执行，各分片为子执行）
+pub const RUN_TYPE_NORMAL: &str = "NORMAL";
+pub const RUN_TYPE_BACKFILL: &str = "BACKFILL";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskRun {
+    pub trans_task_run_id: Option<i64>,
+    pub trans_task_id: i64,
+    pub status: String,
+    pub start_time: DateTime,
+    pub end_time: Option<DateTime>,
+    pub duration_ms: i64,
+    pub rows_read: i64,
+    pub rows_written: i64,
+    pub rows_deleted: i64,
+    pub bytes: i64,
+    pub error_message: Option<String>,
+    pub fence_token: i64,
+    // 本次执行的尝试次数（含重试）
+    pub attempts: i32,
+    pub run_type: String,
+    // 回填分片所属的回填执行
+    pub parent_run_id: Option<i64>,
+    // 回填的水位列区间，包含起点、不包含终点
+    pub window_start: Option<String>,
+    pub window_end: Option<String>,
+    #[serde(flatten)]
+    pub base_entity: BaseEntity,
+}
+
+impl TransTaskRun {
+    pub fn start(trans_task_id: i64, operator_id: i64) -> Self {
+        Self {
+            trans_task_run_id: None,
+            trans_task_id,
+            status: String::from(RUN_STATUS_RUNNING),
+            start_time: DateTime::now(),
+            end_time: None,
+            duration_ms: 0,
+            rows_read: 0,
+            rows_written: 0,
+            rows_deleted: 0,
+            bytes: 0,
+            error_message: None,
+            fence_token: 0,
+            attempts: 0,
+            run_type: String::from(RUN_TYPE_NORMAL),
+            parent_run_id: None,
+            window_start: None,
+            window_end: None,
+            base_entity: BaseEntity::new(operator_id),
+        }
+    }
+
+    // 回填执行，parent_run_id 为空时为整个回填，否则为其中一个分片
+    pub fn backfill(
+        trans_task_id: i64,
+        parent_run_id: Option<i64>,
+        window: &BackfillWindow,
+        operator_id: i64,
+    ) -> Self {
+        Self {
+            run_type: String::from(RUN_TYPE_BACKFILL),
+            parent_run_id,
+            window_start: Some(window.start_string()),
+            window_end: Some(window.end_string()),
+            ..Self::start(trans_task_id, operator_id)
+        }
+    }
+
+    // 未实际执行的触发记录，开始与结束时间均为触发时间
+    pub fn not_run(
+        trans_task_id: i64,
+        status: &str,
+        fire_time: DateTime,
+        message: &str,
+        operator_id: i64,
+    ) -> Self {
+        Self {
+            status: String::from(status),
+            start_time: fire_time.clone(),
+            end_time: Some(fire_time),
+            error_message: Some(String::from(message)),
+            ..Self::start(trans_task_id, operator_id)
+        }
+    }
+
+    pub fn finish(&mut self, status: &str, error_message: Option<String>) {
+        let end_time = DateTime::now();
+        self.duration_ms = end_time.unix_timestamp_millis()
+            - self.start_time.unix_timestamp_millis();
+        self.status = String::from(status);
+        self.end_time = Some(end_time);
+        self.error_message = error_message;
+        self.base_entity.update(self.base_entity.created_by);
+    }
+
+    pub fn to_list_vo(&self) -> TransTaskRunListVo {
+        TransTaskRunListVo {
+            trans_task_run_id: self.trans_task_run_id.unwrap(),
+            trans_task_id: self.trans_task_id,
+            status: self.status.clone(),
+            start_time: self.start_time.clone(),
+            end_time: self.end_time.clone(),
+            duration_ms: self.duration_ms,
+            rows_read: self.rows_read,
+            rows_written: self.rows_written,
+            rows_deleted: self.rows_deleted,
+            bytes: self.bytes,
+            error_message: self.error_message.clone(),
+            fence_token: self.fence_token,
+            attempts: self.attempts,
+            run_type: self.run_type.clone(),
+            parent_run_id: self.parent_run_id,
+            window_start: self.window_start.clone(),
+            window_end: self.window_end.clone(),
+            base_entity: self.base_entity.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskRunListVo {
+    pub trans_task_run_id: i64,
+    pub trans_task_id: i64,
+    pub status: String,
+    pub start_time: DateTime,
+    pub end_time: Option<DateTime>,
+    pub duration_ms: i64,
+    pub rows_read: i64,
+    pub rows_written: i64,
+    pub rows_deleted: i64,
+    pub bytes: i64,
+    pub error_message: Option<String>,
+    pub fence_token: i64,
+    pub attempts: i32,
+    pub run_type: String,
+    pub parent_run_id: Option<i64>,
+    pub window_start: Option<String>,
+    pub window_end: Option<String>,
+    #[serde(flatten)]
+    pub base_entity: BaseEntity,
+}
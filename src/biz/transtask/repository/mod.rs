@@ -1 +1,8 @@
+pub mod trans_dag_run_node_repo;
+pub mod trans_dag_run_repo;
+pub mod trans_task_column_repo;
+pub mod trans_task_dependency_repo;
+pub mod trans_task_quality_result_repo;
+pub mod trans_task_quality_rule_repo;
 pub mod trans_task_repo;
+pub mod trans_task_run_repo;
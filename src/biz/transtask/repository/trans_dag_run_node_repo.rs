@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use rbatis::{RBatis, crud, impl_select, impl_update};
+use rbdc::db::ExecResult;
+
+use crate::biz::transtask::model::trans_dag_run::TransDagRunNode;
+
+#[derive(Clone)]
+pub struct TransDagRunNodeRepository {
+    rb: Arc<RBatis>,
+}
+
+impl TransDagRunNodeRepository {
+    pub fn new(rb: Arc<RBatis>) -> Self {
+        Self { rb }
+    }
+
+    pub async fn select_by_trans_dag_run_id(
+        &self,
+        trans_dag_run_id: &i64,
+    ) -> Result<Vec<TransDagRunNode>, rbatis::Error> {
+        TransDagRunNode::select_by_trans_dag_run_id(&*self.rb, trans_dag_run_id)
+            .await
+    }
+
+    pub async fn insert(
+        &self,
+        trans_dag_run_node: &TransDagRunNode,
+    ) -> Result<ExecResult, rbatis::Error> {
+        TransDagRunNode::insert(&*self.rb, trans_dag_run_node).await
+    }
+
+    pub async fn update_by_id(
+        &self,
+        trans_dag_run_node: &TransDagRunNode,
+        trans_dag_run_node_id: &i64,
+    ) -> Result<ExecResult, rbatis::Error> {
+        TransDagRunNode::update_by_id(
+            &*self.rb,
+            trans_dag_run_node,
+            trans_dag_run_node_id,
+        )
+        .await
+    }
+}
+
+crud!(TransDagRunNode {});
+impl_select!(
+    TransDagRunNode{select_by_trans_dag_run_id(trans_dag_run_id: &i64) => "`where trans_dag_run_id = #{trans_dag_run_id} and deleted_by is null and deleted_date is null order by trans_dag_run_node_id`"}
+);
+impl_update!(
+    TransDagRunNode{update_by_id(trans_dag_run_node_id: &i64) => "`where trans_dag_run_node_id = #{trans_dag_run_node_id} and deleted_by is null and deleted_date is null`"}
+);
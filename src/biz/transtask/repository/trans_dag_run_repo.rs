@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use rbatis::{RBatis, crud, impl_select, impl_update};
+use rbdc::db::ExecResult;
+
+use crate::biz::transtask::model::trans_dag_run::TransDagRun;
+
+#[derive(Clone)]
+pub struct TransDagRunRepository {
+    rb: Arc<RBatis>,
+}
+
+impl TransDagRunRepository {
+    pub fn new(rb: Arc<RBatis>) -> Self {
+        Self { rb }
+    }
+
+    pub async fn select_latest(
+        &self,
+    ) -> Result<Option<TransDagRun>, rbatis::Error> {
+        TransDagRun::select_latest(&*self.rb).await
+    }
+
+    pub async fn insert(
+        &self,
+        trans_dag_run: &TransDagRun,
+    ) -> Result<ExecResult, rbatis::Error> {
+        TransDagRun::insert(&*self.rb, trans_dag_run).await
+    }
+
+    pub async fn update_by_id(
+        &self,
+        trans_dag_run: &TransDagRun,
+        trans_dag_run_id: &i64,
+    ) -> Result<ExecResult, rbatis::Error> {
+        TransDagRun::update_by_id(&*self.rb, trans_dag_run, trans_dag_run_id)
+            .await
+    }
+}
+
+crud!(TransDagRun {});
+impl_select!(
+    TransDagRun{select_latest() -> Option => "`where deleted_by is null and deleted_date is null order by start_time desc, trans_dag_run_id desc limit 1`"}
+);
+impl_update!(
+    TransDagRun{update_by_id(trans_dag_run_id: &i64) => "`where trans_dag_run_id = #{trans_dag_run_id} and deleted_by is null and deleted_date is null`"}
+);
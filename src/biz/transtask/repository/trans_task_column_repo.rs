@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use rbatis::{RBatis, crud, impl_delete, impl_select};
+
+use crate::biz::transtask::model::trans_task_column::TransTaskColumn;
+
+// 每批插入的行数
+const INSERT_BATCH_SIZE: u64 = 100;
+
+#[derive(Clone)]
+pub struct TransTaskColumnRepository {
+    rb: Arc<RBatis>,
+}
+
+impl TransTaskColumnRepository {
+    pub fn new(rb: Arc<RBatis>) -> Self {
+        Self { rb }
+    }
+
+    pub async fn select_by_trans_task_id(
+        &self,
+        trans_task_id: &i64,
+    ) -> Result<Vec<TransTaskColumn>, rbatis::Error> {
+        TransTaskColumn::select_by_trans_task_id(&*self.rb, trans_task_id).await
+    }
+
+    // 在同一事务中整体替换任务的列映射
+    pub async fn replace_by_trans_task_id(
+        &self,
+        trans_task_id: &i64,
+        columns: &[TransTaskColumn],
+    ) -> Result<(), rbatis::Error> {
+        let tx = self.rb.acquire_begin().await?;
+        TransTaskColumn::delete_by_trans_task_id(&tx, trans_task_id).await?;
+        if !columns.is_empty() {
+            TransTaskColumn::insert_batch(&tx, columns, INSERT_BATCH_SIZE)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+crud!(TransTaskColumn {});
+impl_select!(
+    TransTaskColumn{select_by_trans_task_id(trans_task_id: &i64) => "`where trans_task_id = #{trans_task_id} and deleted_by is null and deleted_date is null order by ordinal_position`"}
+);
+impl_delete!(
+    TransTaskColumn{delete_by_trans_task_id(trans_task_id: &i64) => "`where trans_task_id = #{trans_task_id}`"}
+);
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use rbatis::{RBatis, crud, impl_delete, impl_select};
+
+use crate::biz::transtask::model::trans_task_dependency::TransTaskDependency;
+
+// 每批插入的行数
+const INSERT_BATCH_SIZE: u64 = 100;
+
+#[derive(Clone)]
+pub struct TransTaskDependencyRepository {
+    rb: Arc<RBatis>,
+}
+
+impl TransTaskDependencyRepository {
+    pub fn new(rb: Arc<RBatis>) -> Self {
+        Self { rb }
+    }
+
+    pub async fn select_all(
+        &self,
+    ) -> Result<Vec<TransTaskDependency>, rbatis::Error> {
+        TransTaskDependency::select_all_valid(&*self.rb).await
+    }
+
+    // 在同一事务中整体替换任务的上游任务
+    pub async fn replace_by_trans_task_id(
+        &self,
+        trans_task_id: &i64,
+        dependencies: &[TransTaskDependency],
+    ) -> Result<(), rbatis::Error> {
+        let tx = self.rb.acquire_begin().await?;
+        TransTaskDependency::delete_by_trans_task_id(&tx, trans_task_id)
+            .await?;
+        if !dependencies.is_empty() {
+            TransTaskDependency::insert_batch(
+                &tx,
+                dependencies,
+                INSERT_BATCH_SIZE,
+            )
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+crud!(TransTaskDependency {});
+impl_select!(
+    TransTaskDependency{select_all_valid() => "`where deleted_by is null and deleted_date is null`"}
+);
+impl_delete!(
+    TransTaskDependency{delete_by_trans_task_id(trans_task_id: &i64) => "`where trans_task_id = #{trans_task_id}`"}
+);
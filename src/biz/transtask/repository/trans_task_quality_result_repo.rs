@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use rbatis::{
+    RBatis, crud, impl_select, impl_select_page,
+    plugin::page::{Page, PageRequest},
+};
+
+use crate::biz::transtask::model::trans_task_quality_result::TransTaskQualityResult;
+
+// 每批插入的行数
+const INSERT_BATCH_SIZE: u64 = 100;
+
+#[derive(Clone)]
+pub struct TransTaskQualityResultRepository {
+    rb: Arc<RBatis>,
+}
+
+impl TransTaskQualityResultRepository {
+    pub fn new(rb: Arc<RBatis>) -> Self {
+        Self { rb }
+    }
+
+    pub async fn insert_batch(
+        &self,
+        results: &[TransTaskQualityResult],
+    ) -> Result<(), rbatis::Error> {
+        if !results.is_empty() {
+            TransTaskQualityResult::insert_batch(
+                &*self.rb,
+                results,
+                INSERT_BATCH_SIZE,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn select_page_by_trans_task_id(
+        &self,
+        page_request: &PageRequest,
+        trans_task_id: &i64,
+    ) -> Result<Page<TransTaskQualityResult>, rbatis::Error> {
+        TransTaskQualityResult::select_page_by_trans_task_id(
+            &*self.rb,
+            page_request,
+            trans_task_id,
+        )
+        .await
+    }
+
+    pub async fn select_by_trans_task_run_id(
+        &self,
+        trans_task_run_id: &i64,
+    ) -> Result<Vec<TransTaskQualityResult>, rbatis::Error> {
+        TransTaskQualityResult::select_by_trans_task_run_id(
+            &*self.rb,
+            trans_task_run_id,
+        )
+        .await
+    }
+}
+
+crud!(TransTaskQualityResult {});
+impl_select_page!(
+    TransTaskQualityResult{select_page_by_trans_task_id(trans_task_id: &i64) => "`where trans_task_id = #{trans_task_id} and deleted_by is null and deleted_date is null order by checked_time desc, trans_task_quality_result_id`"}
+);
+impl_select!(
+    TransTaskQualityResult{select_by_trans_task_run_id(trans_task_run_id: &i64) => "`where trans_task_run_id = #{trans_task_run_id} and deleted_by is null and deleted_date is null order by trans_task_quality_result_id`"}
+);
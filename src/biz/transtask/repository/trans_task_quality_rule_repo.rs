@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use rbatis::{RBatis, crud, impl_delete, impl_select};
+
+use crate::biz::transtask::model::trans_task_quality_rule::TransTaskQualityRule;
+
+// 每批插入的行数
+const INSERT_BATCH_SIZE: u64 = 100;
+
+#[derive(Clone)]
+pub struct TransTaskQualityRuleRepository {
+    rb: Arc<RBatis>,
+}
+
+impl TransTaskQualityRuleRepository {
+    pub fn new(rb: Arc<RBatis>) -> Self {
+        Self { rb }
+    }
+
+    pub async fn select_by_trans_task_id(
+        &self,
+        trans_task_id: &i64,
+    ) -> Result<Vec<TransTaskQualityRule>, rbatis::Error> {
+        TransTaskQualityRule::select_by_trans_task_id(&*self.rb, trans_task_id)
+            .await
+    }
+
+    // 在同一事务中整体替换任务的质量规则
+    pub async fn replace_by_trans_task_id(
+        &self,
+        trans_task_id: &i64,
+        rules: &[TransTaskQualityRule],
+    ) -> Result<(), rbatis::Error> {
+        let tx = self.rb.acquire_begin().await?;
+        TransTaskQualityRule::delete_by_trans_task_id(&tx, trans_task_id)
+            .await?;
+        if !rules.is_empty() {
+            TransTaskQualityRule::insert_batch(&tx, rules, INSERT_BATCH_SIZE)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+crud!(TransTaskQualityRule {});
+impl_select!(
+    TransTaskQualityRule{select_by_trans_task_id(trans_task_id: &i64) => "`where trans_task_id = #{trans_task_id} and deleted_by is null and deleted_date is null order by trans_task_quality_rule_id`"}
+);
+impl_delete!(
+    TransTaskQualityRule{delete_by_trans_task_id(trans_task_id: &i64) => "`where trans_task_id = #{trans_task_id}`"}
+);
@@ -1,10 +1,14 @@
 use std::sync::Arc;
 
 use rbatis::{RBatis, crud, impl_delete, impl_select, impl_update};
-use rbdc::db::ExecResult;
+use rbdc::{DateTime, db::ExecResult};
+use rbs::value;
 
 use crate::biz::transtask::model::trans_task::TransTask;
 
+// 每批插入的行数
+const INSERT_BATCH_SIZE: u64 = 100;
+
 #[derive(Clone)]
 pub struct TransTaskRepository {
     rb: Arc<RBatis>,
@@ -19,6 +23,12 @@ impl TransTaskRepository {
         TransTask::select_all(&*self.rb).await
     }
 
+    pub async fn select_scheduled(
+        &self,
+    ) -> Result<Vec<TransTask>, rbatis::Error> {
+        TransTask::select_scheduled(&*self.rb).await
+    }
+
     pub async fn select_by_id(
         &self,
         trans_task_id: &i64,
@@ -33,6 +43,17 @@ impl TransTaskRepository {
         TransTask::insert(&*self.rb, data_source).await
     }
 
+    // 在同一事务中插入多个任务
+    pub async fn insert_batch(
+        &self,
+        trans_tasks: &[TransTask],
+    ) -> Result<(), rbatis::Error> {
+        let tx = self.rb.acquire_begin().await?;
+        TransTask::insert_batch(&tx, trans_tasks, INSERT_BATCH_SIZE).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
     pub async fn update_by_id(
         &self,
         data_source: &TransTask,
@@ -41,6 +62,50 @@ impl TransTaskRepository {
         TransTask::update_by_id(&*self.rb, data_source, trans_task_id).await
     }
 
+    // 带fencing的回写：仅当库中的 fence_token 不大于实体的 fence_token 时更新，
+    // 影响行数为0表示已有更新的锁持有者回写过
+    pub async fn update_by_id_fenced(
+        &self,
+        trans_task: &TransTask,
+        trans_task_id: &i64,
+    ) -> Result<ExecResult, rbatis::Error> {
+        TransTask::update_by_id_fenced(
+            &*self.rb,
+            trans_task,
+            trans_task_id,
+            &trans_task.fence_token,
+        )
+        .await
+    }
+
+    // 仅更新调度触发时间，避免覆盖执行过程中写入的其他字段
+    pub async fn update_last_fire_time(
+        &self,
+        trans_task_id: &i64,
+        last_fire_time: &DateTime,
+    ) -> Result<ExecResult, rbatis::Error> {
+        self.rb
+            .exec(
+                "update trans_task set last_fire_time = ? where trans_task_id = ?",
+                vec![value!(last_fire_time), value!(trans_task_id)],
+            )
+            .await
+    }
+
+    // 仅更新执行状态
+    pub async fn update_run_state(
+        &self,
+        trans_task_id: &i64,
+        run_state: &str,
+    ) -> Result<ExecResult, rbatis::Error> {
+        self.rb
+            .exec(
+                "update trans_task set run_state = ? where trans_task_id = ?",
+                vec![value!(run_state), value!(trans_task_id)],
+            )
+            .await
+    }
+
     pub async fn delete_by_id(
         &self,
         trans_task_id: &i64,
@@ -53,9 +118,15 @@ crud!(TransTask {});
 impl_select!(
     TransTask{select_by_id(trans_task_id: &i64) -> Option => "`where trans_task_id = #{trans_task_id} and deleted_by is null and deleted_date is null limit 1`"}
 );
+impl_select!(
+    TransTask{select_scheduled() => "`where schedule_cron is not null and schedule_cron != '' and deleted_by is null and deleted_date is null`"}
+);
 impl_update!(
     TransTask{update_by_id(trans_task_id: &i64) => "`where trans_task_id = #{trans_task_id} and deleted_by is null and deleted_date is null`"}
 );
+impl_update!(
+    TransTask{update_by_id_fenced(trans_task_id: &i64, fence_token: &i64) => "`where trans_task_id = #{trans_task_id} and fence_token <= #{fence_token} and deleted_by is null and deleted_date is null`"}
+);
 impl_delete!(
     TransTask{delete_by_id(trans_task_id: &i64) => "`where trans_task_id = #{trans_task_id} and deleted_by is null and deleted_date is null`"}
 );
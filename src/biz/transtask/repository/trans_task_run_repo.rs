@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use rbatis::{
+    RBatis, crud, impl_select_page, impl_update,
+    plugin::page::{Page, PageRequest},
+};
+use rbdc::db::ExecResult;
+
+use crate::biz::transtask::model::trans_task_run::TransTaskRun;
+
+#[derive(Clone)]
+pub struct TransTaskRunRepository {
+    rb: Arc<RBatis>,
+}
+
+impl TransTaskRunRepository {
+    pub fn new(rb: Arc<RBatis>) -> Self {
+        Self { rb }
+    }
+
+    pub async fn select_page_by_trans_task_id(
+        &self,
+        page_request: &PageRequest,
+        trans_task_id: &i64,
+    ) -> Result<Page<TransTaskRun>, rbatis::Error> {
+        TransTaskRun::select_page_by_trans_task_id(
+            &*self.rb,
+            page_request,
+            trans_task_id,
+        )
+        .await
+    }
+
+    pub async fn insert(
+        &self,
+        trans_task_run: &TransTaskRun,
+    ) -> Result<ExecResult, rbatis::Error> {
+        TransTaskRun::insert(&*self.rb, trans_task_run).await
+    }
+
+    pub async fn update_by_id(
+        &self,
+        trans_task_run: &TransTaskRun,
+        trans_task_run_id: &i64,
+    ) -> Result<ExecResult, rbatis::Error> {
+        TransTaskRun::update_by_id(&*self.rb, trans_task_run, trans_task_run_id)
+            .await
+    }
+}
+
+crud!(TransTaskRun {});
+impl_select_page!(
+    TransTaskRun{select_page_by_trans_task_id(trans_task_id: &i64) => "`where trans_task_id = #{trans_task_id} and deleted_by is null and deleted_date is null order by start_time desc`"}
+);
+impl_update!(
+    TransTaskRun{update_by_id(trans_task_run_id: &i64) => "`where trans_task_run_id = #{trans_task_run_id} and deleted_by is null and deleted_date is null`"}
+);
@@ -2,14 +2,34 @@ use std::sync::Arc;
 
 use axum::{
     Router,
-    routing::{delete, get},
+    routing::{delete, get, post},
 };
 
 use crate::{
     app::AppState,
-    biz::transtask::handler::trans_task_handler::{
-        create_trans_task, delete_trans_task, get_trans_task_by_id,
-        list_trans_task, true_delete_trans_task, update_trans_task,
+    biz::transtask::handler::{
+        trans_dag_handler::{
+            get_trans_dag, list_trans_task_dependency, run_trans_dag,
+            save_trans_task_dependency,
+        },
+        trans_task_column_handler::{
+            generate_trans_task_column, list_trans_task_column,
+            list_type_mapping, save_trans_task_column,
+        },
+        trans_task_handler::{
+            backfill_trans_task, bulk_create_trans_task, cancel_trans_task,
+            create_trans_task, delete_trans_task, get_trans_task_by_id,
+            list_trans_task, next_runs_trans_task, pause_trans_task,
+            plan_trans_task, progress_trans_task, reconcile_trans_task,
+            resume_trans_task, run_trans_task, true_delete_trans_task,
+            update_trans_task,
+        },
+        trans_task_quality_handler::{
+            list_trans_task_quality_result_by_run,
+            list_trans_task_quality_rule, page_trans_task_quality_result,
+            save_trans_task_quality_rule,
+        },
+        trans_task_run_handler::page_trans_task_run,
     },
 };
 
@@ -25,5 +45,43 @@ pub fn trans_task_route() -> Router<Arc<AppState>> {
             "/transtask/{id}",
             get(get_trans_task_by_id).delete(delete_trans_task),
         )
+        .route("/transtask/bulk", post(bulk_create_trans_task))
         .route("/transtask/delete/{id}", delete(true_delete_trans_task))
+        .route("/transtask/run/{id}", post(run_trans_task))
+        .route("/transtask/{id}/runs", get(page_trans_task_run))
+        .route("/transtask/{id}/next_runs", get(next_runs_trans_task))
+        .route("/transtask/{id}/progress", get(progress_trans_task))
+        .route("/transtask/{id}/cancel", post(cancel_trans_task))
+        .route("/transtask/{id}/pause", post(pause_trans_task))
+        .route("/transtask/{id}/resume", post(resume_trans_task))
+        .route("/transtask/{id}/plan", post(plan_trans_task))
+        .route("/transtask/{id}/backfill", post(backfill_trans_task))
+        .route("/transtask/{id}/reconcile", post(reconcile_trans_task))
+        .route(
+            "/transtask/{id}/columns",
+            get(list_trans_task_column).put(save_trans_task_column),
+        )
+        .route(
+            "/transtask/{id}/columns/generate",
+            post(generate_trans_task_column),
+        )
+        .route("/transtask/type_mappings", get(list_type_mapping))
+        .route(
+            "/transtask/{id}/quality_rules",
+            get(list_trans_task_quality_rule).put(save_trans_task_quality_rule),
+        )
+        .route(
+            "/transtask/{id}/quality_results",
+            get(page_trans_task_quality_result),
+        )
+        .route(
+            "/transtask/runs/{id}/quality_results",
+            get(list_trans_task_quality_result_by_run),
+        )
+        .route(
+            "/transtask/{id}/dependencies",
+            get(list_trans_task_dependency).put(save_trans_task_dependency),
+        )
+        .route("/transtask/dag", get(get_trans_dag))
+        .route("/transtask/dag/run", post(run_trans_dag))
 }
@@ -0,0 +1,1220 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, io,
+    time::Duration,
+};
+
+use chrono::{DateTime, Datelike, Timelike};
+use duckdb::{Connection, Transaction, appender_params_from_iter};
+use r2d2_mysql::mysql::{
+    BinlogRequest, Row, Value as MyValue,
+    binlog::{
+        events::{EventData, RowsEventData, TableMapEvent},
+        row::BinlogRow,
+        value::BinlogValue,
+    },
+    prelude::Queryable,
+};
+
+use crate::{
+    biz::{
+        datasource::{
+            model::data_source::DataSourceDetailVo,
+            service::data_source_service::DataSourceService,
+        },
+        transtask::{
+            model::trans_task::{PARQUET_EXPORT_TABLE, TransTask},
+            service::{
+                parquet_export::ParquetExport,
+                quality_check::{self, QualityCheck},
+                run_control::{RunControl, RunSignal},
+                trans_engine::{
+                    BATCH_SIZE, PROGRESS_ROWS, SourceColumn, TaskSettings,
+                    TransOutcome, apply_mappings, create_table_sql,
+                    duck_columns, duck_ident, key_join, kill_query,
+                    load_columns, load_primary_key, mysql_columns, mysql_ident,
+                    value_size,
+                },
+                trans_progress::ProgressReporter,
+            },
+        },
+    },
+    error::error::AppError,
+    util::redis_lock::LockHolder,
+};
+
+// 累计变更的主键数达到该值时，在事务边界处写入目标表
+const CDC_FLUSH_KEYS: usize = 10_000;
+
+// 作为复制客户端连接源库时使用的server_id基数，加上任务ID保证各任务互不冲突，
+// 需与源库及其副本的server_id错开
+const CDC_SERVER_ID_BASE: u32 = 1_000_000;
+
+// 读取binlog的超时，超时视为连接异常
+const BINLOG_READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+// binlog位置，file与position指向事务边界
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BinlogPosition {
+    pub file: String,
+    pub position: u64,
+    pub gtid_set: Option<String>,
+}
+
+impl BinlogPosition {
+    pub fn of_task(task: &TransTask) -> Option<Self> {
+        Some(Self {
+            file: task.binlog_file.clone()?,
+            position: task.binlog_position? as u64,
+            gtid_set: task.gtid_set.clone(),
+        })
+    }
+
+    // 是否已到达 target：开启GTID时比较已执行的GTID集合，否则比较文件序号与偏移量
+    fn reached(&self, target: &BinlogPosition) -> bool {
+        if let (Some(current), Some(target)) =
+            (&self.gtid_set, &target.gtid_set)
+        {
+            return GtidSet::parse(current).contains(&GtidSet::parse(target));
+        }
+        match (binlog_sequence(&self.file), binlog_sequence(&target.file)) {
+            (Some(current), Some(target_sequence)) => {
+                (current, self.position) >= (target_sequence, target.position)
+            }
+            _ => {
+                (self.file.as_str(), self.position)
+                    >= (target.file.as_str(), target.position)
+            }
+        }
+    }
+}
+
+// binlog文件名的序号，如 mysql-bin.000123 为123。序号超过6位后位数增加，
+// 不能按字符串比较
+fn binlog_sequence(file: &str) -> Option<u64> {
+    file.rsplit_once('.')?.1.parse().ok()
+}
+
+// 源库binlog配置及当前写入位置
+struct SourceBinlog {
+    gtid_mode: bool,
+    current: BinlogPosition,
+}
+
+impl SourceBinlog {
+    fn load(source: &mut impl Queryable) -> Result<Self, AppError> {
+        let variables: HashMap<String, String> = source
+            .query_map(
+                "SHOW VARIABLES WHERE Variable_name IN \
+                 ('log_bin', 'binlog_format', 'binlog_row_image', 'gtid_mode')",
+                |(name, value): (String, String)| {
+                    (name.to_lowercase(), value.to_uppercase())
+                },
+            )?
+            .into_iter()
+            .collect();
+        let variable = |name: &str| {
+            variables.get(name).map(String::as_str).unwrap_or_default()
+        };
+        if variable("log_bin") != "ON" {
+            return Err(AppError::TransError(String::from(
+                "CDC要求源库开启binlog(log_bin=ON)",
+            )));
+        }
+        if variable("binlog_format") != "ROW" {
+            return Err(AppError::TransError(String::from(
+                "CDC要求源库使用行格式binlog(binlog_format=ROW)",
+            )));
+        }
+        // 更新与删除需要完整的行镜像，才能按主键定位并写入整行
+        if variable("binlog_row_image") != "FULL" {
+            return Err(AppError::TransError(String::from(
+                "CDC要求源库记录完整行镜像(binlog_row_image=FULL)",
+            )));
+        }
+
+        // MySQL 8.2起以 SHOW BINARY LOG STATUS 取代 SHOW MASTER STATUS
+        let status: Option<Row> =
+            match source.query_first("SHOW BINARY LOG STATUS") {
+                Ok(status) => status,
+                Err(_) => source.query_first("SHOW MASTER STATUS")?,
+            };
+        let status = status.ok_or_else(|| {
+            AppError::TransError(String::from(
+                "无法读取源库binlog位置, 需要 REPLICATION CLIENT 权限",
+            ))
+        })?;
+        let gtid_mode = variable("gtid_mode") == "ON";
+        let gtid_set = status
+            .get::<Option<String>, _>("Executed_Gtid_Set")
+            .flatten()
+            .filter(|_| gtid_mode)
+            .map(|gtid_set| GtidSet::parse(&gtid_set).to_string());
+        Ok(Self {
+            gtid_mode,
+            current: BinlogPosition {
+                file: status.get("File").unwrap_or_default(),
+                position: status.get("Position").unwrap_or_default(),
+                gtid_set,
+            },
+        })
+    }
+}
+
+// 一个主键对应的净变更，None 表示该行已删除
+struct Change {
+    key: Vec<MyValue>,
+    row: Option<Vec<MyValue>>,
+}
+
+// 基于MySQL行格式binlog的变更捕获：以复制客户端连接源库读取binlog，
+// 解码目标表的插入、更新、删除事件，按主键合并为净变更后写入DuckDB目标表。
+// 首次执行（或记录的binlog已被清理）时先记录当前位置再全量快照，随后从该位置重放，
+// 按主键覆盖与删除的重放是幂等的，因此快照期间发生的变更重放后结果与源端一致。
+// 每次执行读取到开始时源库的写入位置为止，全部变更与快照在同一事务中提交，
+// 提交后返回新的位置，由任务记录供下次执行继续
+pub struct BinlogCdc<'a> {
+    pub data_source: &'a DataSourceDetailVo,
+    pub task: &'a TransTask,
+    pub settings: &'a TaskSettings,
+    pub holder: &'a LockHolder,
+    pub control: &'a RunControl,
+    pub parquet_dir: &'a str,
+}
+
+// 本次执行中目标表的列及其在binlog行中的位置
+struct TableLayout {
+    columns: Vec<SourceColumn>,
+    // 各目标列在源表全部列中的序号，binlog行按源表列顺序排列
+    positions: Vec<usize>,
+    column_count: usize,
+    // 主键列在 columns 中的序号
+    key_indexes: Vec<usize>,
+    primary_key: Vec<SourceColumn>,
+}
+
+impl BinlogCdc<'_> {
+    // 该方法会阻塞当前线程，需在 spawn_blocking 中调用
+    pub fn run(
+        &self,
+        duck: &mut Connection,
+        progress: &mut ProgressReporter,
+    ) -> Result<TransOutcome, AppError> {
+        let data_source = self.data_source;
+        let task = self.task;
+        // 一个连接读取元数据与快照，一个用于中途停止时终止快照查询
+        let source_pool = DataSourceService::create_pool(data_source, 2)?;
+        let mut source = source_pool.get()?;
+        // binlog中的TIMESTAMP为UTC秒数，快照同样按UTC读取，两者换算一致，
+        // 不受源库会话时区与本机时区影响
+        source.query_drop("SET time_zone = '+00:00'")?;
+        let layout = self.load_layout(&mut *source)?;
+        let binlog = SourceBinlog::load(&mut *source)?;
+
+        // 记录的binlog文件已被清理时无法继续，重新快照
+        let resume = match BinlogPosition::of_task(task) {
+            Some(position)
+                if (binlog.gtid_mode && position.gtid_set.is_some())
+                    || binlog_exists(&mut *source, &position.file)? =>
+            {
+                Some(position)
+            }
+            Some(position) => {
+                tracing::warn!(
+                    "传输任务 {} 记录的binlog {} 已不存在, 重新快照",
+                    task.table_name,
+                    position.file
+                );
+                None
+            }
+            None => None,
+        };
+
+        let schema = &data_source.code;
+        duck.execute_batch(&format!(
+            "CREATE SCHEMA IF NOT EXISTS {}",
+            duck_ident(schema)
+        ))?;
+        let tx = duck.transaction()?;
+        let mut outcome = TransOutcome::default();
+        let from = match resume {
+            Some(position) => {
+                tx.execute_batch(&create_table_sql(
+                    schema,
+                    &task.table_name,
+                    &layout.columns,
+                    false,
+                ))?;
+                position
+            }
+            None => {
+                // 快照前的位置，快照之后从此处重放
+                let from = binlog.current.clone();
+                self.snapshot(
+                    &tx,
+                    &source_pool,
+                    &mut source,
+                    &layout,
+                    progress,
+                    &mut outcome,
+                )?;
+                from
+            }
+        };
+
+        // 读取到此刻源库的写入位置为止
+        let target = SourceBinlog::load(&mut *source)?.current;
+        let position = if from.reached(&target) {
+            from
+        } else {
+            self.stream(
+                &tx,
+                &layout,
+                binlog.gtid_mode,
+                from,
+                &target,
+                &mut outcome,
+            )?
+        };
+        progress.batch_written(&outcome);
+        outcome.batches = progress.batches();
+
+        // 暂停时只应用了部分变更，不做质量检查
+        if !outcome.paused && !self.settings.rules.is_empty() {
+            let check = QualityCheck {
+                conn: &tx,
+                schema,
+                task,
+            };
+            outcome.quality_results = check.run(&self.settings.rules)?;
+            if quality_check::requires_rollback(&outcome.quality_results) {
+                tx.rollback()?;
+                tracing::warn!(
+                    "传输任务 {} 数据质量检查未通过, 已回滚本次写入",
+                    task.table_name
+                );
+                outcome.rolled_back = true;
+                outcome.target_rows = task.row_count.max(0) as u64;
+                return Ok(outcome);
+            }
+        }
+        if !self.holder.is_held() {
+            return Err(AppError::TransError(format!(
+                "分布式锁租约已丢失, 放弃提交: {}, fencing token {}",
+                task.table_name,
+                self.holder.token()
+            )));
+        }
+        tx.commit()?;
+        outcome.binlog_position = Some(position);
+
+        if task.parquet_export == PARQUET_EXPORT_TABLE {
+            let export = ParquetExport {
+                base_dir: self.parquet_dir,
+                schema,
+                task,
+            };
+            if let Err(e) = export.export(duck, &task.table_name, true) {
+                tracing::error!(
+                    "传输任务 {} 导出Parquet失败: {:?}",
+                    task.table_name,
+                    e
+                );
+                outcome.export_error = Some(e.to_string());
+            }
+        }
+        outcome.target_rows = duck.query_row(
+            &format!(
+                "SELECT count(*) FROM {}.{}",
+                duck_ident(schema),
+                duck_ident(&task.table_name)
+            ),
+            [],
+            |row| row.get(0),
+        )?;
+
+        tracing::info!(
+            "CDC传输完成: {}.{} -> {}.{}, 读取 {} 行变更, 写入 {} 行, 删除 {} 行, 位置 {:?}",
+            data_source.db_name,
+            task.table_name,
+            schema,
+            task.table_name,
+            outcome.rows_read,
+            outcome.rows_written,
+            outcome.rows_deleted,
+            outcome.binlog_position
+        );
+        Ok(outcome)
+    }
+
+    fn load_layout(
+        &self,
+        source: &mut impl Queryable,
+    ) -> Result<TableLayout, AppError> {
+        let db_name = &self.data_source.db_name;
+        let table_name = &self.task.table_name;
+        let all_columns = load_columns(source, db_name, table_name)?;
+        if all_columns.is_empty() {
+            return Err(AppError::TransError(format!(
+                "源表不存在或没有列: {}.{}",
+                db_name, table_name
+            )));
+        }
+        let columns =
+            apply_mappings(all_columns.clone(), &self.settings.mappings)?;
+        let positions = columns
+            .iter()
+            .map(|column| {
+                all_columns
+                    .iter()
+                    .position(|source| source.name == column.name)
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        // 按主键合并变更，要求源表存在主键且列映射包含全部主键列
+        let key_names = load_primary_key(source, db_name, table_name)?;
+        let key_indexes = key_names
+            .iter()
+            .filter_map(|name| {
+                columns.iter().position(|column| &column.name == name)
+            })
+            .collect::<Vec<_>>();
+        if key_names.is_empty() || key_indexes.len() != key_names.len() {
+            return Err(AppError::TransError(format!(
+                "CDC要求源表存在主键且列映射包含全部主键列: {}.{}",
+                db_name, table_name
+            )));
+        }
+        Ok(TableLayout {
+            primary_key: key_indexes
+                .iter()
+                .map(|&i| columns[i].clone())
+                .collect(),
+            column_count: all_columns.len(),
+            columns,
+            positions,
+            key_indexes,
+        })
+    }
+
+    // 全量读取源表替换目标表，快照无法从中途继续，暂停时同样回滚
+    fn snapshot(
+        &self,
+        tx: &Transaction,
+        source_pool: &r2d2::Pool<r2d2_mysql::MySqlConnectionManager>,
+        source: &mut r2d2_mysql::mysql::Conn,
+        layout: &TableLayout,
+        progress: &mut ProgressReporter,
+        outcome: &mut TransOutcome,
+    ) -> Result<(), AppError> {
+        let schema = &self.data_source.code;
+        let table_name = &self.task.table_name;
+        tx.execute_batch(&create_table_sql(
+            schema,
+            table_name,
+            &layout.columns,
+            true,
+        ))?;
+        let mut appender = tx.appender_to_db(table_name, schema)?;
+        let connection_id = source.connection_id();
+        // 与常规传输一样使用二进制协议读取带类型的值，经相同的类型转换写入
+        let mut rows = source.exec_iter(
+            format!(
+                "SELECT {} FROM {}",
+                mysql_columns(&layout.columns),
+                mysql_ident(table_name)
+            ),
+            (),
+        )?;
+        let mut interrupted = None;
+        for row in rows.by_ref() {
+            match self.control.signal() {
+                RunSignal::Cancel => {
+                    interrupted = Some(AppError::TransCancelled)
+                }
+                RunSignal::Pause => interrupted = Some(AppError::TransPaused),
+                RunSignal::None => {}
+            }
+            if interrupted.is_some() {
+                break;
+            }
+            let values = row?.unwrap();
+            outcome.rows_read += 1;
+            outcome.bytes += values.iter().map(value_size).sum::<u64>();
+            appender.append_row(appender_params_from_iter(
+                values
+                    .into_iter()
+                    .zip(&layout.columns)
+                    .map(|(value, column)| column.to_duck_value(value)),
+            ))?;
+            outcome.rows_written += 1;
+            if outcome.rows_written.is_multiple_of(BATCH_SIZE) {
+                appender.flush()?;
+                progress.batch_written(outcome);
+            } else if outcome.rows_read.is_multiple_of(PROGRESS_ROWS) {
+                progress.rows_processed(outcome);
+            }
+        }
+        if let Some(e) = interrupted {
+            kill_query(source_pool, connection_id);
+            return Err(e);
+        }
+        drop(rows);
+        appender.flush()?;
+        tracing::info!(
+            "传输任务 {} CDC快照完成, {} 行",
+            table_name,
+            outcome.rows_written
+        );
+        Ok(())
+    }
+
+    // 从 from 读取binlog直到到达 target 或被暂停，返回已应用到的位置
+    fn stream(
+        &self,
+        tx: &Transaction,
+        layout: &TableLayout,
+        gtid_mode: bool,
+        from: BinlogPosition,
+        target: &BinlogPosition,
+        outcome: &mut TransOutcome,
+    ) -> Result<BinlogPosition, AppError> {
+        let server_id =
+            cdc_server_id(self.task.trans_task_id.unwrap_or_default())?;
+        let conn = DataSourceService::connect(
+            self.data_source,
+            Some(BINLOG_READ_TIMEOUT),
+        )?;
+        // 开启GTID时按已执行的GTID集合请求，源库切换后仍可继续
+        let request = match from.gtid_set.as_deref() {
+            Some(gtid_set) if gtid_mode => BinlogRequest::new(server_id)
+                .with_use_gtid(true)
+                .with_pos(4_u64)
+                .with_sids(
+                    gtid_set
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|sid| !sid.is_empty())
+                        .map(str::parse)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(binlog_error)?,
+                ),
+            _ => BinlogRequest::new(server_id)
+                .with_filename(from.file.as_bytes())
+                .with_pos(from.position),
+        };
+        let stream = conn.get_binlog_stream(request).map_err(|e| {
+            AppError::TransError(format!(
+                "读取binlog失败, 需要 REPLICATION SLAVE 权限: {}",
+                e
+            ))
+        })?;
+
+        let mut current = from.clone();
+        let mut gtid_set = current.gtid_set.as_deref().map(GtidSet::parse);
+        let mut committed = from;
+        let mut pending_gtid = None;
+        let mut tables: HashMap<u64, TableMapEvent<'static>> = HashMap::new();
+        let mut changes: HashMap<String, Change> = HashMap::new();
+        for event in stream {
+            if self.control.signal() == RunSignal::Cancel {
+                return Err(AppError::TransCancelled);
+            }
+            let event = event?;
+            let log_pos = event.header().log_pos();
+            let mut boundary = false;
+            match event.read_data().map_err(binlog_error)? {
+                Some(EventData::RotateEvent(rotate)) => {
+                    current.file = rotate.name().into_owned();
+                    current.position = rotate.position();
+                    continue;
+                }
+                Some(EventData::TableMapEvent(table_map))
+                    if table_map.database_name()
+                        == self.data_source.db_name
+                        && table_map.table_name() == self.task.table_name =>
+                {
+                    tables.insert(table_map.table_id(), table_map.into_owned());
+                }
+                Some(EventData::GtidEvent(gtid)) => {
+                    pending_gtid = Some((format_uuid(&gtid.sid()), gtid.gno()));
+                }
+                Some(EventData::RowsEvent(rows_event)) => {
+                    if let Some(table_map) = tables.get(&rows_event.table_id())
+                    {
+                        self.decode(
+                            &rows_event,
+                            table_map,
+                            layout,
+                            &mut changes,
+                            outcome,
+                        )?;
+                    }
+                }
+                Some(EventData::XidEvent(_)) => boundary = true,
+                Some(EventData::QueryEvent(query)) => {
+                    boundary = query.query().trim() != "BEGIN";
+                }
+                Some(EventData::TransactionPayloadEvent(_)) => {
+                    return Err(AppError::TransError(String::from(
+                        "CDC不支持压缩的binlog事务(binlog_transaction_compression=OFF)",
+                    )));
+                }
+                _ => {}
+            }
+            if log_pos > 0 {
+                current.position = log_pos as u64;
+            }
+            if !boundary {
+                continue;
+            }
+
+            // 事务提交处：记录位置，按需写入目标表并检查暂停与终点
+            if let (Some(set), Some((uuid, gno))) =
+                (gtid_set.as_mut(), pending_gtid.take())
+            {
+                set.add(&uuid, gno);
+            }
+            current.gtid_set = gtid_set.as_ref().map(GtidSet::to_string);
+            committed = current.clone();
+            if changes.len() >= CDC_FLUSH_KEYS {
+                self.flush(tx, layout, &mut changes, outcome)?;
+            }
+            if self.control.signal() == RunSignal::Pause {
+                outcome.paused = true;
+                break;
+            }
+            if committed.reached(target) {
+                break;
+            }
+        }
+        self.flush(tx, layout, &mut changes, outcome)?;
+        Ok(committed)
+    }
+
+    // 解码目标表的行事件并合并到按主键的净变更中
+    fn decode(
+        &self,
+        rows_event: &RowsEventData,
+        table_map: &TableMapEvent,
+        layout: &TableLayout,
+        changes: &mut HashMap<String, Change>,
+        outcome: &mut TransOutcome,
+    ) -> Result<(), AppError> {
+        if table_map.columns_count() as usize != layout.column_count {
+            return Err(AppError::TransError(format!(
+                "源表结构已变更, 请确认列映射后重新快照: {}",
+                self.task.table_name
+            )));
+        }
+        for row in rows_event.rows(table_map) {
+            let (before, after) = row.map_err(binlog_error)?;
+            outcome.rows_read += 1;
+            let before =
+                before.map(|row| self.row_values(row, layout)).transpose()?;
+            let after =
+                after.map(|row| self.row_values(row, layout)).transpose()?;
+            if let Some(before) = before {
+                let key = layout.key(&before);
+                // 更新时主键未变化则由新行直接覆盖
+                let key_changed =
+                    after.as_ref().is_none_or(|after| layout.key(after) != key);
+                if key_changed {
+                    changes.insert(key_string(&key), Change { key, row: None });
+                }
+            }
+            if let Some(after) = after {
+                let key = layout.key(&after);
+                changes.insert(
+                    key_string(&key),
+                    Change {
+                        key,
+                        row: Some(after),
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn row_values(
+        &self,
+        mut row: BinlogRow,
+        layout: &TableLayout,
+    ) -> Result<Vec<MyValue>, AppError> {
+        layout
+            .columns
+            .iter()
+            .zip(&layout.positions)
+            .map(|(column, &position)| match row.take(position) {
+                Some(BinlogValue::Value(value)) => Ok(normalize(column, value)),
+                Some(BinlogValue::Jsonb(value)) => serde_json::Value::try_from(value)
+                    .map(|json| MyValue::Bytes(json.to_string().into_bytes()))
+                    .map_err(|e| {
+                        AppError::TransError(format!("解析binlog中的JSON失败: {}", e))
+                    }),
+                Some(BinlogValue::JsonDiff(_)) => Err(AppError::TransError(String::from(
+                    "CDC不支持部分JSON更新(binlog_row_value_options=PARTIAL_JSON)",
+                ))),
+                None => Err(AppError::TransError(format!(
+                    "binlog行镜像缺少列 {}, 需 binlog_row_image=FULL",
+                    column.name
+                ))),
+            })
+            .collect()
+    }
+
+    // 在事务中以暂存表替换目标表中变更主键对应的行
+    fn flush(
+        &self,
+        tx: &Transaction,
+        layout: &TableLayout,
+        changes: &mut HashMap<String, Change>,
+        outcome: &mut TransOutcome,
+    ) -> Result<(), AppError> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let schema = &self.data_source.code;
+        let table_name = &self.task.table_name;
+        let rows_table = format!("{}__cdc", table_name);
+        let keys_table = format!("{}__cdc_keys", table_name);
+        tx.execute_batch(&create_table_sql(
+            schema,
+            &rows_table,
+            &layout.columns,
+            true,
+        ))?;
+        tx.execute_batch(&create_table_sql(
+            schema,
+            &keys_table,
+            &layout.primary_key,
+            true,
+        ))?;
+        {
+            let mut rows_appender = tx.appender_to_db(&rows_table, schema)?;
+            let mut keys_appender = tx.appender_to_db(&keys_table, schema)?;
+            for change in changes.drain().map(|(_, change)| change) {
+                keys_appender.append_row(appender_params_from_iter(
+                    change
+                        .key
+                        .into_iter()
+                        .zip(&layout.primary_key)
+                        .map(|(value, column)| column.to_duck_value(value)),
+                ))?;
+                match change.row {
+                    Some(row) => {
+                        outcome.bytes +=
+                            row.iter().map(value_size).sum::<u64>();
+                        rows_appender.append_row(appender_params_from_iter(
+                            row.into_iter().zip(&layout.columns).map(
+                                |(value, column)| column.to_duck_value(value),
+                            ),
+                        ))?;
+                        outcome.rows_written += 1;
+                    }
+                    None => outcome.rows_deleted += 1,
+                }
+            }
+            rows_appender.flush()?;
+            keys_appender.flush()?;
+        }
+        tx.execute(
+            &format!(
+                "DELETE FROM {}.{} USING {}.{} WHERE {}",
+                duck_ident(schema),
+                duck_ident(table_name),
+                duck_ident(schema),
+                duck_ident(&keys_table),
+                key_join(&layout.primary_key, table_name, &keys_table)
+            ),
+            [],
+        )?;
+        tx.execute_batch(&format!(
+            "INSERT INTO {}.{} ({}) SELECT {} FROM {}.{}; \
+             DROP TABLE {}.{}; DROP TABLE {}.{}",
+            duck_ident(schema),
+            duck_ident(table_name),
+            duck_columns(&layout.columns),
+            duck_columns(&layout.columns),
+            duck_ident(schema),
+            duck_ident(&rows_table),
+            duck_ident(schema),
+            duck_ident(&rows_table),
+            duck_ident(schema),
+            duck_ident(&keys_table)
+        ))?;
+        Ok(())
+    }
+}
+
+impl TableLayout {
+    fn key(&self, values: &[MyValue]) -> Vec<MyValue> {
+        self.key_indexes
+            .iter()
+            .map(|&i| values[i].clone())
+            .collect()
+    }
+}
+
+fn key_string(key: &[MyValue]) -> String {
+    key.iter()
+        .map(|value| value.as_sql(true))
+        .collect::<Vec<_>>()
+        .join("\u{1}")
+}
+
+fn binlog_exists(
+    source: &mut impl Queryable,
+    file: &str,
+) -> Result<bool, AppError> {
+    let files: Vec<String> = source
+        .query_map("SHOW BINARY LOGS", |row: Row| {
+            row.get::<String, _>(0).unwrap_or_default()
+        })?;
+    Ok(files.iter().any(|name| name == file))
+}
+
+// 任务作为复制客户端使用的server_id。server_id 取值为 1..=u32::MAX，
+// 任务ID超出范围时报错，不截断，避免与其他任务冲突
+fn cdc_server_id(trans_task_id: i64) -> Result<u32, AppError> {
+    u32::try_from(trans_task_id)
+        .ok()
+        .and_then(|id| CDC_SERVER_ID_BASE.checked_add(id))
+        .ok_or_else(|| {
+            AppError::TransError(format!(
+                "任务ID {} 超出复制server_id的取值范围",
+                trans_task_id
+            ))
+        })
+}
+
+fn binlog_error(e: io::Error) -> AppError {
+    AppError::TransError(format!("解析binlog失败: {}", e))
+}
+
+// 把binlog中与查询结果表示不同的值转换为查询结果的表示，以复用相同的类型转换
+fn normalize(column: &SourceColumn, value: MyValue) -> MyValue {
+    match (column.data_type.as_str(), value) {
+        // TIMESTAMP在binlog中为UTC秒数（可带小数），快照的会话时区为UTC
+        ("timestamp", MyValue::Int(seconds)) => utc_datetime(seconds, 0),
+        ("timestamp", MyValue::Bytes(bytes)) => {
+            let text = String::from_utf8_lossy(&bytes);
+            let (seconds, fraction) =
+                text.split_once('.').unwrap_or((&text, "0"));
+            utc_datetime(
+                seconds.parse().unwrap_or_default(),
+                format!("{:0<6}", fraction).parse().unwrap_or_default(),
+            )
+        }
+        // ENUM在binlog中为从1开始的序号，0表示空字符串
+        ("enum", MyValue::Int(index)) => {
+            let labels = type_labels(&column.column_type);
+            let label = (index as usize)
+                .checked_sub(1)
+                .and_then(|i| labels.get(i))
+                .cloned()
+                .unwrap_or_default();
+            MyValue::Bytes(label.into_bytes())
+        }
+        // SET在binlog中为小端位图
+        ("set", MyValue::Bytes(bits)) => {
+            let labels = type_labels(&column.column_type)
+                .into_iter()
+                .enumerate()
+                .filter(|(i, _)| {
+                    bits.get(i / 8)
+                        .is_some_and(|byte| byte & (1 << (i % 8)) != 0)
+                })
+                .map(|(_, label)| label)
+                .collect::<Vec<_>>();
+            MyValue::Bytes(labels.join(",").into_bytes())
+        }
+        ("year", MyValue::Bytes(bytes)) => String::from_utf8_lossy(&bytes)
+            .parse::<i64>()
+            .map(MyValue::Int)
+            .unwrap_or(MyValue::NULL),
+        // 缺少符号元数据时无符号列按有符号解码，按列宽还原
+        (data_type, MyValue::Int(v))
+            if v < 0 && column.column_type.contains("unsigned") =>
+        {
+            match data_type {
+                "tinyint" => MyValue::UInt(v as u8 as u64),
+                "smallint" => MyValue::UInt(v as u16 as u64),
+                "mediumint" => MyValue::UInt(v as u64 & 0x00ff_ffff),
+                "int" | "integer" => MyValue::UInt(v as u32 as u64),
+                _ => MyValue::UInt(v as u64),
+            }
+        }
+        (_, value) => value,
+    }
+}
+
+fn utc_datetime(seconds: i64, micros: u32) -> MyValue {
+    // 零值时间戳与查询结果一致按NULL处理
+    if seconds == 0 && micros == 0 {
+        return MyValue::NULL;
+    }
+    match DateTime::from_timestamp(seconds, micros * 1000) {
+        Some(datetime) => MyValue::Date(
+            datetime.year() as u16,
+            datetime.month() as u8,
+            datetime.day() as u8,
+            datetime.hour() as u8,
+            datetime.minute() as u8,
+            datetime.second() as u8,
+            micros,
+        ),
+        None => MyValue::NULL,
+    }
+}
+
+// 解析 enum('a','b') / set('a','b') 中的取值
+fn type_labels(column_type: &str) -> Vec<String> {
+    let Some(start) = column_type.find('(') else {
+        return Vec::new();
+    };
+    let mut labels = Vec::new();
+    let mut chars = column_type[start + 1..].chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\'' {
+            continue;
+        }
+        let mut label = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' if chars.peek() == Some(&'\'') => {
+                    chars.next();
+                    label.push('\'');
+                }
+                '\'' => break,
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        label.push(escaped);
+                    }
+                }
+                c => label.push(c),
+            }
+        }
+        labels.push(label);
+    }
+    labels
+}
+
+fn format_uuid(sid: &[u8; 16]) -> String {
+    let hex = sid
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+// GTID集合，形如 uuid:1-5:7,uuid2:1-3，区间为闭区间
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct GtidSet(BTreeMap<String, Vec<(u64, u64)>>);
+
+impl GtidSet {
+    // 忽略无法解析的部分
+    fn parse(value: &str) -> Self {
+        let mut set = Self::default();
+        for sid in value
+            .split(',')
+            .map(str::trim)
+            .filter(|sid| !sid.is_empty())
+        {
+            let mut parts = sid.split(':');
+            let uuid = parts.next().unwrap_or_default().to_lowercase();
+            for interval in parts {
+                let (start, end) =
+                    interval.split_once('-').unwrap_or((interval, interval));
+                if let (Ok(start), Ok(end)) = (start.parse(), end.parse()) {
+                    set.add_interval(&uuid, start, end);
+                }
+            }
+        }
+        set
+    }
+
+    fn add(&mut self, uuid: &str, gno: u64) {
+        self.add_interval(uuid, gno, gno);
+    }
+
+    fn add_interval(&mut self, uuid: &str, start: u64, end: u64) {
+        let intervals = self.0.entry(uuid.to_string()).or_default();
+        intervals.push((start, end));
+        intervals.sort_unstable();
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(intervals.len());
+        for &(start, end) in intervals.iter() {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => {
+                    last.1 = last.1.max(end)
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+        *intervals = merged;
+    }
+
+    fn contains(&self, other: &GtidSet) -> bool {
+        other.0.iter().all(|(uuid, intervals)| {
+            let own = self.0.get(uuid).map(Vec::as_slice).unwrap_or_default();
+            intervals.iter().all(|&(start, end)| {
+                own.iter().any(|&(own_start, own_end)| {
+                    own_start <= start && end <= own_end
+                })
+            })
+        })
+    }
+}
+
+impl fmt::Display for GtidSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sids = self
+            .0
+            .iter()
+            .map(|(uuid, intervals)| {
+                let intervals = intervals
+                    .iter()
+                    .map(|&(start, end)| {
+                        if start == end {
+                            start.to_string()
+                        } else {
+                            format!("{}-{}", start, end)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                format!("{}:{}", uuid, intervals.join(":"))
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", sids.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(data_type: &str, column_type: &str) -> SourceColumn {
+        SourceColumn::new(
+            String::from("c"),
+            data_type.to_string(),
+            column_type.to_string(),
+            None,
+            None,
+        )
+    }
+
+    fn position(file: &str, position: u64) -> BinlogPosition {
+        BinlogPosition {
+            file: file.to_string(),
+            position,
+            gtid_set: None,
+        }
+    }
+
+    const UUID: &str = "3e11fa47-71ca-11e1-9e33-c80aa9429562";
+
+    #[test]
+    fn gtid_set_parse_and_display() {
+        let set = GtidSet::parse(
+            " 3E11FA47-71CA-11E1-9E33-C80AA9429562:1-5:7:6, \
+             aaaaaaaa-0000-0000-0000-000000000000:3 ,",
+        );
+        assert_eq!(
+            set.to_string(),
+            "3e11fa47-71ca-11e1-9e33-c80aa9429562:1-7,\
+             aaaaaaaa-0000-0000-0000-000000000000:3"
+        );
+        assert_eq!(GtidSet::parse(""), GtidSet::default());
+        assert_eq!(
+            GtidSet::parse(&format!("{}:x-3:9", UUID)).to_string(),
+            format!("{}:9", UUID)
+        );
+    }
+
+    #[test]
+    fn gtid_set_add_merges_adjacent_intervals() {
+        let mut set = GtidSet::default();
+        set.add(UUID, 3);
+        set.add(UUID, 1);
+        assert_eq!(set.to_string(), format!("{}:1:3", UUID));
+        set.add(UUID, 2);
+        assert_eq!(set.to_string(), format!("{}:1-3", UUID));
+        set.add(UUID, 3);
+        set.add_interval(UUID, 10, 12);
+        set.add_interval(UUID, 4, 9);
+        assert_eq!(set.to_string(), format!("{}:1-12", UUID));
+    }
+
+    #[test]
+    fn gtid_set_contains() {
+        let executed = GtidSet::parse(&format!("{}:1-10:20-30", UUID));
+        assert!(executed.contains(&GtidSet::default()));
+        assert!(executed.contains(&GtidSet::parse(&format!("{}:2-5", UUID))));
+        assert!(
+            executed.contains(&GtidSet::parse(&format!("{}:1-10:25", UUID)))
+        );
+        assert!(!executed.contains(&GtidSet::parse(&format!("{}:9-21", UUID))));
+        assert!(!executed.contains(&GtidSet::parse(&format!("{}:31", UUID))));
+        assert!(!executed.contains(&GtidSet::parse(
+            "aaaaaaaa-0000-0000-0000-000000000000:1"
+        )));
+    }
+
+    #[test]
+    fn reached_compares_binlog_sequence_numerically() {
+        assert!(
+            position("mysql-bin.1000000", 4)
+                .reached(&position("mysql-bin.999999", 900))
+        );
+        assert!(
+            !position("mysql-bin.999999", 900)
+                .reached(&position("mysql-bin.1000000", 4))
+        );
+        assert!(
+            position("mysql-bin.000002", 120)
+                .reached(&position("mysql-bin.000002", 120))
+        );
+        assert!(
+            !position("mysql-bin.000002", 119)
+                .reached(&position("mysql-bin.000002", 120))
+        );
+        assert_eq!(binlog_sequence("binlog"), None);
+    }
+
+    #[test]
+    fn reached_prefers_gtid_set() {
+        let mut current = position("mysql-bin.000001", 4);
+        current.gtid_set = Some(format!("{}:1-10", UUID));
+        let mut target = position("mysql-bin.000009", 900);
+        target.gtid_set = Some(format!("{}:1-10", UUID));
+        assert!(current.reached(&target));
+        target.gtid_set = Some(format!("{}:1-11", UUID));
+        assert!(!current.reached(&target));
+    }
+
+    #[test]
+    fn server_ids_are_distinct_and_in_range() {
+        assert_eq!(cdc_server_id(1).unwrap(), CDC_SERVER_ID_BASE + 1);
+        assert_ne!(cdc_server_id(1).unwrap(), cdc_server_id(2).unwrap());
+        assert_eq!(
+            cdc_server_id(i64::from(u32::MAX - CDC_SERVER_ID_BASE)).unwrap(),
+            u32::MAX
+        );
+        assert!(
+            cdc_server_id(i64::from(u32::MAX - CDC_SERVER_ID_BASE) + 1)
+                .is_err()
+        );
+        assert!(cdc_server_id(i64::from(u32::MAX) + 1).is_err());
+        assert!(cdc_server_id(-1).is_err());
+    }
+
+    #[test]
+    fn type_labels_handles_quotes_and_escapes() {
+        assert_eq!(type_labels("enum('a','b c','')"), vec!["a", "b c", ""]);
+        assert_eq!(
+            type_labels(r"set('it''s','back\\slash','x,y')"),
+            vec!["it's", r"back\slash", "x,y"]
+        );
+        assert!(type_labels("varchar").is_empty());
+    }
+
+    #[test]
+    fn normalize_enum_and_set() {
+        let enum_column = column("enum", "enum('small','medium','large')");
+        let text = |value| match normalize(&enum_column, value) {
+            MyValue::Bytes(bytes) => String::from_utf8(bytes).unwrap(),
+            value => panic!("unexpected value: {:?}", value),
+        };
+        assert_eq!(text(MyValue::Int(1)), "small");
+        assert_eq!(text(MyValue::Int(3)), "large");
+        assert_eq!(text(MyValue::Int(0)), "");
+        assert_eq!(text(MyValue::Int(4)), "");
+
+        let labels = (0..10).map(|i| format!("'v{}'", i)).collect::<Vec<_>>();
+        let set_column = column("set", &format!("set({})", labels.join(",")));
+        assert_eq!(
+            normalize(&set_column, MyValue::Bytes(vec![0b0000_0101, 0b10])),
+            MyValue::Bytes(b"v0,v2,v9".to_vec())
+        );
+        assert_eq!(
+            normalize(&set_column, MyValue::Bytes(vec![0])),
+            MyValue::Bytes(Vec::new())
+        );
+    }
+
+    #[test]
+    fn normalize_year_and_unsigned() {
+        assert_eq!(
+            normalize(
+                &column("year", "year"),
+                MyValue::Bytes(b"2024".to_vec())
+            ),
+            MyValue::Int(2024)
+        );
+        assert_eq!(
+            normalize(&column("tinyint", "tinyint unsigned"), MyValue::Int(-1)),
+            MyValue::UInt(255)
+        );
+        assert_eq!(
+            normalize(
+                &column("smallint", "smallint(5) unsigned"),
+                MyValue::Int(-2)
+            ),
+            MyValue::UInt(65534)
+        );
+        assert_eq!(
+            normalize(
+                &column("mediumint", "mediumint unsigned"),
+                MyValue::Int(-1)
+            ),
+            MyValue::UInt(16_777_215)
+        );
+        assert_eq!(
+            normalize(&column("int", "int unsigned"), MyValue::Int(-1)),
+            MyValue::UInt(4_294_967_295)
+        );
+        assert_eq!(
+            normalize(&column("bigint", "bigint unsigned"), MyValue::Int(-1)),
+            MyValue::UInt(u64::MAX)
+        );
+        assert_eq!(
+            normalize(&column("int", "int"), MyValue::Int(-1)),
+            MyValue::Int(-1)
+        );
+    }
+
+    #[test]
+    fn normalize_timestamp_in_utc() {
+        let timestamp = column("timestamp", "timestamp(6)");
+        // 2024-03-10 07:30:00 UTC
+        assert_eq!(
+            normalize(&timestamp, MyValue::Int(1_710_055_800)),
+            MyValue::Date(2024, 3, 10, 7, 30, 0, 0)
+        );
+        assert_eq!(
+            normalize(&timestamp, MyValue::Bytes(b"1710055800.25".to_vec())),
+            MyValue::Date(2024, 3, 10, 7, 30, 0, 250_000)
+        );
+        assert_eq!(
+            normalize(
+                &timestamp,
+                MyValue::Bytes(b"1710055800.000123".to_vec())
+            ),
+            MyValue::Date(2024, 3, 10, 7, 30, 0, 123)
+        );
+        assert_eq!(normalize(&timestamp, MyValue::Int(0)), MyValue::NULL);
+        assert_eq!(
+            normalize(&timestamp, MyValue::Bytes(b"0.000000".to_vec())),
+            MyValue::NULL
+        );
+    }
+}
@@ -1 +1,18 @@
+pub mod binlog_cdc;
+pub mod parquet_export;
+pub mod quality_check;
+pub mod run_control;
+pub mod source_query;
+pub mod trans_chunk;
+pub mod trans_dag;
+pub mod trans_dag_service;
+pub mod trans_engine;
+pub mod trans_plan;
+pub mod trans_progress;
+pub mod trans_reconcile;
+pub mod trans_task_column_service;
+pub mod trans_task_quality_service;
+pub mod trans_task_run_service;
+pub mod trans_task_scheduler;
 pub mod trans_task_service;
+pub mod type_mapping;
@@ -0,0 +1,116 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use duckdb::Connection;
+
+use crate::{
+    biz::transtask::{
+        model::trans_task::TransTask,
+        service::trans_engine::{duck_ident, duck_string},
+    },
+    error::error::AppError,
+    util::path_util,
+};
+
+// 将DuckDB中的表导出为Parquet文件，目录结构为 {导出根目录}/{schema}/{表名}/，
+// 按分区列导出时为 Hive 风格的 {列名}={值}/ 子目录。
+// 导出后创建 {表名}__parquet 视图，可直接在DuckDB中查询已导出的全部文件
+pub struct ParquetExport<'a> {
+    pub base_dir: &'a str,
+    pub schema: &'a str,
+    pub task: &'a TransTask,
+}
+
+impl ParquetExport<'_> {
+    // schema 与表名各自只能是一个普通路径段，防止目录越出导出根目录
+    fn table_dir(&self) -> Result<PathBuf, AppError> {
+        for name in [self.schema, self.task.table_name.as_str()] {
+            let path = Path::new(name);
+            if !path_util::is_relative_normal(path)
+                || path.components().count() != 1
+            {
+                return Err(AppError::TransError(format!(
+                    "Parquet导出目录名无效: {}",
+                    name
+                )));
+            }
+        }
+        Ok(PathBuf::from(self.base_dir)
+            .join(self.schema)
+            .join(&self.task.table_name))
+    }
+
+    // 导出 source_table 的全部行；replace 为true时先清空目标表已导出的文件
+    pub fn export(
+        &self,
+        conn: &Connection,
+        source_table: &str,
+        replace: bool,
+    ) -> Result<PathBuf, AppError> {
+        let table_dir = self.table_dir()?;
+        // 删除前按规范化路径（解析符号链接）再确认仍在导出根目录下
+        if replace && table_dir.exists() {
+            let within =
+                path_util::is_within(Path::new(self.base_dir), &table_dir)
+                    .map_err(|e| {
+                        AppError::TransError(format!(
+                            "解析Parquet目录失败: {}",
+                            e
+                        ))
+                    })?;
+            if !within {
+                return Err(AppError::TransError(format!(
+                    "Parquet目录不在导出根目录下: {}",
+                    table_dir.display()
+                )));
+            }
+            fs::remove_dir_all(&table_dir).map_err(|e| {
+                AppError::TransError(format!("清理Parquet目录失败: {}", e))
+            })?;
+        }
+        fs::create_dir_all(&table_dir).map_err(|e| {
+            AppError::TransError(format!("创建Parquet目录失败: {}", e))
+        })?;
+
+        let mut options = vec![
+            String::from("FORMAT PARQUET"),
+            format!("COMPRESSION {}", self.task.parquet_compression),
+            format!("ROW_GROUP_SIZE {}", self.task.parquet_row_group_size),
+        ];
+        // 每次导出的文件名唯一，追加导出时不会覆盖已有文件
+        let target = match &self.task.parquet_partition_by {
+            Some(partition_by) => {
+                options.push(format!(
+                    "PARTITION_BY ({})",
+                    duck_ident(partition_by)
+                ));
+                options.push(String::from("FILENAME_PATTERN 'part_{uuid}'"));
+                options.push(String::from("OVERWRITE_OR_IGNORE true"));
+                table_dir.clone()
+            }
+            None => table_dir.join(format!(
+                "part_{}.parquet",
+                Local::now().format("%Y%m%d%H%M%S%3f")
+            )),
+        };
+        conn.execute_batch(&format!(
+            "COPY (SELECT * FROM {}.{}) TO {} ({})",
+            duck_ident(self.schema),
+            duck_ident(source_table),
+            duck_string(&target.to_string_lossy()),
+            options.join(", ")
+        ))?;
+
+        conn.execute_batch(&format!(
+            "CREATE OR REPLACE VIEW {}.{} AS SELECT * FROM read_parquet({}, hive_partitioning = {})",
+            duck_ident(self.schema),
+            duck_ident(&format!("{}__parquet", self.task.table_name)),
+            duck_string(&table_dir.join("**").join("*.parquet").to_string_lossy()),
+            self.task.parquet_partition_by.is_some()
+        ))?;
+        Ok(table_dir)
+    }
+}
@@ -0,0 +1,275 @@
+use chrono::{Duration, Local};
+use duckdb::Connection;
+
+use crate::{
+    biz::transtask::{
+        model::{
+            trans_task::TransTask,
+            trans_task_quality_result::TransTaskQualityResult,
+            trans_task_quality_rule::{
+                ON_FAILURE_ROLLBACK, ON_FAILURE_WARN,
+                RULE_TYPE_ACCEPTED_VALUES, RULE_TYPE_CUSTOM_SQL,
+                RULE_TYPE_FRESHNESS, RULE_TYPE_NOT_NULL, RULE_TYPE_RANGE,
+                RULE_TYPE_ROW_COUNT_DELTA, RULE_TYPE_UNIQUE,
+                TransTaskQualityRule,
+            },
+        },
+        service::trans_engine::{duck_ident, duck_string},
+    },
+    error::error::AppError,
+};
+
+// 单条规则的检查结论
+struct Verdict {
+    passed: bool,
+    observed_value: Option<String>,
+    message: Option<String>,
+}
+
+impl Verdict {
+    // 以违反规则的行数为实际值，为0时通过
+    fn violations(count: i64, what: &str) -> Self {
+        Self {
+            passed: count == 0,
+            observed_value: Some(count.to_string()),
+            message: (count > 0).then(|| format!("{} {} 行", what, count)),
+        }
+    }
+}
+
+// 质量检查：加载完成、提交之前在同一事务中对目标表逐条检查启用的规则。
+// DuckDB中语句出错会使事务失效，规则本身执行出错（列不存在、类型无法转换等）
+// 时整次执行失败并回滚
+pub struct QualityCheck<'a> {
+    pub conn: &'a Connection,
+    pub schema: &'a str,
+    pub task: &'a TransTask,
+}
+
+impl QualityCheck<'_> {
+    pub fn run(
+        &self,
+        rules: &[TransTaskQualityRule],
+    ) -> Result<Vec<TransTaskQualityResult>, AppError> {
+        let mut results = Vec::new();
+        for rule in rules.iter().filter(|rule| rule.enabled) {
+            let verdict = self.check(rule).map_err(|e| {
+                AppError::QualityCheckFailed(format!(
+                    "规则 {} 执行失败: {}",
+                    rule.rule_name, e
+                ))
+            })?;
+            results.push(TransTaskQualityResult::new(
+                rule,
+                verdict.passed,
+                verdict.observed_value,
+                verdict.message,
+            ));
+        }
+        Ok(results)
+    }
+
+    fn table(&self) -> String {
+        format!(
+            "{}.{}",
+            duck_ident(self.schema),
+            duck_ident(&self.task.table_name)
+        )
+    }
+
+    fn count(&self, condition: &str) -> Result<i64, AppError> {
+        Ok(self.conn.query_row(
+            &format!(
+                "SELECT count(*) FROM {} WHERE {}",
+                self.table(),
+                condition
+            ),
+            [],
+            |row| row.get(0),
+        )?)
+    }
+
+    fn check(&self, rule: &TransTaskQualityRule) -> Result<Verdict, AppError> {
+        let column =
+            duck_ident(rule.column_name.as_deref().unwrap_or_default());
+        match rule.rule_type.as_str() {
+            RULE_TYPE_NOT_NULL => Ok(Verdict::violations(
+                self.count(&format!("{} IS NULL", column))?,
+                "存在NULL值",
+            )),
+            RULE_TYPE_UNIQUE => {
+                let columns = rule
+                    .column_name
+                    .as_deref()
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(|column| duck_ident(column.trim()))
+                    .collect::<Vec<_>>();
+                let not_null = columns
+                    .iter()
+                    .map(|column| format!("{} IS NOT NULL", column))
+                    .collect::<Vec<_>>();
+                let duplicates: i64 = self.conn.query_row(
+                    &format!(
+                        "SELECT count(*) FROM (SELECT {} FROM {} WHERE {} \
+                         GROUP BY ALL HAVING count(*) > 1)",
+                        columns.join(", "),
+                        self.table(),
+                        not_null.join(" AND ")
+                    ),
+                    [],
+                    |row| row.get(0),
+                )?;
+                Ok(Verdict::violations(duplicates, "重复值"))
+            }
+            RULE_TYPE_ACCEPTED_VALUES => {
+                let values = rule
+                    .accepted_values
+                    .as_deref()
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(|value| duck_string(value.trim()))
+                    .collect::<Vec<_>>();
+                Ok(Verdict::violations(
+                    self.count(&format!(
+                        "{} IS NOT NULL AND CAST({} AS VARCHAR) NOT IN ({})",
+                        column,
+                        column,
+                        values.join(", ")
+                    ))?,
+                    "不在可选值中的行",
+                ))
+            }
+            RULE_TYPE_RANGE => {
+                // 边界以字符串字面量给出，由DuckDB按列类型转换后比较
+                let bounds = [
+                    rule.min_value.as_deref().map(|min| {
+                        format!("{} < {}", column, duck_string(min))
+                    }),
+                    rule.max_value.as_deref().map(|max| {
+                        format!("{} > {}", column, duck_string(max))
+                    }),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+                Ok(Verdict::violations(
+                    self.count(&format!("({})", bounds.join(" OR ")))?,
+                    "超出范围的行",
+                ))
+            }
+            RULE_TYPE_ROW_COUNT_DELTA => self.check_row_count_delta(rule),
+            RULE_TYPE_FRESHNESS => {
+                let max_age = parse_number(rule.max_value.as_deref())?;
+                // 源端时间为不带时区的本地时间，按本地时间计算截止点
+                let threshold = Local::now().naive_local()
+                    - Duration::milliseconds((max_age * 1000.0) as i64);
+                let (latest, passed): (Option<String>, bool) =
+                    self.conn.query_row(
+                        &format!(
+                            "SELECT CAST(max({}) AS VARCHAR), \
+                             coalesce(max({}) >= CAST(? AS TIMESTAMP), false) \
+                             FROM {}",
+                            column,
+                            column,
+                            self.table()
+                        ),
+                        [threshold.format("%Y-%m-%d %H:%M:%S%.6f").to_string()],
+                        |row| Ok((row.get(0)?, row.get(1)?)),
+                    )?;
+                Ok(Verdict {
+                    passed,
+                    message: (!passed).then(|| {
+                        format!(
+                            "最新数据 {} 早于 {}",
+                            latest.as_deref().unwrap_or("NULL"),
+                            threshold.format("%Y-%m-%d %H:%M:%S")
+                        )
+                    }),
+                    observed_value: latest,
+                })
+            }
+            RULE_TYPE_CUSTOM_SQL => {
+                let sql = rule
+                    .custom_sql
+                    .as_deref()
+                    .unwrap_or_default()
+                    .trim()
+                    .trim_end_matches(';')
+                    .replace("{table}", &self.table());
+                let count: Option<i64> = self.conn.query_row(
+                    &format!("SELECT CAST(({}) AS BIGINT)", sql),
+                    [],
+                    |row| row.get(0),
+                )?;
+                Ok(Verdict::violations(count.unwrap_or_default(), "断言失败"))
+            }
+            _ => Err(AppError::QualityCheckFailed(format!(
+                "不支持的规则类型: {}",
+                rule.rule_type
+            ))),
+        }
+    }
+
+    // 与任务记录的上一次执行后的目标表行数比较，首次执行时不检查
+    fn check_row_count_delta(
+        &self,
+        rule: &TransTaskQualityRule,
+    ) -> Result<Verdict, AppError> {
+        let max_percent = parse_number(rule.max_value.as_deref())?;
+        let current: i64 = self.conn.query_row(
+            &format!("SELECT count(*) FROM {}", self.table()),
+            [],
+            |row| row.get(0),
+        )?;
+        let previous = self.task.row_count;
+        if previous <= 0 {
+            return Ok(Verdict {
+                passed: true,
+                observed_value: None,
+                message: Some(String::from("没有上一次执行的行数, 跳过")),
+            });
+        }
+        let percent =
+            (current - previous).abs() as f64 * 100.0 / previous as f64;
+        let passed = percent <= max_percent;
+        Ok(Verdict {
+            passed,
+            observed_value: Some(format!("{:.2}", percent)),
+            message: (!passed).then(|| {
+                format!(
+                    "行数由 {} 变为 {}, 变化 {:.2}% 超过 {}%",
+                    previous, current, percent, max_percent
+                )
+            }),
+        })
+    }
+}
+
+fn parse_number(value: Option<&str>) -> Result<f64, AppError> {
+    value
+        .unwrap_or_default()
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| AppError::QualityCheckFailed(e.to_string()))
+}
+
+// 未通过的阻断规则汇总，没有时返回None
+pub fn blocking_failure(results: &[TransTaskQualityResult]) -> Option<String> {
+    let failures = results
+        .iter()
+        .filter(|result| !result.passed && result.on_failure != ON_FAILURE_WARN)
+        .map(|result| match &result.message {
+            Some(message) => format!("{}({})", result.rule_name, message),
+            None => result.rule_name.clone(),
+        })
+        .collect::<Vec<_>>();
+    (!failures.is_empty()).then(|| failures.join("; "))
+}
+
+// 是否有要求回滚的规则未通过
+pub fn requires_rollback(results: &[TransTaskQualityResult]) -> bool {
+    results.iter().any(|result| {
+        !result.passed && result.on_failure == ON_FAILURE_ROLLBACK
+    })
+}
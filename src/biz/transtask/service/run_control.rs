@@ -0,0 +1,72 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicU8, Ordering},
+};
+
+// 对执行中任务发出的控制信号
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunSignal {
+    None,
+    Pause,
+    Cancel,
+}
+
+impl RunSignal {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => RunSignal::Pause,
+            2 => RunSignal::Cancel,
+            _ => RunSignal::None,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            RunSignal::None => 0,
+            RunSignal::Pause => 1,
+            RunSignal::Cancel => 2,
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "PAUSE" => RunSignal::Pause,
+            "CANCEL" => RunSignal::Cancel,
+            _ => RunSignal::None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RunSignal::None => "NONE",
+            RunSignal::Pause => "PAUSE",
+            RunSignal::Cancel => "CANCEL",
+        }
+    }
+}
+
+// 单次执行的控制句柄，传输引擎在批次循环中检查信号并协作停止
+#[derive(Clone, Debug, Default)]
+pub struct RunControl {
+    signal: Arc<AtomicU8>,
+    finished: Arc<AtomicBool>,
+}
+
+impl RunControl {
+    // 取消优先于暂停，已发出取消后不再降级为暂停
+    pub fn send(&self, signal: RunSignal) {
+        self.signal.fetch_max(signal.as_u8(), Ordering::SeqCst);
+    }
+
+    pub fn signal(&self) -> RunSignal {
+        RunSignal::from_u8(self.signal.load(Ordering::SeqCst))
+    }
+
+    pub fn finish(&self) {
+        self.finished.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,410 @@
+use chrono::NaiveDate;
+use r2d2_mysql::mysql::{
+    Column, Value as MyValue,
+    consts::{ColumnFlags, ColumnType},
+    prelude::Queryable,
+};
+
+use crate::{
+    biz::transtask::service::trans_engine::{SourceColumn, mysql_ident},
+    error::error::AppError,
+};
+
+// 自定义SELECT包装为派生表时使用的别名
+const CUSTOM_ALIAS: &str = "`__source`";
+
+// MySQL binary 字符集编号，用于区分二进制串与文本
+const BINARY_CHARSET: u16 = 63;
+
+// 抽取来源：源表、带过滤条件的源表或自定义SELECT。
+// 过滤条件与自定义SELECT中可使用命名参数，执行时按位置参数绑定：
+//   :last_watermark  任务当前的高水位，尚未同步过时为NULL
+//   :run_date        本次执行的日期，格式 YYYY-MM-DD
+#[derive(Clone, Debug)]
+pub struct SourceQuery {
+    // FROM 之后的部分：源表，或包装为派生表的自定义SELECT
+    relation: String,
+    // 过滤条件，不含 WHERE
+    filter: Option<String>,
+    // relation 与 filter 中命名参数对应的取值，按出现顺序排列
+    params: Vec<MyValue>,
+    custom: bool,
+}
+
+impl SourceQuery {
+    pub fn new(
+        table_name: &str,
+        source_filter: Option<&str>,
+        source_sql: Option<&str>,
+        last_watermark: Option<&str>,
+        run_date: NaiveDate,
+    ) -> Result<Self, AppError> {
+        let bind = |sql: &str| {
+            bind_named_params(sql, |name| match name {
+                "last_watermark" => {
+                    Some(last_watermark.map_or(MyValue::NULL, MyValue::from))
+                }
+                "run_date" => {
+                    Some(MyValue::from(run_date.format("%Y-%m-%d").to_string()))
+                }
+                _ => None,
+            })
+            .map_err(AppError::SourceSqlError)
+        };
+
+        match (non_empty(source_sql), non_empty(source_filter)) {
+            (Some(_), Some(_)) => Err(AppError::SourceSqlError(String::from(
+                "过滤条件与自定义SELECT只能配置其一",
+            ))),
+            (Some(source_sql), None) => {
+                let head = source_sql
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_uppercase();
+                if head != "SELECT" && head != "WITH" {
+                    return Err(AppError::SourceSqlError(String::from(
+                        "自定义抽取SQL必须是SELECT语句",
+                    )));
+                }
+                let (sql, params) = bind(source_sql)?;
+                Ok(Self {
+                    relation: format!("({}) AS {}", sql, CUSTOM_ALIAS),
+                    filter: None,
+                    params,
+                    custom: true,
+                })
+            }
+            (None, Some(source_filter)) => {
+                let (sql, params) = bind(source_filter)?;
+                Ok(Self {
+                    relation: mysql_ident(table_name),
+                    filter: Some(sql),
+                    params,
+                    custom: false,
+                })
+            }
+            (None, None) => Ok(Self {
+                relation: mysql_ident(table_name),
+                filter: None,
+                params: Vec::new(),
+                custom: false,
+            }),
+        }
+    }
+
+    // 是否为自定义SELECT，此时列信息取自查询结果而非源表
+    pub fn is_custom(&self) -> bool {
+        self.custom
+    }
+
+    // 直接读取整张源表，没有任何过滤
+    pub fn is_plain(&self) -> bool {
+        !self.custom && self.filter.is_none()
+    }
+
+    // 生成抽取语句，conditions 为附加的过滤条件，参数需排在 params() 之后
+    pub fn select_sql(
+        &self,
+        columns: &str,
+        conditions: &[String],
+        order_by: Option<&str>,
+    ) -> String {
+        let conditions = self
+            .filter
+            .iter()
+            .map(|filter| format!("({})", filter))
+            .chain(conditions.iter().cloned())
+            .collect::<Vec<_>>();
+        let mut sql = format!("SELECT {} FROM {}", columns, self.relation);
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        if let Some(order_by) = order_by {
+            sql.push_str(" ORDER BY ");
+            sql.push_str(order_by);
+        }
+        sql
+    }
+
+    pub fn params(&self) -> Vec<MyValue> {
+        self.params.clone()
+    }
+
+    // 在源端执行 EXPLAIN 校验语法、表与列是否存在，不读取数据
+    pub fn explain(&self, conn: &mut impl Queryable) -> Result<(), AppError> {
+        conn.exec_drop(
+            format!("EXPLAIN {}", self.select_sql("*", &[], None)),
+            self.params(),
+        )
+        .map_err(|e| AppError::SourceSqlError(e.to_string()))
+    }
+
+    // 自定义SELECT的结果列，按结果集元数据推断MySQL类型
+    pub fn load_columns(
+        &self,
+        conn: &mut impl Queryable,
+    ) -> Result<Vec<SourceColumn>, AppError> {
+        let sql = format!("{} LIMIT 0", self.select_sql("*", &[], None));
+        let result = conn.exec_iter(sql, self.params())?;
+        let columns = result
+            .columns()
+            .as_ref()
+            .iter()
+            .map(source_column)
+            .collect();
+        Ok(columns)
+    }
+}
+
+fn non_empty(sql: Option<&str>) -> Option<&str> {
+    sql.map(|sql| sql.trim().trim_end_matches(';').trim())
+        .filter(|sql| !sql.is_empty())
+}
+
+// 由结果集列元数据还原 information_schema 中的类型写法
+fn source_column(column: &Column) -> SourceColumn {
+    let flags = column.flags();
+    let unsigned = flags.contains(ColumnFlags::UNSIGNED_FLAG);
+    let binary = column.character_set() == BINARY_CHARSET;
+    let decimals = column.decimals() as u64;
+    let (data_type, numeric_precision, numeric_scale) = match column
+        .column_type()
+    {
+        ColumnType::MYSQL_TYPE_TINY => ("tinyint", None, None),
+        ColumnType::MYSQL_TYPE_SHORT => ("smallint", None, None),
+        ColumnType::MYSQL_TYPE_INT24 => ("mediumint", None, None),
+        ColumnType::MYSQL_TYPE_LONG => ("int", None, None),
+        ColumnType::MYSQL_TYPE_LONGLONG => ("bigint", None, None),
+        ColumnType::MYSQL_TYPE_DECIMAL | ColumnType::MYSQL_TYPE_NEWDECIMAL => {
+            // 显示长度包含小数点与符号位
+            let precision = (column.column_length() as u64)
+                .saturating_sub((decimals > 0) as u64)
+                .saturating_sub((!unsigned) as u64);
+            ("decimal", Some(precision), Some(decimals))
+        }
+        ColumnType::MYSQL_TYPE_FLOAT => ("float", None, None),
+        ColumnType::MYSQL_TYPE_DOUBLE => ("double", None, None),
+        ColumnType::MYSQL_TYPE_YEAR => ("year", None, None),
+        ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE => {
+            ("date", None, None)
+        }
+        ColumnType::MYSQL_TYPE_DATETIME
+        | ColumnType::MYSQL_TYPE_DATETIME2
+        | ColumnType::MYSQL_TYPE_TIMESTAMP
+        | ColumnType::MYSQL_TYPE_TIMESTAMP2 => ("datetime", None, None),
+        ColumnType::MYSQL_TYPE_TIME | ColumnType::MYSQL_TYPE_TIME2 => {
+            ("time", None, None)
+        }
+        ColumnType::MYSQL_TYPE_BIT => ("bit", None, None),
+        ColumnType::MYSQL_TYPE_JSON => ("json", None, None),
+        ColumnType::MYSQL_TYPE_GEOMETRY => ("geometry", None, None),
+        ColumnType::MYSQL_TYPE_TINY_BLOB
+        | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+        | ColumnType::MYSQL_TYPE_LONG_BLOB
+        | ColumnType::MYSQL_TYPE_BLOB
+        | ColumnType::MYSQL_TYPE_VARCHAR
+        | ColumnType::MYSQL_TYPE_VAR_STRING
+        | ColumnType::MYSQL_TYPE_STRING
+            if binary =>
+        {
+            ("blob", None, None)
+        }
+        _ => ("varchar", None, None),
+    };
+    let column_type = match data_type {
+        "bit" => format!("bit({})", column.column_length()),
+        _ if unsigned => format!("{} unsigned", data_type),
+        _ => String::from(data_type),
+    };
+    SourceColumn::new(
+        column.name_str().into_owned(),
+        String::from(data_type),
+        column_type,
+        numeric_precision,
+        numeric_scale,
+    )
+}
+
+// 将SQL中的 :name 命名参数替换为 ? 并按出现顺序收集取值，
+// 跳过字符串、带引号的标识符、注释与 :: 类型转换。末尾的 ; 忽略，
+// 不允许出现多条语句
+fn bind_named_params(
+    sql: &str,
+    value_of: impl Fn(&str) -> Option<MyValue>,
+) -> Result<(String, Vec<MyValue>), String> {
+    let chars = sql.chars().collect::<Vec<_>>();
+    let mut bound = String::with_capacity(sql.len());
+    let mut params = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' | '"' | '`' => {
+                // 引号内按原样保留，支持反斜杠转义与连续两个引号
+                let start = i;
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == '\\' && c != '`' {
+                        i += 2;
+                        continue;
+                    }
+                    if chars[i] == c {
+                        if chars.get(i + 1) == Some(&c) {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(String::from("SQL中存在未闭合的引号"));
+                }
+                bound.extend(&chars[start..=i]);
+                i += 1;
+            }
+            // 与MySQL一致，-- 之后须为空白才是注释，否则如 1--1 为减负数
+            '-' if chars.get(i + 1) == Some(&'-')
+                && chars.get(i + 2).is_none_or(|next| next.is_whitespace()) =>
+            {
+                while i < chars.len() && chars[i] != '\n' {
+                    bound.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    bound.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                let start = i;
+                i += 2;
+                while i + 1 < chars.len()
+                    && !(chars[i] == '*' && chars[i + 1] == '/')
+                {
+                    i += 1;
+                }
+                if i + 1 >= chars.len() {
+                    return Err(String::from("SQL中存在未闭合的注释"));
+                }
+                bound.extend(&chars[start..i + 2]);
+                i += 2;
+            }
+            ';' if chars[i + 1..].iter().all(|next| next.is_whitespace()) => {
+                break;
+            }
+            ';' => return Err(String::from("不允许包含多条语句")),
+            ':' if chars.get(i + 1) == Some(&':') => {
+                bound.push_str("::");
+                i += 2;
+            }
+            ':' if chars.get(i + 1).is_some_and(|next| {
+                next.is_ascii_alphabetic() || *next == '_'
+            }) =>
+            {
+                let start = i + 1;
+                i = start;
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric() || chars[i] == '_')
+                {
+                    i += 1;
+                }
+                let name = chars[start..i].iter().collect::<String>();
+                let value = value_of(&name)
+                    .ok_or_else(|| format!("不支持的命名参数: :{}", name))?;
+                bound.push('?');
+                params.push(value);
+            }
+            _ => {
+                bound.push(c);
+                i += 1;
+            }
+        }
+    }
+    Ok((bound, params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bind(sql: &str) -> Result<(String, Vec<MyValue>), String> {
+        bind_named_params(sql, |name| match name {
+            "run_date" => Some(MyValue::from("2024-01-31")),
+            "last_watermark" => Some(MyValue::NULL),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn binds_in_order_of_appearance() {
+        let (sql, params) =
+            bind("d >= :last_watermark AND d < :run_date OR e = :run_date")
+                .unwrap();
+        assert_eq!(sql, "d >= ? AND d < ? OR e = ?");
+        assert_eq!(
+            params,
+            vec![
+                MyValue::NULL,
+                MyValue::from("2024-01-31"),
+                MyValue::from("2024-01-31")
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_params_are_rejected() {
+        assert!(bind("d = :missing").is_err());
+        assert!(bind("d = :run_date AND e = :other").is_err());
+    }
+
+    #[test]
+    fn quoted_text_and_comments_are_kept() {
+        for sql in [
+            "s = ':run_date'",
+            "s = 'it''s :run_date'",
+            r"s = 'a\' :run_date'",
+            "s = \":run_date\"",
+            "`:run_date` = 1",
+            "a = 1 -- :run_date",
+            "a = 1 # :run_date",
+            "a = 1 /* :run_date ; */",
+        ] {
+            let (bound, params) = bind(sql).unwrap();
+            assert_eq!(bound, sql);
+            assert!(params.is_empty(), "{}", sql);
+        }
+        let (sql, params) = bind("a = 1 -- :x\nAND d = :run_date").unwrap();
+        assert_eq!(sql, "a = 1 -- :x\nAND d = ?");
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn double_dash_without_space_is_not_a_comment() {
+        let (sql, params) = bind("a = 1--1 AND d = :run_date").unwrap();
+        assert_eq!(sql, "a = 1--1 AND d = ?");
+        assert_eq!(params.len(), 1);
+        assert!(bind("a = 1--1; DROP TABLE t").is_err());
+    }
+
+    #[test]
+    fn double_colon_casts_are_kept() {
+        let (sql, params) = bind("a::int = 1 AND d = :run_date").unwrap();
+        assert_eq!(sql, "a::int = 1 AND d = ?");
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn statements() {
+        assert_eq!(bind("a = 1;").unwrap().0, "a = 1");
+        assert_eq!(bind("a = 1 ;  \n").unwrap().0, "a = 1 ");
+        assert!(bind("a = 1; DELETE FROM t").is_err());
+        assert!(bind("a = 1;;").is_err());
+        assert!(bind("s = ';").is_err());
+        assert!(bind("a = 1 /* ; ").is_err());
+    }
+}
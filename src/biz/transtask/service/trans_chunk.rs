@@ -0,0 +1,409 @@
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError, SyncSender},
+    },
+    thread,
+    time::Duration,
+};
+
+use duckdb::types::Value as DuckValue;
+use r2d2::Pool;
+use r2d2_mysql::{
+    MySqlConnectionManager,
+    mysql::{Value as MyValue, prelude::Queryable},
+};
+
+use crate::{
+    biz::transtask::service::{
+        run_control::{RunControl, RunSignal},
+        source_query::SourceQuery,
+        trans_engine::{
+            SourceColumn, kill_query, mysql_columns, mysql_ident, value_size,
+        },
+    },
+    error::error::AppError,
+};
+
+// 估算行数低于该值时不拆分，单线程读取即可
+const MIN_PARALLEL_ROWS: u64 = 100_000;
+
+// 每个读取线程分到的区间数，区间多于线程数可缓解主键分布不均
+const CHUNKS_PER_WORKER: usize = 4;
+
+// 非整数主键按采样划分区间时，每个区间期望的样本数
+const SAMPLES_PER_CHUNK: usize = 100;
+
+// 读取线程每次向写入方发送的行数
+const SEND_ROWS: usize = 1_000;
+
+// 等待数据期间检查控制信号的间隔
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+// 主键区间，下界包含、上界不包含，None 表示不限
+#[derive(Clone, Debug)]
+pub struct KeyRange {
+    pub lower: Option<MyValue>,
+    pub upper: Option<MyValue>,
+}
+
+impl KeyRange {
+    // 区间对应的过滤条件，参数追加到 params 之后
+    pub fn conditions(
+        &self,
+        key: &str,
+        params: &mut Vec<MyValue>,
+    ) -> Vec<String> {
+        let mut conditions = Vec::new();
+        if let Some(lower) = &self.lower {
+            conditions.push(format!("{} >= ?", key));
+            params.push(lower.clone());
+        }
+        if let Some(upper) = &self.upper {
+            conditions.push(format!("{} < ?", key));
+            params.push(upper.clone());
+        }
+        conditions
+    }
+}
+
+// 区间边界的展示形式，整数不带引号
+pub fn bound_string(value: &MyValue) -> String {
+    match value {
+        MyValue::Int(v) => v.to_string(),
+        MyValue::UInt(v) => v.to_string(),
+        _ => value.as_sql(true),
+    }
+}
+
+// 将有序的分界值转换为首尾相接、覆盖全部取值的区间
+fn ranges_from_bounds(bounds: Vec<MyValue>) -> Vec<KeyRange> {
+    let mut ranges = Vec::with_capacity(bounds.len() + 1);
+    let mut lower = None;
+    for bound in bounds {
+        ranges.push(KeyRange {
+            lower: lower.take(),
+            upper: Some(bound.clone()),
+        });
+        lower = Some(bound);
+    }
+    ranges.push(KeyRange { lower, upper: None });
+    ranges
+}
+
+// 按单列主键把源表划分为若干区间：整数主键按 MIN/MAX 等分，
+// 其余类型按随机采样的分位点划分。表太小或无法划分时返回None
+pub fn plan_key_ranges(
+    conn: &mut impl Queryable,
+    table_name: &str,
+    key: &SourceColumn,
+    parallelism: usize,
+    estimated_rows: Option<u64>,
+) -> Result<Option<Vec<KeyRange>>, AppError> {
+    if estimated_rows.is_some_and(|rows| rows < MIN_PARALLEL_ROWS) {
+        return Ok(None);
+    }
+    let chunks = parallelism * CHUNKS_PER_WORKER;
+    let bounds = if key.is_integer() {
+        integer_bounds(conn, table_name, key, chunks)?
+    } else {
+        // 没有统计信息时无法确定采样比例
+        let Some(rows) = estimated_rows else {
+            return Ok(None);
+        };
+        sampled_bounds(conn, table_name, key, chunks, rows)?
+    };
+    if bounds.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(ranges_from_bounds(bounds)))
+}
+
+// 按整数主键的 MIN/MAX 把源表等分为 chunks 个区间，空表时为单个不限区间
+pub fn integer_key_ranges(
+    conn: &mut impl Queryable,
+    table_name: &str,
+    key: &SourceColumn,
+    chunks: usize,
+) -> Result<Vec<KeyRange>, AppError> {
+    let bounds = integer_bounds(conn, table_name, key, chunks)?;
+    Ok(ranges_from_bounds(bounds))
+}
+
+fn integer_bounds(
+    conn: &mut impl Queryable,
+    table_name: &str,
+    key: &SourceColumn,
+    chunks: usize,
+) -> Result<Vec<MyValue>, AppError> {
+    let key = mysql_ident(&key.name);
+    // 使用二进制协议读取，取得带类型的整数值
+    let min_max: Option<(MyValue, MyValue)> = conn.exec_first(
+        format!(
+            "SELECT MIN({}), MAX({}) FROM {}",
+            key,
+            key,
+            mysql_ident(table_name)
+        ),
+        (),
+    )?;
+    let Some((min, max)) =
+        min_max.and_then(|(min, max)| Some((to_i128(&min)?, to_i128(&max)?)))
+    else {
+        // 空表
+        return Ok(Vec::new());
+    };
+
+    let span = max - min + 1;
+    let mut bounds: Vec<i128> = (1..chunks as i128)
+        .map(|i| min + span * i / chunks as i128)
+        .filter(|bound| *bound > min)
+        .collect();
+    bounds.dedup();
+    Ok(bounds
+        .into_iter()
+        .map(|bound| match i64::try_from(bound) {
+            Ok(bound) => MyValue::Int(bound),
+            Err(_) => MyValue::UInt(bound as u64),
+        })
+        .collect())
+}
+
+// 主键边界值转换为整数，文本协议返回的数字字符串同样解析；NULL（空表）为None
+fn to_i128(value: &MyValue) -> Option<i128> {
+    match value {
+        MyValue::Int(v) => Some(*v as i128),
+        MyValue::UInt(v) => Some(*v as i128),
+        MyValue::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn sampled_bounds(
+    conn: &mut impl Queryable,
+    table_name: &str,
+    key: &SourceColumn,
+    chunks: usize,
+    rows: u64,
+) -> Result<Vec<MyValue>, AppError> {
+    let fraction =
+        ((chunks * SAMPLES_PER_CHUNK) as f64 / rows.max(1) as f64).min(1.0);
+    let key = mysql_ident(&key.name);
+    // 由MySQL排序，保证分界值与源端的排序规则一致
+    let samples: Vec<MyValue> = conn.exec(
+        format!(
+            "SELECT {} FROM {} WHERE RAND() < ? ORDER BY {}",
+            key,
+            mysql_ident(table_name),
+            key
+        ),
+        (fraction,),
+    )?;
+    if samples.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut bounds: Vec<MyValue> = (1..chunks)
+        .map(|i| samples[samples.len() * i / chunks].clone())
+        .collect();
+    bounds.dedup();
+    Ok(bounds)
+}
+
+// 读取线程发给写入方的一批行，已转换为DuckDB的值
+pub struct ChunkBatch {
+    pub rows: Vec<Vec<DuckValue>>,
+    pub bytes: u64,
+}
+
+// 并行读取：多个线程各自从连接池取连接，领取区间读取后经有界通道交给调用方写入，
+// 写入仍在调用方的单个事务中完成
+pub struct ChunkReader<'a> {
+    pub source_pool: &'a Pool<MySqlConnectionManager>,
+    pub query: &'a SourceQuery,
+    pub columns: &'a [SourceColumn],
+    pub key: &'a SourceColumn,
+    pub ranges: Vec<KeyRange>,
+    pub parallelism: usize,
+}
+
+// 读取线程间共享的状态
+#[derive(Default)]
+struct ReadState {
+    next_range: AtomicUsize,
+    stop: AtomicBool,
+    // 正在执行查询的源端连接ID，停止时逐一终止
+    active: Mutex<Vec<u32>>,
+}
+
+impl ChunkReader<'_> {
+    // 读取全部区间并依次交给 consume；收到控制信号、任一线程出错或 consume 出错时
+    // 停止所有线程并返回错误
+    pub fn read(
+        &self,
+        control: &RunControl,
+        mut consume: impl FnMut(ChunkBatch) -> Result<(), AppError>,
+    ) -> Result<(), AppError> {
+        let state = ReadState::default();
+        let (sender, receiver) = mpsc::sync_channel(self.parallelism * 2);
+        thread::scope(|scope| {
+            for _ in 0..self.parallelism {
+                let sender = sender.clone();
+                let state = &state;
+                scope.spawn(move || {
+                    if let Err(e) = self.read_ranges(state, &sender) {
+                        state.stop.store(true, Ordering::SeqCst);
+                        let _ = sender.send(Err(e));
+                    }
+                });
+            }
+            drop(sender);
+
+            let mut interrupted = None;
+            loop {
+                match control.signal() {
+                    RunSignal::Cancel => {
+                        interrupted = Some(AppError::TransCancelled);
+                        break;
+                    }
+                    // 并行读取没有顺序，暂停时回滚
+                    RunSignal::Pause => {
+                        interrupted = Some(AppError::TransPaused);
+                        break;
+                    }
+                    RunSignal::None => {}
+                }
+                match receiver.recv_timeout(SIGNAL_CHECK_INTERVAL) {
+                    Ok(Ok(batch)) => {
+                        if let Err(e) = consume(batch) {
+                            interrupted = Some(e);
+                            break;
+                        }
+                    }
+                    Ok(Err(e)) => {
+                        interrupted = Some(e);
+                        break;
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+
+            if interrupted.is_some() {
+                state.stop.store(true, Ordering::SeqCst);
+                for connection_id in state.active.lock().unwrap().iter() {
+                    kill_query(self.source_pool, *connection_id);
+                }
+            }
+            // 丢弃接收端，阻塞在发送上的线程随即退出
+            drop(receiver);
+            interrupted.map_or(Ok(()), Err)
+        })
+    }
+
+    fn read_ranges(
+        &self,
+        state: &ReadState,
+        sender: &SyncSender<Result<ChunkBatch, AppError>>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.source_pool.get()?;
+        let connection_id = conn.connection_id();
+        let key = mysql_ident(&self.key.name);
+        loop {
+            let index = state.next_range.fetch_add(1, Ordering::SeqCst);
+            let Some(range) = self.ranges.get(index) else {
+                return Ok(());
+            };
+            state.active.lock().unwrap().push(connection_id);
+            // 登记后再检查停止标志，保证停止时该查询一定会被终止
+            if state.stop.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+
+            let mut params = self.query.params();
+            let conditions = range.conditions(&key, &mut params);
+            let select_sql = self.query.select_sql(
+                &mysql_columns(self.columns),
+                &conditions,
+                None,
+            );
+            let mut batch = ChunkBatch {
+                rows: Vec::with_capacity(SEND_ROWS),
+                bytes: 0,
+            };
+            for row in conn.exec_iter(select_sql, params)? {
+                if state.stop.load(Ordering::SeqCst) {
+                    return Ok(());
+                }
+                let values = row?.unwrap();
+                batch.bytes += values.iter().map(value_size).sum::<u64>();
+                batch.rows.push(
+                    values
+                        .into_iter()
+                        .zip(self.columns)
+                        .map(|(value, column)| column.to_duck_value(value))
+                        .collect(),
+                );
+                if batch.rows.len() >= SEND_ROWS {
+                    let full = std::mem::replace(
+                        &mut batch,
+                        ChunkBatch {
+                            rows: Vec::with_capacity(SEND_ROWS),
+                            bytes: 0,
+                        },
+                    );
+                    if sender.send(Ok(full)).is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+            if !batch.rows.is_empty() && sender.send(Ok(batch)).is_err() {
+                return Ok(());
+            }
+            state
+                .active
+                .lock()
+                .unwrap()
+                .retain(|active| *active != connection_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_i128_accepts_returned_values() {
+        assert_eq!(to_i128(&MyValue::Int(-5)), Some(-5));
+        assert_eq!(to_i128(&MyValue::UInt(u64::MAX)), Some(u64::MAX as i128));
+        assert_eq!(to_i128(&MyValue::Bytes(b"42".to_vec())), Some(42));
+        assert_eq!(to_i128(&MyValue::Bytes(b"-7".to_vec())), Some(-7));
+        assert_eq!(
+            to_i128(&MyValue::Bytes(b"18446744073709551615".to_vec())),
+            Some(u64::MAX as i128)
+        );
+        assert_eq!(to_i128(&MyValue::Bytes(b"1.5".to_vec())), None);
+        assert_eq!(to_i128(&MyValue::NULL), None);
+    }
+
+    #[test]
+    fn ranges_cover_all_values() {
+        let ranges =
+            ranges_from_bounds(vec![MyValue::Int(10), MyValue::Int(20)]);
+        assert_eq!(ranges.len(), 3);
+        assert!(ranges[0].lower.is_none());
+        assert_eq!(ranges[0].upper, Some(MyValue::Int(10)));
+        assert_eq!(ranges[1].lower, Some(MyValue::Int(10)));
+        assert_eq!(ranges[1].upper, Some(MyValue::Int(20)));
+        assert_eq!(ranges[2].lower, Some(MyValue::Int(20)));
+        assert!(ranges[2].upper.is_none());
+
+        let mut params = Vec::new();
+        assert_eq!(
+            ranges[1].conditions("`id`", &mut params),
+            vec!["`id` >= ?", "`id` < ?"]
+        );
+        assert_eq!(params, vec![MyValue::Int(10), MyValue::Int(20)]);
+    }
+}
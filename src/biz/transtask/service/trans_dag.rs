@@ -0,0 +1,137 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+// 任务依赖图，边由上游任务指向下游任务
+#[derive(Clone, Debug, Default)]
+pub struct TaskGraph {
+    // 任务ID -> 上游任务ID
+    upstream: BTreeMap<i64, BTreeSet<i64>>,
+    // 任务ID -> 下游任务ID
+    downstream: BTreeMap<i64, BTreeSet<i64>>,
+}
+
+impl TaskGraph {
+    // 端点不在 task_ids 中的边（如已删除的任务）被忽略
+    pub fn new(task_ids: &[i64], edges: &[(i64, i64)]) -> Self {
+        let mut graph = Self::default();
+        for &id in task_ids {
+            graph.upstream.entry(id).or_default();
+            graph.downstream.entry(id).or_default();
+        }
+        for &(upstream_id, trans_task_id) in edges {
+            if graph.upstream.contains_key(&upstream_id)
+                && graph.upstream.contains_key(&trans_task_id)
+            {
+                graph.add_edge(upstream_id, trans_task_id);
+            }
+        }
+        graph
+    }
+
+    fn add_edge(&mut self, upstream_id: i64, trans_task_id: i64) {
+        self.upstream
+            .entry(trans_task_id)
+            .or_default()
+            .insert(upstream_id);
+        self.downstream
+            .entry(upstream_id)
+            .or_default()
+            .insert(trans_task_id);
+        self.upstream.entry(upstream_id).or_default();
+        self.downstream.entry(trans_task_id).or_default();
+    }
+
+    // 替换任务的全部上游任务
+    pub fn replace_upstream(
+        &mut self,
+        trans_task_id: i64,
+        upstream_ids: &[i64],
+    ) {
+        if let Some(previous) = self.upstream.get_mut(&trans_task_id) {
+            for upstream_id in std::mem::take(previous) {
+                if let Some(downstream) = self.downstream.get_mut(&upstream_id)
+                {
+                    downstream.remove(&trans_task_id);
+                }
+            }
+        }
+        for &upstream_id in upstream_ids {
+            self.add_edge(upstream_id, trans_task_id);
+        }
+    }
+
+    pub fn task_ids(&self) -> Vec<i64> {
+        self.upstream.keys().copied().collect()
+    }
+
+    pub fn edges(&self) -> Vec<(i64, i64)> {
+        self.upstream
+            .iter()
+            .flat_map(|(&id, upstream)| {
+                upstream.iter().map(move |&upstream_id| (upstream_id, id))
+            })
+            .collect()
+    }
+
+    pub fn upstream_of(&self, trans_task_id: i64) -> Vec<i64> {
+        self.upstream
+            .get(&trans_task_id)
+            .map(|upstream| upstream.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn downstream_of(&self, trans_task_id: i64) -> Vec<i64> {
+        self.downstream
+            .get(&trans_task_id)
+            .map(|downstream| downstream.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    // 拓扑排序（Kahn算法），存在环时返回环上及依赖环的任务
+    pub fn topological_order(&self) -> Result<Vec<i64>, Vec<i64>> {
+        let mut pending: BTreeMap<i64, usize> = self
+            .upstream
+            .iter()
+            .map(|(&id, upstream)| (id, upstream.len()))
+            .collect();
+        let mut ready: VecDeque<i64> = pending
+            .iter()
+            .filter(|&(_, &count)| count == 0)
+            .map(|(&id, _)| id)
+            .collect();
+        let mut order = Vec::with_capacity(pending.len());
+        while let Some(id) = ready.pop_front() {
+            pending.remove(&id);
+            order.push(id);
+            for downstream_id in self.downstream_of(id) {
+                if let Some(count) = pending.get_mut(&downstream_id) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push_back(downstream_id);
+                    }
+                }
+            }
+        }
+        if pending.is_empty() {
+            Ok(order)
+        } else {
+            Err(pending.into_keys().collect())
+        }
+    }
+
+    // 指定任务及其全部上游任务构成的子图
+    pub fn with_upstream_closure(&self, trans_task_ids: &[i64]) -> Self {
+        let mut selected = BTreeSet::new();
+        let mut queue: VecDeque<i64> = trans_task_ids
+            .iter()
+            .copied()
+            .filter(|id| self.upstream.contains_key(id))
+            .collect();
+        while let Some(id) = queue.pop_front() {
+            if selected.insert(id) {
+                queue.extend(self.upstream_of(id));
+            }
+        }
+        let task_ids = selected.into_iter().collect::<Vec<_>>();
+        Self::new(&task_ids, &self.edges())
+    }
+}
@@ -0,0 +1,425 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use tokio::task::JoinSet;
+
+use crate::{
+    app::Infrastructure,
+    biz::transtask::{
+        model::{
+            trans_dag_run::{
+                DAG_NODE_STATUS_FAILED, DAG_NODE_STATUS_PENDING,
+                DAG_NODE_STATUS_SKIPPED, DAG_NODE_STATUS_SUCCESS,
+                DAG_RUN_STATUS_FAILED, DAG_RUN_STATUS_SUCCESS, TransDagEdgeVo,
+                TransDagNodeVo, TransDagRun, TransDagRunBo, TransDagRunNode,
+                TransDagRunVo, TransDagVo,
+            },
+            trans_task::TransTask,
+            trans_task_dependency::{
+                TransTaskDependency, TransTaskDependencyBo,
+            },
+            trans_task_run::{RUN_STATUS_SUCCESS, TransTaskRunListVo},
+        },
+        repository::{
+            trans_dag_run_node_repo::TransDagRunNodeRepository,
+            trans_dag_run_repo::TransDagRunRepository,
+            trans_task_dependency_repo::TransTaskDependencyRepository,
+            trans_task_repo::TransTaskRepository,
+        },
+        service::{trans_dag::TaskGraph, trans_task_service::TransTaskService},
+    },
+    error::error::AppError,
+    sys::user::model::user::User,
+};
+
+// 任务依赖与DAG执行：按拓扑顺序执行就绪的任务，上游任务全部成功后下游任务才就绪，
+// 任一任务失败时其全部下游任务跳过，不相关的分支继续执行
+#[derive(Clone)]
+pub struct TransDagService {
+    trans_task_repo: TransTaskRepository,
+    trans_task_dependency_repo: TransTaskDependencyRepository,
+    trans_dag_run_repo: TransDagRunRepository,
+    trans_dag_run_node_repo: TransDagRunNodeRepository,
+    trans_task_service: TransTaskService,
+    // 同时执行的任务数上限
+    concurrency: usize,
+    // 本实例是否有DAG正在执行，同一时间只允许一次DAG执行
+    running: Arc<AtomicBool>,
+}
+
+// 标记DAG正在执行，离开作用域时自动清除
+struct DagRunningGuard {
+    running: Arc<AtomicBool>,
+}
+
+impl DagRunningGuard {
+    fn acquire(running: &Arc<AtomicBool>) -> Option<Self> {
+        running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()?;
+        Some(Self {
+            running: running.clone(),
+        })
+    }
+}
+
+impl Drop for DagRunningGuard {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+impl TransDagService {
+    pub fn new(
+        infra: &Infrastructure,
+        trans_task_service: TransTaskService,
+    ) -> Self {
+        Self {
+            trans_task_repo: TransTaskRepository::new(infra.batis.clone()),
+            trans_task_dependency_repo: TransTaskDependencyRepository::new(
+                infra.batis.clone(),
+            ),
+            trans_dag_run_repo: TransDagRunRepository::new(infra.batis.clone()),
+            trans_dag_run_node_repo: TransDagRunNodeRepository::new(
+                infra.batis.clone(),
+            ),
+            trans_task_service,
+            concurrency: infra.config.transfer.dag_concurrency.max(1),
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // 未删除的任务及它们之间的依赖图
+    async fn load_graph(
+        &self,
+    ) -> Result<(Vec<TransTask>, TaskGraph), AppError> {
+        let tasks = self
+            .trans_task_repo
+            .select_all()
+            .await?
+            .into_iter()
+            .filter(|task| !task.base_entity.is_deleted())
+            .collect::<Vec<_>>();
+        let task_ids = tasks
+            .iter()
+            .filter_map(|task| task.trans_task_id)
+            .collect::<Vec<_>>();
+        let edges = self
+            .trans_task_dependency_repo
+            .select_all()
+            .await?
+            .iter()
+            .map(|dependency| {
+                (dependency.upstream_task_id, dependency.trans_task_id)
+            })
+            .collect::<Vec<_>>();
+        Ok((tasks, TaskGraph::new(&task_ids, &edges)))
+    }
+
+    // 任务的上游任务
+    pub async fn list_dependencies(
+        &self,
+        trans_task_id: i64,
+    ) -> Result<Vec<TransDagNodeVo>, AppError> {
+        let (tasks, graph) = self.load_graph().await?;
+        let upstream_ids = graph.upstream_of(trans_task_id);
+        Ok(tasks
+            .iter()
+            .filter(|task| {
+                task.trans_task_id
+                    .is_some_and(|id| upstream_ids.contains(&id))
+            })
+            .map(to_node_vo)
+            .collect())
+    }
+
+    // 整体保存任务的上游任务，保存前检查上游任务存在且不形成循环依赖
+    pub async fn save_dependencies(
+        &self,
+        trans_task_id: i64,
+        bo: TransTaskDependencyBo,
+        current_user: &User,
+    ) -> Result<Vec<TransDagNodeVo>, AppError> {
+        let (_, mut graph) = self.load_graph().await?;
+        let task_ids = graph.task_ids();
+        if !task_ids.contains(&trans_task_id) {
+            return Err(AppError::BusinessError("传输任务不存在"));
+        }
+        let mut upstream_ids = bo.upstream_task_ids;
+        upstream_ids.sort_unstable();
+        upstream_ids.dedup();
+        if upstream_ids.contains(&trans_task_id) {
+            return Err(AppError::DependencyError(String::from(
+                "任务不能依赖自身",
+            )));
+        }
+        if let Some(missing) =
+            upstream_ids.iter().find(|id| !task_ids.contains(id))
+        {
+            return Err(AppError::DependencyError(format!(
+                "上游任务不存在: {}",
+                missing
+            )));
+        }
+
+        graph.replace_upstream(trans_task_id, &upstream_ids);
+        if let Err(cycle) = graph.topological_order() {
+            return Err(AppError::DependencyError(format!(
+                "存在循环依赖, 涉及任务: {:?}",
+                cycle
+            )));
+        }
+        let dependencies = upstream_ids
+            .iter()
+            .map(|&upstream_id| {
+                TransTaskDependency::new(
+                    trans_task_id,
+                    upstream_id,
+                    current_user,
+                )
+            })
+            .collect::<Vec<_>>();
+        self.trans_task_dependency_repo
+            .replace_by_trans_task_id(&trans_task_id, &dependencies)
+            .await?;
+        self.list_dependencies(trans_task_id).await
+    }
+
+    // 依赖图及最近一次DAG执行的状态
+    pub async fn graph(&self) -> Result<TransDagVo, AppError> {
+        let (tasks, graph) = self.load_graph().await?;
+        Ok(TransDagVo {
+            nodes: tasks.iter().map(to_node_vo).collect(),
+            edges: graph
+                .edges()
+                .into_iter()
+                .map(|(upstream_task_id, trans_task_id)| TransDagEdgeVo {
+                    upstream_task_id,
+                    trans_task_id,
+                })
+                .collect(),
+            latest_run: self.latest_run().await?,
+        })
+    }
+
+    pub async fn latest_run(&self) -> Result<Option<TransDagRunVo>, AppError> {
+        let Some(dag_run) = self.trans_dag_run_repo.select_latest().await?
+        else {
+            return Ok(None);
+        };
+        let nodes = self
+            .trans_dag_run_node_repo
+            .select_by_trans_dag_run_id(&dag_run.trans_dag_run_id.unwrap())
+            .await?;
+        Ok(Some(
+            dag_run.to_vo(nodes.iter().map(|node| node.to_vo()).collect()),
+        ))
+    }
+
+    // 发起一次DAG执行，在后台按依赖顺序执行，立即返回执行记录
+    pub async fn run(
+        &self,
+        bo: TransDagRunBo,
+        current_user: &User,
+    ) -> Result<TransDagRunVo, AppError> {
+        let guard = DagRunningGuard::acquire(&self.running)
+            .ok_or(AppError::BusinessError("DAG正在执行"))?;
+        let (_, graph) = self.load_graph().await?;
+        let graph = match &bo.trans_task_ids {
+            Some(trans_task_ids) => graph.with_upstream_closure(trans_task_ids),
+            None => graph,
+        };
+        let order = graph.topological_order().map_err(|cycle| {
+            AppError::DependencyError(format!(
+                "存在循环依赖, 涉及任务: {:?}",
+                cycle
+            ))
+        })?;
+        if order.is_empty() {
+            return Err(AppError::BusinessError("没有可执行的传输任务"));
+        }
+
+        let operator_id = current_user.get_user_id();
+        let mut dag_run = TransDagRun::start(order.len(), operator_id);
+        let result = self.trans_dag_run_repo.insert(&dag_run).await?;
+        dag_run.trans_dag_run_id = result.last_insert_id.as_i64();
+        let trans_dag_run_id = dag_run.trans_dag_run_id.unwrap();
+        let mut nodes = HashMap::new();
+        for &trans_task_id in &order {
+            let mut node = TransDagRunNode::pending(
+                trans_dag_run_id,
+                trans_task_id,
+                operator_id,
+            );
+            let result = self.trans_dag_run_node_repo.insert(&node).await?;
+            node.trans_dag_run_node_id = result.last_insert_id.as_i64();
+            nodes.insert(trans_task_id, node);
+        }
+        let vo = dag_run.to_vo(
+            order.iter().map(|id| nodes[id].to_vo()).collect::<Vec<_>>(),
+        );
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            let _guard = guard;
+            service
+                .execute(dag_run, nodes, &graph, &order, operator_id)
+                .await;
+        });
+        Ok(vo)
+    }
+
+    async fn execute(
+        &self,
+        mut dag_run: TransDagRun,
+        mut nodes: HashMap<i64, TransDagRunNode>,
+        graph: &TaskGraph,
+        order: &[i64],
+        operator_id: i64,
+    ) {
+        tracing::info!(
+            "DAG开始执行: {:?}, {} 个任务",
+            dag_run.trans_dag_run_id,
+            order.len()
+        );
+        // 各任务尚未成功的上游任务数，为0时就绪
+        let mut waiting: HashMap<i64, usize> = order
+            .iter()
+            .map(|&id| (id, graph.upstream_of(id).len()))
+            .collect();
+        let mut ready: VecDeque<i64> = order
+            .iter()
+            .copied()
+            .filter(|id| waiting[id] == 0)
+            .collect();
+        let mut join_set = JoinSet::new();
+        let mut spawned = HashMap::new();
+
+        loop {
+            while join_set.len() < self.concurrency
+                && let Some(trans_task_id) = ready.pop_front()
+            {
+                let node = nodes.get_mut(&trans_task_id).unwrap();
+                node.start();
+                self.update_node(node).await;
+                let trans_task_service = self.trans_task_service.clone();
+                let handle = join_set.spawn(async move {
+                    trans_task_service
+                        .run_task(trans_task_id, operator_id)
+                        .await
+                });
+                spawned.insert(handle.id(), trans_task_id);
+            }
+
+            let Some(joined) = join_set.join_next_with_id().await else {
+                break;
+            };
+            let (trans_task_id, result) = match joined {
+                Ok((id, result)) => (spawned.remove(&id), result),
+                Err(e) => (
+                    spawned.remove(&e.id()),
+                    Err(AppError::TransError(e.to_string())),
+                ),
+            };
+            let Some(trans_task_id) = trans_task_id else {
+                continue;
+            };
+            let node = nodes.get_mut(&trans_task_id).unwrap();
+            if finish_node(node, result) {
+                for downstream_id in graph.downstream_of(trans_task_id) {
+                    if let Some(count) = waiting.get_mut(&downstream_id) {
+                        *count -= 1;
+                        if *count == 0 {
+                            ready.push_back(downstream_id);
+                        }
+                    }
+                }
+            }
+            self.update_node(node).await;
+        }
+
+        // 未能就绪的任务均因上游任务失败而跳过
+        let mut failed = false;
+        for trans_task_id in order {
+            let node = nodes.get_mut(trans_task_id).unwrap();
+            if node.status == DAG_NODE_STATUS_PENDING {
+                node.finish(
+                    DAG_NODE_STATUS_SKIPPED,
+                    Some(String::from("上游任务失败")),
+                );
+                self.update_node(node).await;
+            }
+            failed |= node.status != DAG_NODE_STATUS_SUCCESS;
+        }
+        dag_run.finish(if failed {
+            DAG_RUN_STATUS_FAILED
+        } else {
+            DAG_RUN_STATUS_SUCCESS
+        });
+        let trans_dag_run_id = dag_run.trans_dag_run_id.unwrap();
+        if let Err(e) = self
+            .trans_dag_run_repo
+            .update_by_id(&dag_run, &trans_dag_run_id)
+            .await
+        {
+            tracing::error!("更新DAG执行记录失败: {:?}", e);
+        }
+        tracing::info!(
+            "DAG执行结束: {}, 状态 {}, 耗时 {} ms",
+            trans_dag_run_id,
+            dag_run.status,
+            dag_run.duration_ms
+        );
+    }
+
+    async fn update_node(&self, node: &TransDagRunNode) {
+        let trans_dag_run_node_id = node.trans_dag_run_node_id.unwrap();
+        if let Err(e) = self
+            .trans_dag_run_node_repo
+            .update_by_id(node, &trans_dag_run_node_id)
+            .await
+        {
+            tracing::error!("更新DAG任务状态失败: {:?}", e);
+        }
+    }
+}
+
+// 记录任务的执行结果，仅执行成功时返回true；暂停等未完成的执行按失败处理
+fn finish_node(
+    node: &mut TransDagRunNode,
+    result: Result<TransTaskRunListVo, AppError>,
+) -> bool {
+    match result {
+        Ok(run) => {
+            node.trans_task_run_id = Some(run.trans_task_run_id);
+            if run.status == RUN_STATUS_SUCCESS {
+                node.finish(DAG_NODE_STATUS_SUCCESS, None);
+                true
+            } else {
+                node.finish(
+                    DAG_NODE_STATUS_FAILED,
+                    Some(format!("任务执行状态为 {}", run.status)),
+                );
+                false
+            }
+        }
+        Err(e) => {
+            node.finish(DAG_NODE_STATUS_FAILED, Some(e.to_string()));
+            false
+        }
+    }
+}
+
+fn to_node_vo(task: &TransTask) -> TransDagNodeVo {
+    TransDagNodeVo {
+        trans_task_id: task.trans_task_id.unwrap(),
+        table_name: task.table_name.clone(),
+        table_comment: task.table_comment.clone(),
+        run_state: task.run_state.clone(),
+    }
+}
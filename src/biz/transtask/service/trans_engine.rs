@@ -0,0 +1,954 @@
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use duckdb::{
+    DuckdbConnectionManager, Transaction, appender_params_from_iter,
+    types::{TimeUnit, Value as DuckValue},
+};
+use r2d2::Pool;
+use r2d2_mysql::{
+    MySqlConnectionManager,
+    mysql::{Value as MyValue, prelude::Queryable},
+};
+
+use crate::{
+    app::Infrastructure,
+    biz::{
+        datasource::{
+            model::data_source::DataSourceDetailVo,
+            service::data_source_service::DataSourceService,
+        },
+        transtask::{
+            model::{
+                trans_backfill::BackfillWindow,
+                trans_plan::TransPlanVo,
+                trans_task::{
+                    PARQUET_EXPORT_DELTA, PARQUET_EXPORT_TABLE, SYNC_MODE_CDC,
+                    SYNC_MODE_FULL, TransTask,
+                },
+                trans_task_column::TransTaskColumn,
+                trans_task_quality_result::TransTaskQualityResult,
+                trans_task_quality_rule::TransTaskQualityRule,
+            },
+            service::{
+                binlog_cdc::{BinlogCdc, BinlogPosition},
+                parquet_export::ParquetExport,
+                quality_check::{self, QualityCheck},
+                run_control::{RunControl, RunSignal},
+                source_query::SourceQuery,
+                trans_chunk::ChunkReader,
+                trans_plan::{LoadPlan, RowEstimate},
+                trans_progress::ProgressReporter,
+                type_mapping,
+            },
+        },
+    },
+    error::error::AppError,
+    util::redis_lock::LockHolder,
+};
+
+// 每批次写入DuckDB的行数
+pub const BATCH_SIZE: u64 = 10_000;
+
+// 每读取该行数检查一次是否需要上报进度
+pub const PROGRESS_ROWS: u64 = 1_000;
+
+// 源表列信息，取自 information_schema.columns，并附带列映射确定的目标列
+#[derive(Clone, Debug)]
+pub struct SourceColumn {
+    pub name: String,
+    pub data_type: String,
+    pub column_type: String,
+    pub numeric_precision: Option<u64>,
+    pub numeric_scale: Option<u64>,
+    pub target_name: String,
+    pub target_type: String,
+    // 源值为NULL时写入的默认值
+    pub default_value: Option<String>,
+}
+
+impl SourceColumn {
+    // 目标列默认与源列同名，并取默认类型映射
+    pub fn new(
+        name: String,
+        data_type: String,
+        column_type: String,
+        numeric_precision: Option<u64>,
+        numeric_scale: Option<u64>,
+    ) -> Self {
+        let target_type = type_mapping::duck_type(
+            &data_type,
+            &column_type,
+            numeric_precision,
+            numeric_scale,
+        );
+        Self {
+            target_name: name.clone(),
+            name,
+            data_type,
+            column_type,
+            numeric_precision,
+            numeric_scale,
+            target_type,
+            default_value: None,
+        }
+    }
+
+    // MySQL列类型对应的默认DuckDB列类型
+    pub fn duck_type(&self) -> String {
+        type_mapping::duck_type(
+            &self.data_type,
+            &self.column_type,
+            self.numeric_precision,
+            self.numeric_scale,
+        )
+    }
+
+    fn is_binary(&self) -> bool {
+        self.duck_type() == "BLOB"
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self.data_type.as_str(),
+            "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint"
+        )
+    }
+
+    // 将MySQL二进制协议返回的值转换为DuckDB的值，NULL按列映射的默认值写入
+    pub fn to_duck_value(&self, value: MyValue) -> DuckValue {
+        match (self.convert_value(value), &self.default_value) {
+            (DuckValue::Null, Some(default_value)) => {
+                DuckValue::Text(default_value.clone())
+            }
+            (value, _) => value,
+        }
+    }
+
+    fn convert_value(&self, value: MyValue) -> DuckValue {
+        match value {
+            MyValue::NULL => DuckValue::Null,
+            MyValue::Int(v) => DuckValue::BigInt(v),
+            MyValue::UInt(v) => DuckValue::UBigInt(v),
+            MyValue::Float(v) => DuckValue::Float(v),
+            MyValue::Double(v) => DuckValue::Double(v),
+            // BIT按大端无符号整数返回
+            MyValue::Bytes(v) if self.data_type == "bit" => {
+                let bits = v
+                    .iter()
+                    .fold(0u64, |bits, byte| (bits << 8) | *byte as u64);
+                if self.column_type == "bit(1)" {
+                    DuckValue::Boolean(bits != 0)
+                } else {
+                    DuckValue::UBigInt(bits)
+                }
+            }
+            MyValue::Bytes(v) if self.is_binary() => DuckValue::Blob(v),
+            MyValue::Bytes(v) => {
+                DuckValue::Text(String::from_utf8_lossy(&v).into_owned())
+            }
+            MyValue::Date(year, month, day, hour, minute, second, micros) => {
+                // 零日期（0000-00-00）等非法日期按NULL处理
+                let Some(datetime) = NaiveDate::from_ymd_opt(
+                    year as i32,
+                    month as u32,
+                    day as u32,
+                )
+                .and_then(|date| {
+                    date.and_hms_micro_opt(
+                        hour as u32,
+                        minute as u32,
+                        second as u32,
+                        micros,
+                    )
+                }) else {
+                    return DuckValue::Null;
+                };
+                if self.data_type == "date" {
+                    let days = datetime
+                        .date()
+                        .signed_duration_since(NaiveDate::default())
+                        .num_days();
+                    DuckValue::Date32(days as i32)
+                } else {
+                    DuckValue::Timestamp(
+                        TimeUnit::Microsecond,
+                        datetime
+                            .signed_duration_since(NaiveDateTime::default())
+                            .num_microseconds()
+                            .unwrap_or_default(),
+                    )
+                }
+            }
+            MyValue::Time(negative, days, hours, minutes, seconds, micros) => {
+                let micros = ((days as i64 * 24 + hours as i64) * 3600
+                    + minutes as i64 * 60
+                    + seconds as i64)
+                    * 1_000_000
+                    + micros as i64;
+                let micros = if negative { -micros } else { micros };
+                DuckValue::Interval {
+                    months: 0,
+                    days: 0,
+                    nanos: micros * 1000,
+                }
+            }
+        }
+    }
+}
+
+// 一次传输的执行结果
+#[derive(Clone, Debug, Default)]
+pub struct TransOutcome {
+    pub rows_read: u64,
+    pub rows_written: u64,
+    pub bytes: u64,
+    // 加载完成后目标表的总行数
+    pub target_rows: u64,
+    // 合并策略下因源表删除而从目标表删除的行数
+    pub rows_deleted: u64,
+    pub batches: u64,
+    // 增量模式被暂停，仅提交了部分数据
+    pub paused: bool,
+    // 本次写入数据中水位列的最大值，仅增量模式有值
+    pub watermark: Option<String>,
+    // 数据已提交但导出Parquet失败时的错误信息
+    pub export_error: Option<String>,
+    // 提交前的数据质量检查结果
+    pub quality_results: Vec<TransTaskQualityResult>,
+    // 要求回滚的质量规则未通过，本次写入已回滚
+    pub rolled_back: bool,
+    // CDC模式本次已应用到的binlog位置
+    pub binlog_position: Option<BinlogPosition>,
+}
+
+// 任务的附属配置：列映射与数据质量规则，回填时附带本次的水位列区间
+#[derive(Clone, Debug, Default)]
+pub struct TaskSettings {
+    pub mappings: Vec<TransTaskColumn>,
+    pub rules: Vec<TransTaskQualityRule>,
+    pub backfill: Option<BackfillWindow>,
+}
+
+impl TransOutcome {
+    // 阻断规则未通过时本次执行应视为失败
+    pub fn quality_error(&self) -> Option<AppError> {
+        quality_check::blocking_failure(&self.quality_results).map(|failure| {
+            if self.rolled_back {
+                AppError::QualityCheckFailed(format!(
+                    "{}, 已回滚本次写入",
+                    failure
+                ))
+            } else {
+                AppError::QualityCheckFailed(failure)
+            }
+        })
+    }
+}
+
+// 数据传输引擎：从MySQL数据源读取整表并写入本地DuckDB数仓，
+// 支持直接追加与按主键合并两种加载策略，以及按主键区间并行读取，
+// 加载后可将目标表或本次增量导出为Parquet
+#[derive(Clone)]
+pub struct TransEngine {
+    duck_pool: Pool<DuckdbConnectionManager>,
+    // 单个任务读取源表的最大并行度
+    max_parallelism: usize,
+    // Parquet导出的根目录
+    parquet_dir: String,
+}
+
+impl TransEngine {
+    pub fn new(infra: &Infrastructure) -> Self {
+        Self {
+            duck_pool: infra.pool.duck_pool.clone(),
+            max_parallelism: infra.config.transfer.max_parallelism.max(1),
+            parquet_dir: infra.config.export.parquet_dir.clone(),
+        }
+    }
+
+    // 预览一次传输：按执行时相同的规则解析抽取语句、目标表结构、主键与并行区间，
+    // 行数取统计信息或 EXPLAIN 的估算值，不读取也不写入数据；
+    // 该方法会阻塞当前线程，需在 spawn_blocking 中调用
+    pub fn plan(
+        &self,
+        data_source: &DataSourceDetailVo,
+        task: &TransTask,
+        settings: &TaskSettings,
+    ) -> Result<TransPlanVo, AppError> {
+        let query = SourceQuery::new(
+            &task.table_name,
+            task.source_filter.as_deref(),
+            task.source_sql.as_deref(),
+            task.watermark_value.as_deref(),
+            Local::now().date_naive(),
+        )?;
+        let parallelism =
+            LoadPlan::parallelism(task, &query, self.max_parallelism);
+        let source_pool = DataSourceService::create_pool(data_source, 1)?;
+        let mut source = source_pool.get()?;
+        let plan = LoadPlan::resolve(
+            &mut *source,
+            data_source,
+            task,
+            settings,
+            query,
+            parallelism,
+            RowEstimate::Explain,
+        )?;
+
+        let schema = &data_source.code;
+        let duck = self.duck_pool.get()?;
+        let target_exists: bool = duck.query_row(
+            "SELECT count(*) > 0 FROM information_schema.tables \
+             WHERE table_schema = ? AND table_name = ?",
+            [schema, &task.table_name],
+            |row| row.get(0),
+        )?;
+        Ok(plan.to_vo(schema, task, target_exists))
+    }
+
+    // 按任务的同步模式执行一次传输，目标表位于以数据源编码命名的schema下；
+    // 该方法会阻塞当前线程，需在 spawn_blocking 中调用
+    pub fn run(
+        &self,
+        data_source: &DataSourceDetailVo,
+        task: &TransTask,
+        settings: &TaskSettings,
+        holder: &LockHolder,
+        progress: &mut ProgressReporter,
+        control: &RunControl,
+    ) -> Result<TransOutcome, AppError> {
+        if task.sync_mode == SYNC_MODE_CDC {
+            let mut duck = self.duck_pool.get()?;
+            let cdc = BinlogCdc {
+                data_source,
+                task,
+                settings,
+                holder,
+                control,
+                parquet_dir: &self.parquet_dir,
+            };
+            return cdc.run(&mut duck, progress);
+        }
+        let query = SourceQuery::new(
+            &task.table_name,
+            task.source_filter.as_deref(),
+            task.source_sql.as_deref(),
+            task.watermark_value.as_deref(),
+            Local::now().date_naive(),
+        )?;
+        let parallelism =
+            LoadPlan::parallelism(task, &query, self.max_parallelism);
+        // 除读取线程外，一个连接用于读取元数据，一个用于中途停止时终止源端查询
+        let source_pool = DataSourceService::create_pool(
+            data_source,
+            parallelism as u32 + 2,
+        )?;
+        let mut source = source_pool.get()?;
+        let LoadPlan {
+            query,
+            parallelism,
+            columns,
+            watermark_index,
+            merge,
+            primary_key,
+            conditions,
+            params,
+            order_by,
+            estimated_rows,
+            key_ranges,
+        } = LoadPlan::resolve(
+            &mut *source,
+            data_source,
+            task,
+            settings,
+            query,
+            parallelism,
+            RowEstimate::Count,
+        )?;
+        progress.set_total_rows(estimated_rows);
+
+        let schema = &data_source.code;
+        let mut duck = self.duck_pool.get()?;
+        duck.execute_batch(&format!(
+            "CREATE SCHEMA IF NOT EXISTS {}",
+            duck_ident(schema)
+        ))?;
+
+        // 建表与写入放在同一事务中，失败时目标表保持原样
+        let tx = duck.transaction()?;
+        tx.execute_batch(&create_table_sql(
+            schema,
+            &task.table_name,
+            &columns,
+            task.sync_mode == SYNC_MODE_FULL && !merge,
+        ))?;
+
+        // 回填先删除目标表中该区间的行，再写入源端该区间的数据
+        let replaced_rows = match &settings.backfill {
+            Some(window) => {
+                let watermark_column =
+                    task.watermark_column.as_deref().unwrap_or_default();
+                let column = columns
+                    .iter()
+                    .find(|column| column.name == watermark_column)
+                    .ok_or_else(|| {
+                        AppError::TransError(format!(
+                            "水位列不存在: {}",
+                            watermark_column
+                        ))
+                    })?;
+                let column = duck_ident(&column.target_name);
+                tx.execute(
+                    &format!(
+                        "DELETE FROM {}.{} WHERE CAST({} AS TIMESTAMP) >= \
+                         CAST(? AS TIMESTAMP) AND CAST({} AS TIMESTAMP) < \
+                         CAST(? AS TIMESTAMP)",
+                        duck_ident(schema),
+                        duck_ident(&task.table_name),
+                        column,
+                        column
+                    ),
+                    [window.start_string(), window.end_string()],
+                )? as u64
+            }
+            None => 0,
+        };
+
+        // 合并策略先写入暂存表，再按主键替换目标表中的行；
+        // 按增量导出Parquet时同样经暂存表写入，提交后从暂存表导出本次数据
+        let export_delta = task.parquet_export == PARQUET_EXPORT_DELTA;
+        let stage_table = format!("{}__stage", task.table_name);
+        let load_table = if merge || export_delta {
+            tx.execute_batch(&create_table_sql(
+                schema,
+                &stage_table,
+                &columns,
+                true,
+            ))?;
+            &stage_table
+        } else {
+            &task.table_name
+        };
+
+        let mut outcome = TransOutcome {
+            rows_deleted: replaced_rows,
+            ..Default::default()
+        };
+        if let Some(ranges) = key_ranges {
+            tracing::debug!(
+                "传输任务 {} 按 {} 个主键区间并行读取, 并行度 {}",
+                task.table_name,
+                ranges.len(),
+                parallelism
+            );
+            let mut appender = tx.appender_to_db(load_table, schema)?;
+            let reader = ChunkReader {
+                source_pool: &source_pool,
+                query: &query,
+                columns: &columns,
+                key: &primary_key[0],
+                ranges,
+                parallelism,
+            };
+            // 读取线程按区间交付，行数不一定恰好凑满批次，按累计行数刷新
+            let mut flushed_rows = 0;
+            reader.read(control, |batch| {
+                outcome.bytes += batch.bytes;
+                for row in batch.rows {
+                    appender.append_row(appender_params_from_iter(row))?;
+                    outcome.rows_read += 1;
+                    outcome.rows_written += 1;
+                }
+                if outcome.rows_written - flushed_rows >= BATCH_SIZE {
+                    appender.flush()?;
+                    flushed_rows = outcome.rows_written;
+                    progress.batch_written(&outcome);
+                } else {
+                    progress.rows_processed(&outcome);
+                }
+                Ok(())
+            })?;
+            appender.flush()?;
+            if outcome.rows_written > flushed_rows {
+                progress.batch_written(&outcome);
+            }
+            outcome.batches = progress.batches();
+        } else {
+            let mut appender = tx.appender_to_db(load_table, schema)?;
+            let select_sql = query.select_sql(
+                &mysql_columns(&columns),
+                &conditions,
+                order_by.as_deref(),
+            );
+            let connection_id = source.connection_id();
+            let mut rows = source.exec_iter(select_sql, params)?;
+            let mut interrupted = None;
+            for row in rows.by_ref() {
+                let values = row?.unwrap();
+                let watermark = watermark_index
+                    .and_then(|index| watermark_string(&values[index]));
+                match control.signal() {
+                    RunSignal::Cancel => {
+                        interrupted = Some(AppError::TransCancelled);
+                        break;
+                    }
+                    // 非增量模式无法从中途继续，暂停时回滚
+                    RunSignal::Pause if watermark_index.is_none() => {
+                        interrupted = Some(AppError::TransPaused);
+                        break;
+                    }
+                    // 增量模式在水位值变化处停止并提交已写入部分，
+                    // 保证恢复后从高水位之后继续时不遗漏同值的行
+                    RunSignal::Pause
+                        if watermark.is_some()
+                            && watermark != outcome.watermark =>
+                    {
+                        outcome.paused = true;
+                        break;
+                    }
+                    _ => {}
+                }
+                outcome.rows_read += 1;
+                if watermark.is_some() {
+                    outcome.watermark = watermark;
+                }
+                outcome.bytes += values.iter().map(value_size).sum::<u64>();
+                appender.append_row(appender_params_from_iter(
+                    values
+                        .into_iter()
+                        .zip(&columns)
+                        .map(|(value, column)| column.to_duck_value(value)),
+                ))?;
+                outcome.rows_written += 1;
+                if outcome.rows_written.is_multiple_of(BATCH_SIZE) {
+                    appender.flush()?;
+                    progress.batch_written(&outcome);
+                    tracing::debug!(
+                        "传输任务 {} 已写入 {} 行",
+                        task.table_name,
+                        outcome.rows_written
+                    );
+                } else if outcome.rows_read.is_multiple_of(PROGRESS_ROWS) {
+                    progress.rows_processed(&outcome);
+                }
+            }
+            if interrupted.is_some() || outcome.paused {
+                kill_query(&source_pool, connection_id);
+            }
+            drop(rows);
+            if let Some(e) = interrupted {
+                return Err(e);
+            }
+            appender.flush()?;
+            if !outcome.rows_written.is_multiple_of(BATCH_SIZE) {
+                progress.batch_written(&outcome);
+            }
+            outcome.batches = progress.batches();
+        }
+
+        if merge {
+            tx.execute(
+                &format!(
+                    "DELETE FROM {}.{} USING {}.{} WHERE {}",
+                    duck_ident(schema),
+                    duck_ident(&task.table_name),
+                    duck_ident(schema),
+                    duck_ident(&stage_table),
+                    key_join(&primary_key, &task.table_name, &stage_table)
+                ),
+                [],
+            )?;
+        }
+        if merge || export_delta {
+            tx.execute_batch(&format!(
+                "INSERT INTO {}.{} ({}) SELECT {} FROM {}.{}",
+                duck_ident(schema),
+                duck_ident(&task.table_name),
+                duck_columns(&columns),
+                duck_columns(&columns),
+                duck_ident(schema),
+                duck_ident(&stage_table)
+            ))?;
+            if !export_delta {
+                tx.execute_batch(&format!(
+                    "DROP TABLE {}.{}",
+                    duck_ident(schema),
+                    duck_ident(&stage_table)
+                ))?;
+            }
+        }
+        if merge {
+            // 暂停时只加载了部分数据，不能据此判断源表删除；回填只处理区间内的行
+            if task.propagate_deletes
+                && !outcome.paused
+                && settings.backfill.is_none()
+            {
+                outcome.rows_deleted = delete_missing_keys(
+                    &mut *source,
+                    &query,
+                    &tx,
+                    schema,
+                    &task.table_name,
+                    &primary_key,
+                )?;
+            }
+        }
+        // 暂停时只加载了部分数据，不做质量检查
+        if !outcome.paused && !settings.rules.is_empty() {
+            let check = QualityCheck {
+                conn: &tx,
+                schema,
+                task,
+            };
+            outcome.quality_results = check.run(&settings.rules)?;
+            if quality_check::requires_rollback(&outcome.quality_results) {
+                tx.rollback()?;
+                tracing::warn!(
+                    "传输任务 {} 数据质量检查未通过, 已回滚本次写入",
+                    task.table_name
+                );
+                outcome.rolled_back = true;
+                outcome.watermark = None;
+                outcome.target_rows = task.row_count.max(0) as u64;
+                return Ok(outcome);
+            }
+        }
+        // 租约丢失说明其他实例可能已接手该任务，放弃提交
+        if !holder.is_held() {
+            return Err(AppError::TransError(format!(
+                "分布式锁租约已丢失, 放弃提交: {}, fencing token {}",
+                task.table_name,
+                holder.token()
+            )));
+        }
+        tx.commit()?;
+
+        // 数据已提交，导出失败只记录错误，不影响本次传输结果
+        let export_table = match task.parquet_export.as_str() {
+            PARQUET_EXPORT_TABLE => Some(task.table_name.as_str()),
+            PARQUET_EXPORT_DELTA => Some(stage_table.as_str()),
+            _ => None,
+        };
+        if let Some(export_table) = export_table {
+            let export = ParquetExport {
+                base_dir: &self.parquet_dir,
+                schema,
+                task,
+            };
+            match export.export(
+                &duck,
+                export_table,
+                task.parquet_export == PARQUET_EXPORT_TABLE,
+            ) {
+                Ok(path) => tracing::debug!(
+                    "传输任务 {} 已导出Parquet: {}",
+                    task.table_name,
+                    path.display()
+                ),
+                Err(e) => {
+                    tracing::error!(
+                        "传输任务 {} 导出Parquet失败: {:?}",
+                        task.table_name,
+                        e
+                    );
+                    outcome.export_error = Some(e.to_string());
+                }
+            }
+        }
+        if export_delta {
+            duck.execute_batch(&format!(
+                "DROP TABLE IF EXISTS {}.{}",
+                duck_ident(schema),
+                duck_ident(&stage_table)
+            ))?;
+        }
+
+        outcome.target_rows = duck.query_row(
+            &format!(
+                "SELECT count(*) FROM {}.{}",
+                duck_ident(schema),
+                duck_ident(&task.table_name)
+            ),
+            [],
+            |row| row.get(0),
+        )?;
+
+        tracing::info!(
+            "传输任务完成: {}.{} -> {}.{}, 写入 {} 行, 删除 {} 行",
+            data_source.db_name,
+            task.table_name,
+            schema,
+            task.table_name,
+            outcome.rows_written,
+            outcome.rows_deleted
+        );
+        Ok(outcome)
+    }
+}
+
+// 读取源端（含过滤条件）的全部主键值，删除目标表中源端已不存在的行，返回删除的行数
+fn delete_missing_keys(
+    source: &mut impl Queryable,
+    query: &SourceQuery,
+    tx: &Transaction,
+    schema: &str,
+    table_name: &str,
+    primary_key: &[SourceColumn],
+) -> Result<u64, AppError> {
+    let keys_table = format!("{}__keys", table_name);
+    tx.execute_batch(&create_table_sql(
+        schema,
+        &keys_table,
+        primary_key,
+        true,
+    ))?;
+    {
+        let mut appender = tx.appender_to_db(&keys_table, schema)?;
+        let select_sql =
+            query.select_sql(&mysql_columns(primary_key), &[], None);
+        for row in source.exec_iter(select_sql, query.params())? {
+            appender.append_row(appender_params_from_iter(
+                row?.unwrap()
+                    .into_iter()
+                    .zip(primary_key)
+                    .map(|(value, column)| column.to_duck_value(value)),
+            ))?;
+        }
+        appender.flush()?;
+    }
+
+    let deleted = tx.execute(
+        &format!(
+            "DELETE FROM {}.{} WHERE NOT EXISTS (SELECT 1 FROM {}.{} WHERE {})",
+            duck_ident(schema),
+            duck_ident(table_name),
+            duck_ident(schema),
+            duck_ident(&keys_table),
+            key_join(primary_key, table_name, &keys_table)
+        ),
+        [],
+    )?;
+    tx.execute_batch(&format!(
+        "DROP TABLE {}.{}",
+        duck_ident(schema),
+        duck_ident(&keys_table)
+    ))?;
+    Ok(deleted as u64)
+}
+
+// 中途停止读取时终止源端查询，否则丢弃结果集时仍会读完剩余数据
+pub fn kill_query(
+    source_pool: &Pool<MySqlConnectionManager>,
+    connection_id: u32,
+) {
+    let killed =
+        source_pool
+            .get()
+            .map_err(AppError::from)
+            .and_then(|mut conn| {
+                conn.query_drop(format!("KILL QUERY {}", connection_id))
+                    .map_err(AppError::from)
+            });
+    if let Err(e) = killed {
+        tracing::warn!("终止源端查询失败: {:?}", e);
+    }
+}
+
+// 源库中的表
+#[derive(Clone, Debug)]
+pub struct SourceTable {
+    pub name: String,
+    pub comment: String,
+}
+
+// 读取源库的全部表（不含视图），按表名排序
+pub fn load_tables(
+    conn: &mut impl Queryable,
+    db_name: &str,
+) -> Result<Vec<SourceTable>, AppError> {
+    let tables = conn.exec_map(
+        "SELECT TABLE_NAME, TABLE_COMMENT FROM information_schema.TABLES \
+         WHERE TABLE_SCHEMA = ? AND TABLE_TYPE = 'BASE TABLE' \
+         ORDER BY TABLE_NAME",
+        (db_name,),
+        |(name, comment): (String, Option<String>)| SourceTable {
+            name,
+            comment: comment.unwrap_or_default(),
+        },
+    )?;
+    Ok(tables)
+}
+
+// 读取源表主键列，按主键内的顺序返回
+pub fn load_primary_key(
+    conn: &mut impl Queryable,
+    db_name: &str,
+    table_name: &str,
+) -> Result<Vec<String>, AppError> {
+    let key_names = conn.exec(
+        "SELECT COLUMN_NAME FROM information_schema.KEY_COLUMN_USAGE \
+         WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? \
+         AND CONSTRAINT_NAME = 'PRIMARY' ORDER BY ORDINAL_POSITION",
+        (db_name, table_name),
+    )?;
+    Ok(key_names)
+}
+
+// 读取源表的列定义，目标列取默认映射
+pub fn load_columns(
+    conn: &mut impl Queryable,
+    db_name: &str,
+    table_name: &str,
+) -> Result<Vec<SourceColumn>, AppError> {
+    let columns = conn.exec_map(
+        "SELECT COLUMN_NAME, DATA_TYPE, COLUMN_TYPE, NUMERIC_PRECISION, \
+         NUMERIC_SCALE FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION",
+        (db_name, table_name),
+        |(name, data_type, column_type, numeric_precision, numeric_scale): (
+            String,
+            String,
+            String,
+            Option<u64>,
+            Option<u64>,
+        )| {
+            SourceColumn::new(
+                name,
+                data_type.to_lowercase(),
+                column_type.to_lowercase(),
+                numeric_precision,
+                numeric_scale,
+            )
+        },
+    )?;
+    Ok(columns)
+}
+
+// 按列映射选择源列并设置目标列，未配置映射时保留全部列
+pub fn apply_mappings(
+    columns: Vec<SourceColumn>,
+    mappings: &[TransTaskColumn],
+) -> Result<Vec<SourceColumn>, AppError> {
+    if mappings.is_empty() {
+        return Ok(columns);
+    }
+    let mut mapped = Vec::new();
+    for mapping in mappings.iter().filter(|mapping| mapping.included) {
+        let mut column = columns
+            .iter()
+            .find(|column| column.name == mapping.source_column)
+            .cloned()
+            .ok_or_else(|| {
+                AppError::TransError(format!(
+                    "列映射的源列不存在: {}",
+                    mapping.source_column
+                ))
+            })?;
+        column.target_name = mapping.target_column.clone();
+        column.target_type = mapping.target_type.clone();
+        column.default_value = mapping.default_value.clone();
+        mapped.push(column);
+    }
+    if mapped.is_empty() {
+        return Err(AppError::TransError(String::from(
+            "列映射没有需要传输的列",
+        )));
+    }
+    Ok(mapped)
+}
+
+// 全量模式重建目标表，其余模式仅在目标表不存在时建表
+pub fn create_table_sql(
+    schema: &str,
+    table_name: &str,
+    columns: &[SourceColumn],
+    replace: bool,
+) -> String {
+    format!(
+        "{} {}.{} ({})",
+        if replace {
+            "CREATE OR REPLACE TABLE"
+        } else {
+            "CREATE TABLE IF NOT EXISTS"
+        },
+        duck_ident(schema),
+        duck_ident(table_name),
+        columns
+            .iter()
+            .map(|column| format!(
+                "{} {}",
+                duck_ident(&column.target_name),
+                column.target_type
+            ))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+// 将水位列的取值转换为可回传给MySQL比较的字符串，NULL不参与水位计算
+fn watermark_string(value: &MyValue) -> Option<String> {
+    match value {
+        MyValue::NULL => None,
+        MyValue::Bytes(v) => Some(String::from_utf8_lossy(v).into_owned()),
+        _ => Some(value.as_sql(true).trim_matches('\'').to_string()),
+    }
+}
+
+// 按MySQL二进制协议中的取值估算字节数
+pub fn value_size(value: &MyValue) -> u64 {
+    match value {
+        MyValue::NULL => 0,
+        MyValue::Bytes(v) => v.len() as u64,
+        MyValue::Float(_) => 4,
+        _ => 8,
+    }
+}
+
+// 两张表按主键列逐一相等的连接条件
+pub fn key_join(
+    primary_key: &[SourceColumn],
+    left: &str,
+    right: &str,
+) -> String {
+    primary_key
+        .iter()
+        .map(|column| {
+            format!(
+                "{}.{} = {}.{}",
+                duck_ident(left),
+                duck_ident(&column.target_name),
+                duck_ident(right),
+                duck_ident(&column.target_name)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+pub fn duck_columns(columns: &[SourceColumn]) -> String {
+    columns
+        .iter()
+        .map(|column| duck_ident(&column.target_name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn mysql_columns(columns: &[SourceColumn]) -> String {
+    columns
+        .iter()
+        .map(|column| mysql_ident(&column.name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn duck_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+pub fn duck_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+pub fn mysql_ident(ident: &str) -> String {
+    format!("`{}`", ident.replace('`', "``"))
+}
@@ -0,0 +1,393 @@
+use r2d2_mysql::mysql::{Row, Value as MyValue, prelude::Queryable};
+
+use crate::{
+    biz::{
+        datasource::model::data_source::DataSourceDetailVo,
+        transtask::{
+            model::{
+                trans_plan::{
+                    TransPlanChunkVo, TransPlanColumnVo, TransPlanVo,
+                },
+                trans_task::{
+                    LOAD_STRATEGY_MERGE, PARQUET_EXPORT_DELTA, SYNC_MODE_CDC,
+                    SYNC_MODE_FULL, SYNC_MODE_INCREMENTAL, TransTask,
+                },
+            },
+            service::{
+                source_query::SourceQuery,
+                trans_chunk::{KeyRange, bound_string, plan_key_ranges},
+                trans_engine::{
+                    SourceColumn, TaskSettings, apply_mappings,
+                    create_table_sql, duck_ident, load_columns,
+                    load_primary_key, mysql_columns, mysql_ident,
+                },
+            },
+        },
+    },
+    error::error::AppError,
+};
+
+// 行数的估算方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RowEstimate {
+    // 有过滤条件时在源端按条件计数，用于执行时上报进度
+    Count,
+    // 有过滤条件时取 EXPLAIN 的估算值，不扫描源表
+    Explain,
+}
+
+// 一次传输在读取数据之前确定的执行方式：抽取语句、目标列、主键、并行区间等，
+// 执行与预览共用，保证预览结果与实际执行一致
+pub struct LoadPlan {
+    pub query: SourceQuery,
+    pub parallelism: usize,
+    pub columns: Vec<SourceColumn>,
+    // 增量模式下水位列在 columns 中的序号
+    pub watermark_index: Option<usize>,
+    pub merge: bool,
+    // 映射后的主键列，未使用或列映射未包含全部主键列时为空
+    pub primary_key: Vec<SourceColumn>,
+    // 抽取语句附加的过滤条件与排序，参数排在 query.params() 之后
+    pub conditions: Vec<String>,
+    pub params: Vec<MyValue>,
+    pub order_by: Option<String>,
+    pub estimated_rows: Option<u64>,
+    pub key_ranges: Option<Vec<KeyRange>>,
+}
+
+impl LoadPlan {
+    // 增量模式需按水位列顺序读取，自定义SELECT无法按源表主键划分区间，均为单线程
+    pub fn parallelism(
+        task: &TransTask,
+        query: &SourceQuery,
+        max_parallelism: usize,
+    ) -> usize {
+        if task.sync_mode == SYNC_MODE_INCREMENTAL || query.is_custom() {
+            1
+        } else {
+            (task.parallelism.max(1) as usize).min(max_parallelism)
+        }
+    }
+
+    pub fn resolve(
+        source: &mut impl Queryable,
+        data_source: &DataSourceDetailVo,
+        task: &TransTask,
+        settings: &TaskSettings,
+        query: SourceQuery,
+        parallelism: usize,
+        estimate: RowEstimate,
+    ) -> Result<Self, AppError> {
+        let columns = if query.is_custom() {
+            query.load_columns(source)?
+        } else {
+            load_columns(source, &data_source.db_name, &task.table_name)?
+        };
+        if columns.is_empty() {
+            return Err(AppError::TransError(format!(
+                "源表不存在或没有列: {}.{}",
+                data_source.db_name, task.table_name
+            )));
+        }
+        let columns = apply_mappings(columns, &settings.mappings)?;
+
+        // 增量模式按水位列排序读取，最后一行即为新的高水位
+        let watermark_index = if task.sync_mode == SYNC_MODE_INCREMENTAL {
+            let watermark_column =
+                task.watermark_column.as_deref().unwrap_or_default();
+            let index = columns
+                .iter()
+                .position(|column| column.name == watermark_column)
+                .ok_or_else(|| {
+                    AppError::TransError(format!(
+                        "水位列不存在: {}",
+                        watermark_column
+                    ))
+                })?;
+            Some(index)
+        } else {
+            None
+        };
+
+        // 合并策略依赖源表主键定位目标表中已存在的行，并行读取按主键划分区间，
+        // CDC按主键应用变更
+        let merge = task.load_strategy == LOAD_STRATEGY_MERGE;
+        let key_names = if merge
+            || parallelism > 1
+            || task.sync_mode == SYNC_MODE_CDC
+        {
+            load_primary_key(source, &data_source.db_name, &task.table_name)?
+        } else {
+            Vec::new()
+        };
+        let mut primary_key: Vec<SourceColumn> = key_names
+            .iter()
+            .filter_map(|name| {
+                columns.iter().find(|column| &column.name == name).cloned()
+            })
+            .collect();
+        if merge && key_names.is_empty() {
+            return Err(AppError::TransError(format!(
+                "合并策略要求源表存在主键: {}.{}",
+                data_source.db_name, task.table_name
+            )));
+        }
+        // 列映射排除了部分主键列时，既无法合并也无法按主键划分区间
+        if primary_key.len() != key_names.len() {
+            if merge {
+                return Err(AppError::TransError(format!(
+                    "合并策略要求列映射包含全部主键列: {}",
+                    key_names.join(", ")
+                )));
+            }
+            primary_key.clear();
+        }
+
+        // 增量模式只读取高水位之后的行，回填只读取水位列在区间内的行
+        let mut conditions = Vec::new();
+        let mut params = query.params();
+        let mut order_by = None;
+        if let Some(index) = watermark_index {
+            let watermark_column = mysql_ident(&columns[index].name);
+            match &settings.backfill {
+                Some(window) => {
+                    if !matches!(
+                        columns[index].data_type.as_str(),
+                        "date" | "datetime" | "timestamp"
+                    ) {
+                        return Err(AppError::TransError(format!(
+                            "回填要求水位列为日期或时间类型: {}",
+                            columns[index].name
+                        )));
+                    }
+                    conditions.push(format!("{} >= ?", watermark_column));
+                    conditions.push(format!("{} < ?", watermark_column));
+                    params.push(MyValue::from(window.start_string()));
+                    params.push(MyValue::from(window.end_string()));
+                }
+                None => {
+                    if let Some(watermark) = &task.watermark_value {
+                        conditions.push(format!("{} > ?", watermark_column));
+                        params.push(MyValue::from(watermark));
+                    }
+                    order_by = Some(watermark_column);
+                }
+            }
+        }
+        // 回填不推进高水位，暂停时与非增量模式一样回滚
+        let watermark_index =
+            watermark_index.filter(|_| settings.backfill.is_none());
+        let estimated_rows = estimate_rows(
+            source,
+            &data_source.db_name,
+            &task.table_name,
+            &query,
+            &conditions,
+            &params,
+            estimate,
+        );
+
+        // 仅支持按单列主键划分区间，否则退回单线程读取
+        let key_ranges = match primary_key.as_slice() {
+            [key] if parallelism > 1 => plan_key_ranges(
+                source,
+                &task.table_name,
+                key,
+                parallelism,
+                estimated_rows,
+            )?,
+            _ => None,
+        };
+
+        Ok(Self {
+            query,
+            parallelism,
+            columns,
+            watermark_index,
+            merge,
+            primary_key,
+            conditions,
+            params,
+            order_by,
+            estimated_rows,
+            key_ranges,
+        })
+    }
+
+    // 生成预览结果，target_exists 为目标表当前是否已存在
+    pub fn to_vo(
+        &self,
+        schema: &str,
+        task: &TransTask,
+        target_exists: bool,
+    ) -> TransPlanVo {
+        let cdc = task.sync_mode == SYNC_MODE_CDC;
+        let replace = if cdc {
+            !self.cdc_resumable(task, target_exists)
+        } else {
+            task.sync_mode == SYNC_MODE_FULL && !self.merge
+        };
+        let mut ddl = vec![
+            format!("CREATE SCHEMA IF NOT EXISTS {}", duck_ident(schema)),
+            create_table_sql(schema, &task.table_name, &self.columns, replace),
+        ];
+        if !cdc && (self.merge || task.parquet_export == PARQUET_EXPORT_DELTA) {
+            ddl.push(create_table_sql(
+                schema,
+                &format!("{}__stage", task.table_name),
+                &self.columns,
+                true,
+            ));
+        }
+        TransPlanVo {
+            trans_task_id: task.trans_task_id.unwrap_or_default(),
+            sync_mode: task.sync_mode.clone(),
+            load_strategy: task.load_strategy.clone(),
+            strategy: self.describe(task, target_exists),
+            source_sql: self.query.select_sql(
+                &mysql_columns(&self.columns),
+                &self.conditions,
+                self.order_by.as_deref(),
+            ),
+            source_params: self.params.iter().map(bound_string).collect(),
+            estimated_rows: self.estimated_rows,
+            target_table: format!("{}.{}", schema, task.table_name),
+            target_exists,
+            ddl,
+            columns: self
+                .columns
+                .iter()
+                .map(|column| TransPlanColumnVo {
+                    source_name: column.name.clone(),
+                    source_type: column.column_type.clone(),
+                    target_name: column.target_name.clone(),
+                    target_type: column.target_type.clone(),
+                    default_value: column.default_value.clone(),
+                })
+                .collect(),
+            primary_key: self
+                .primary_key
+                .iter()
+                .map(|column| column.name.clone())
+                .collect(),
+            parallelism: self.parallelism,
+            chunks: self
+                .key_ranges
+                .iter()
+                .flatten()
+                .map(|range| TransPlanChunkVo {
+                    lower: range.lower.as_ref().map(bound_string),
+                    upper: range.upper.as_ref().map(bound_string),
+                })
+                .collect(),
+        }
+    }
+
+    // CDC任务已记录binlog位置时从该位置继续，否则重新快照；
+    // 记录的binlog文件是否已被清理需在执行时才能确定
+    fn cdc_resumable(&self, task: &TransTask, target_exists: bool) -> bool {
+        target_exists
+            && task.binlog_file.is_some()
+            && task.binlog_position.is_some()
+    }
+
+    // 按同步模式与加载策略说明本次执行将如何写入目标表
+    fn describe(&self, task: &TransTask, target_exists: bool) -> String {
+        let mut steps = Vec::new();
+        match task.sync_mode.as_str() {
+            SYNC_MODE_CDC if self.cdc_resumable(task, target_exists) => {
+                steps.push(format!(
+                    "从binlog {}:{} 继续读取变更并按主键应用",
+                    task.binlog_file.as_deref().unwrap_or_default(),
+                    task.binlog_position.unwrap_or_default()
+                ));
+            }
+            SYNC_MODE_CDC => steps.push(String::from(
+                "记录当前binlog位置后全量快照重建目标表, 再读取快照期间的变更并按主键应用",
+            )),
+            SYNC_MODE_INCREMENTAL => {
+                let column = task.watermark_column.as_deref().unwrap_or_default();
+                steps.push(match &task.watermark_value {
+                    Some(watermark) => {
+                        format!("按水位列 {} 读取大于 {} 的行", column, watermark)
+                    }
+                    None => format!("尚无高水位, 按水位列 {} 读取全部行", column),
+                });
+            }
+            _ => steps.push(String::from("读取全部行")),
+        }
+        if task.sync_mode != SYNC_MODE_CDC {
+            steps.push(if self.merge {
+                String::from("写入暂存表后按主键替换目标表中已存在的行")
+            } else if task.sync_mode == SYNC_MODE_FULL {
+                String::from("在事务中重建目标表并写入")
+            } else {
+                String::from("追加写入目标表")
+            });
+            if self.merge && task.propagate_deletes {
+                steps.push(String::from("删除目标表中源端已不存在的行"));
+            }
+        }
+        match &self.key_ranges {
+            Some(ranges) => steps.push(format!(
+                "按主键 {} 划分 {} 个区间, {} 个线程并行读取",
+                self.primary_key[0].name,
+                ranges.len(),
+                self.parallelism
+            )),
+            None => steps.push(String::from("单线程读取")),
+        }
+        steps.join(", ")
+    }
+}
+
+// 估算本次待读取的行数：没有过滤条件时取表统计信息中的近似值，
+// 否则按估算方式在源端计数或取 EXPLAIN 的估算值
+fn estimate_rows(
+    conn: &mut impl Queryable,
+    db_name: &str,
+    table_name: &str,
+    query: &SourceQuery,
+    conditions: &[String],
+    params: &[MyValue],
+    estimate: RowEstimate,
+) -> Option<u64> {
+    if query.is_plain() && conditions.is_empty() {
+        // TABLE_ROWS 可能为NULL，按可空类型读取
+        let rows: Result<Option<Option<u64>>, _> = conn.exec_first(
+            "SELECT TABLE_ROWS FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+            (db_name, table_name),
+        );
+        return rows.ok().flatten().flatten();
+    }
+    match estimate {
+        RowEstimate::Count => {
+            let rows: Result<Option<Option<u64>>, _> = conn.exec_first(
+                query.select_sql("COUNT(*)", conditions, None),
+                params.to_vec(),
+            );
+            rows.ok().flatten().flatten()
+        }
+        // 取执行计划第一行的扫描行数与过滤比例
+        RowEstimate::Explain => {
+            let row: Option<Row> = conn
+                .exec_first(
+                    format!(
+                        "EXPLAIN {}",
+                        query.select_sql("1", conditions, None)
+                    ),
+                    params.to_vec(),
+                )
+                .ok()
+                .flatten();
+            let row = row?;
+            let rows = row.get::<Option<u64>, _>("rows").flatten()?;
+            let filtered = row
+                .get::<Option<f64>, _>("filtered")
+                .flatten()
+                .unwrap_or(100.0);
+            Some((rows as f64 * filtered / 100.0).round() as u64)
+        }
+    }
+}
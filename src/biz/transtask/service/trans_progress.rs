@@ -0,0 +1,250 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use r2d2::Pool;
+use redis::Commands;
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc,
+};
+
+use crate::{
+    biz::transtask::{
+        model::{
+            trans_progress::{TransProgressVo, rows_per_sec},
+            trans_task_run::RUN_STATUS_RUNNING,
+        },
+        service::trans_engine::TransOutcome,
+    },
+    error::error::AppError,
+};
+
+// 每个任务的进度广播缓冲，订阅方落后时丢弃旧事件
+const CHANNEL_CAPACITY: usize = 64;
+
+// 两次按时间上报之间的最小间隔
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+fn progress_channel(trans_task_id: i64) -> String {
+    format!("transtask::progress::{}", trans_task_id)
+}
+
+// 进度中心：进程内按任务广播进度事件，开启Redis时同时发布到Redis频道，
+// 订阅方改从Redis接收，以便订阅到其他实例上的执行
+#[derive(Clone)]
+pub struct ProgressHub {
+    channels: Arc<Mutex<HashMap<i64, broadcast::Sender<TransProgressVo>>>>,
+    // 订阅独占连接，直接由客户端建立
+    redis_client: Option<redis::Client>,
+    // 待发布到Redis的 (频道, 内容)，由后台线程发布
+    redis_publisher: Option<std::sync::mpsc::Sender<(String, String)>>,
+}
+
+impl ProgressHub {
+    pub fn new(
+        redis_client: Option<redis::Client>,
+        redis_pool: Pool<redis::Client>,
+    ) -> Self {
+        let redis_publisher =
+            redis_client.as_ref().map(|_| spawn_publisher(redis_pool));
+        Self {
+            channels: Arc::new(Mutex::new(HashMap::new())),
+            redis_client,
+            redis_publisher,
+        }
+    }
+
+    fn sender(&self, trans_task_id: i64) -> broadcast::Sender<TransProgressVo> {
+        self.channels
+            .lock()
+            .unwrap()
+            .entry(trans_task_id)
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .clone()
+    }
+
+    // 不做阻塞IO，可在异步任务中直接调用
+    pub fn publish(&self, progress: TransProgressVo) {
+        if let Some(publisher) = &self.redis_publisher {
+            match serde_json::to_string(&progress) {
+                Ok(payload) => {
+                    let _ = publisher.send((
+                        progress_channel(progress.trans_task_id),
+                        payload,
+                    ));
+                }
+                Err(e) => tracing::warn!("序列化传输进度失败: {:?}", e),
+            }
+        }
+        // 没有订阅方时发送失败，忽略即可
+        let _ = self.sender(progress.trans_task_id).send(progress);
+    }
+
+    // 订阅任务的进度，收到最终事件或订阅方断开后结束
+    pub fn subscribe(
+        &self,
+        trans_task_id: i64,
+    ) -> mpsc::Receiver<TransProgressVo> {
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        match &self.redis_client {
+            Some(client) => {
+                let client = client.clone();
+                tokio::task::spawn_blocking(move || {
+                    if let Err(e) = forward_redis(&client, trans_task_id, &tx) {
+                        tracing::warn!("订阅传输进度失败: {:?}", e);
+                    }
+                });
+            }
+            None => {
+                let mut receiver = self.sender(trans_task_id).subscribe();
+                tokio::spawn(async move {
+                    loop {
+                        let progress = tokio::select! {
+                            _ = tx.closed() => break,
+                            progress = receiver.recv() => progress,
+                        };
+                        match progress {
+                            Ok(progress) => {
+                                let finished = progress.is_finished();
+                                if tx.send(progress).await.is_err() || finished
+                                {
+                                    break;
+                                }
+                            }
+                            Err(RecvError::Lagged(_)) => continue,
+                            Err(RecvError::Closed) => break,
+                        }
+                    }
+                });
+            }
+        }
+        rx
+    }
+}
+
+// 启动发布线程，从连接池取连接依次发布，全部发送端丢弃后退出
+fn spawn_publisher(
+    redis_pool: Pool<redis::Client>,
+) -> std::sync::mpsc::Sender<(String, String)> {
+    let (tx, rx) = std::sync::mpsc::channel::<(String, String)>();
+    thread::spawn(move || {
+        for (channel, payload) in rx {
+            let published = redis_pool.get().map_err(AppError::from).and_then(
+                |mut conn| {
+                    conn.publish::<_, _, ()>(channel, payload)
+                        .map_err(AppError::from)
+                },
+            );
+            if let Err(e) = published {
+                tracing::warn!("发布传输进度失败: {:?}", e);
+            }
+        }
+    });
+    tx
+}
+
+// 在阻塞线程中订阅Redis频道并转发，定时醒来检查订阅方是否已断开
+fn forward_redis(
+    client: &redis::Client,
+    trans_task_id: i64,
+    tx: &mpsc::Sender<TransProgressVo>,
+) -> Result<(), AppError> {
+    let mut conn = client.get_connection()?;
+    let mut pubsub = conn.as_pubsub();
+    pubsub.subscribe(progress_channel(trans_task_id))?;
+    pubsub.set_read_timeout(Some(REPORT_INTERVAL))?;
+    while !tx.is_closed() {
+        let message = match pubsub.get_message() {
+            Ok(message) => message,
+            Err(e) if e.is_timeout() => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let payload: String = message.get_payload()?;
+        let Ok(progress) = serde_json::from_str::<TransProgressVo>(&payload)
+        else {
+            continue;
+        };
+        let finished = progress.is_finished();
+        if tx.blocking_send(progress).is_err() || finished {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// 单次执行的进度上报器，由传输引擎在阻塞线程中调用
+pub struct ProgressReporter {
+    hub: ProgressHub,
+    trans_task_id: i64,
+    trans_task_run_id: i64,
+    started: Instant,
+    last_report: Instant,
+    total_rows: Option<u64>,
+    batches: u64,
+}
+
+impl ProgressReporter {
+    pub fn new(
+        hub: ProgressHub,
+        trans_task_id: i64,
+        trans_task_run_id: i64,
+    ) -> Self {
+        Self {
+            hub,
+            trans_task_id,
+            trans_task_run_id,
+            started: Instant::now(),
+            last_report: Instant::now(),
+            total_rows: None,
+            batches: 0,
+        }
+    }
+
+    pub fn set_total_rows(&mut self, total_rows: Option<u64>) {
+        self.total_rows = total_rows;
+    }
+
+    pub fn batches(&self) -> u64 {
+        self.batches
+    }
+
+    // 每写入一批后上报
+    pub fn batch_written(&mut self, outcome: &TransOutcome) {
+        self.batches += 1;
+        self.report(outcome);
+    }
+
+    // 读取过程中调用，距上次上报超过间隔时才上报
+    pub fn rows_processed(&mut self, outcome: &TransOutcome) {
+        if self.last_report.elapsed() >= REPORT_INTERVAL {
+            self.report(outcome);
+        }
+    }
+
+    fn report(&mut self, outcome: &TransOutcome) {
+        self.last_report = Instant::now();
+        let elapsed_ms = self.started.elapsed().as_millis() as u64;
+        let speed = rows_per_sec(outcome.rows_written, elapsed_ms);
+        let eta_secs = self.total_rows.filter(|_| speed > 0.0).map(|total| {
+            (total.saturating_sub(outcome.rows_read) as f64 / speed).ceil()
+                as u64
+        });
+        self.hub.publish(TransProgressVo {
+            trans_task_id: self.trans_task_id,
+            trans_task_run_id: self.trans_task_run_id,
+            status: String::from(RUN_STATUS_RUNNING),
+            rows_read: outcome.rows_read,
+            rows_written: outcome.rows_written,
+            batches: self.batches,
+            elapsed_ms,
+            rows_per_sec: speed,
+            total_rows: self.total_rows,
+            eta_secs,
+            error_message: None,
+        });
+    }
+}
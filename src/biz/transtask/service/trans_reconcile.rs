@@ -0,0 +1,357 @@
+use std::time::Instant;
+
+use chrono::Local;
+use duckdb::{
+    Connection, DuckdbConnectionManager, appender_params_from_iter,
+    params_from_iter, types::Value as DuckValue,
+};
+use r2d2::Pool;
+use r2d2_mysql::mysql::prelude::Queryable;
+
+use crate::{
+    biz::{
+        datasource::{
+            model::data_source::DataSourceDetailVo,
+            service::data_source_service::DataSourceService,
+        },
+        transtask::{
+            model::{
+                trans_reconcile::{
+                    ReconcileRangeVo, TransTaskReconcileBo,
+                    TransTaskReconcileVo,
+                },
+                trans_task::TransTask,
+                trans_task_column::TransTaskColumn,
+            },
+            service::{
+                source_query::SourceQuery,
+                trans_chunk::{KeyRange, bound_string, integer_key_ranges},
+                trans_engine::{
+                    SourceColumn, apply_mappings, create_table_sql,
+                    duck_columns, duck_ident, load_columns, load_primary_key,
+                    mysql_columns, mysql_ident,
+                },
+            },
+        },
+    },
+    error::error::AppError,
+    util::redis_lock::LockHolder,
+};
+
+// 一个区间的行数与校验和
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ChunkDigest {
+    rows: u64,
+    checksum: u64,
+}
+
+// 源端与目标表对账：按主键把源表划分为区间，逐个区间把源端数据按目标类型读入
+// DuckDB的临时表，再在DuckDB中分别计算临时表与目标表对应区间的行数和校验和
+// （各行全部列哈希值的异或），保证两侧的取值经过相同的类型转换后再比较。
+// 整数单列主键按 MIN/MAX 等分区间，其余主键整表作为一个区间。
+// 修复时在事务中删除目标表该区间的行并写入临时表中的源端数据
+#[derive(Clone)]
+pub struct TransReconciler {
+    duck_pool: Pool<DuckdbConnectionManager>,
+}
+
+impl TransReconciler {
+    pub fn new(duck_pool: Pool<DuckdbConnectionManager>) -> Self {
+        Self { duck_pool }
+    }
+
+    // 该方法会阻塞当前线程，需在 spawn_blocking 中调用
+    pub fn reconcile(
+        &self,
+        data_source: &DataSourceDetailVo,
+        task: &TransTask,
+        mappings: &[TransTaskColumn],
+        bo: &TransTaskReconcileBo,
+        holder: &LockHolder,
+    ) -> Result<TransTaskReconcileVo, AppError> {
+        let started = Instant::now();
+        let query = SourceQuery::new(
+            &task.table_name,
+            task.source_filter.as_deref(),
+            task.source_sql.as_deref(),
+            task.watermark_value.as_deref(),
+            Local::now().date_naive(),
+        )?;
+        if query.is_custom() {
+            return Err(AppError::TransError(String::from(
+                "自定义SELECT的任务无法按主键对账",
+            )));
+        }
+        // 引用 :last_watermark 等命名参数的过滤条件只选出本次增量，
+        // 目标表中的历史数据会被判为不一致，修复时被误删
+        if !query.params().is_empty() {
+            return Err(AppError::TransError(String::from(
+                "过滤条件引用了命名参数的任务无法对账",
+            )));
+        }
+
+        let source_pool = DataSourceService::create_pool(data_source, 1)?;
+        let mut source = source_pool.get()?;
+        let columns = apply_mappings(
+            load_columns(&mut *source, &data_source.db_name, &task.table_name)?,
+            mappings,
+        )?;
+        let key_names = load_primary_key(
+            &mut *source,
+            &data_source.db_name,
+            &task.table_name,
+        )?;
+        let primary_key: Vec<SourceColumn> = key_names
+            .iter()
+            .filter_map(|name| {
+                columns.iter().find(|column| &column.name == name).cloned()
+            })
+            .collect();
+        if key_names.is_empty() || primary_key.len() != key_names.len() {
+            return Err(AppError::TransError(format!(
+                "对账要求源表存在主键且列映射包含全部主键列: {}.{}",
+                data_source.db_name, task.table_name
+            )));
+        }
+
+        let schema = &data_source.code;
+        let duck = self.duck_pool.get()?;
+        let target_exists: bool = duck.query_row(
+            "SELECT count(*) > 0 FROM information_schema.tables \
+             WHERE table_schema = ? AND table_name = ?",
+            [schema, &task.table_name],
+            |row| row.get(0),
+        )?;
+        if !target_exists {
+            return Err(AppError::TransError(format!(
+                "目标表不存在: {}.{}",
+                schema, task.table_name
+            )));
+        }
+
+        let source_rows: Option<u64> = source.exec_first(
+            query.select_sql("COUNT(*)", &[], None),
+            query.params(),
+        )?;
+        let chunks = source_rows.unwrap_or_default().div_ceil(bo.chunk_rows());
+        let key = match primary_key.as_slice() {
+            [key] if key.is_integer() => Some(key),
+            _ => None,
+        };
+        let ranges = match key {
+            Some(key) if chunks > 1 => integer_key_ranges(
+                &mut *source,
+                &task.table_name,
+                key,
+                chunks as usize,
+            )?,
+            _ => vec![KeyRange {
+                lower: None,
+                upper: None,
+            }],
+        };
+
+        let reconcile_table = format!("{}__reconcile", task.table_name);
+        let chunk = ReconcileChunk {
+            duck: &duck,
+            schema,
+            table_name: &task.table_name,
+            reconcile_table: &reconcile_table,
+            columns: &columns,
+            key,
+        };
+        let mut report = TransTaskReconcileVo {
+            trans_task_id: task.trans_task_id.unwrap_or_default(),
+            source_rows: 0,
+            target_rows: 0,
+            chunks: ranges.len(),
+            matched: true,
+            mismatched_ranges: Vec::new(),
+            repaired_rows: 0,
+            elapsed_ms: 0,
+        };
+        let result = (|| {
+            for range in &ranges {
+                chunk.copy_source(&mut *source, &query, range)?;
+                let source_digest = chunk.digest(&reconcile_table, None)?;
+                let target_digest =
+                    chunk.digest(&task.table_name, Some(range))?;
+                report.source_rows += source_digest.rows;
+                report.target_rows += target_digest.rows;
+                if source_digest == target_digest {
+                    continue;
+                }
+
+                report.matched = false;
+                let repaired = bo.repair;
+                if repaired {
+                    // 租约丢失说明其他实例可能已开始传输，不再修改目标表
+                    if !holder.is_held() {
+                        return Err(AppError::TransError(format!(
+                            "分布式锁租约已丢失, 停止修复: {}",
+                            task.table_name
+                        )));
+                    }
+                    chunk.repair(range)?;
+                    report.repaired_rows += source_digest.rows;
+                }
+                report.mismatched_ranges.push(ReconcileRangeVo {
+                    lower: range.lower.as_ref().map(bound_string),
+                    upper: range.upper.as_ref().map(bound_string),
+                    source_rows: source_digest.rows,
+                    target_rows: target_digest.rows,
+                    source_checksum: format!("{:016x}", source_digest.checksum),
+                    target_checksum: format!("{:016x}", target_digest.checksum),
+                    repaired,
+                });
+            }
+            Ok(())
+        })();
+        duck.execute_batch(&format!(
+            "DROP TABLE IF EXISTS {}.{}",
+            duck_ident(schema),
+            duck_ident(&reconcile_table)
+        ))?;
+        result?;
+
+        report.elapsed_ms = started.elapsed().as_millis() as u64;
+        tracing::info!(
+            "传输任务对账完成: {}.{}, {} 个区间, 不一致 {} 个, 修复 {} 行",
+            schema,
+            task.table_name,
+            report.chunks,
+            report.mismatched_ranges.len(),
+            report.repaired_rows
+        );
+        Ok(report)
+    }
+}
+
+// 对账过程中单个区间的操作
+struct ReconcileChunk<'a> {
+    duck: &'a Connection,
+    schema: &'a str,
+    table_name: &'a str,
+    reconcile_table: &'a str,
+    columns: &'a [SourceColumn],
+    // 按区间对账时的整数主键，None 表示整表为一个区间
+    key: Option<&'a SourceColumn>,
+}
+
+impl ReconcileChunk<'_> {
+    // 把源端该区间的数据按目标类型写入临时表
+    fn copy_source(
+        &self,
+        source: &mut impl Queryable,
+        query: &SourceQuery,
+        range: &KeyRange,
+    ) -> Result<(), AppError> {
+        self.duck.execute_batch(&create_table_sql(
+            self.schema,
+            self.reconcile_table,
+            self.columns,
+            true,
+        ))?;
+        let mut params = query.params();
+        let conditions = match self.key {
+            Some(key) => range.conditions(&mysql_ident(&key.name), &mut params),
+            None => Vec::new(),
+        };
+        let select_sql =
+            query.select_sql(&mysql_columns(self.columns), &conditions, None);
+        let mut appender = self
+            .duck
+            .appender_to_db(self.reconcile_table, self.schema)?;
+        for row in source.exec_iter(select_sql, params)? {
+            appender.append_row(appender_params_from_iter(
+                row?.unwrap()
+                    .into_iter()
+                    .zip(self.columns)
+                    .map(|(value, column)| column.to_duck_value(value)),
+            ))?;
+        }
+        appender.flush()?;
+        Ok(())
+    }
+
+    // 目标表中该区间的过滤条件
+    fn target_conditions(
+        &self,
+        range: &KeyRange,
+        params: &mut Vec<DuckValue>,
+    ) -> Vec<String> {
+        let Some(key) = self.key else {
+            return Vec::new();
+        };
+        let column = duck_ident(&key.target_name);
+        let mut conditions = Vec::new();
+        if let Some(lower) = &range.lower {
+            conditions.push(format!("{} >= ?", column));
+            params.push(key.to_duck_value(lower.clone()));
+        }
+        if let Some(upper) = &range.upper {
+            conditions.push(format!("{} < ?", column));
+            params.push(key.to_duck_value(upper.clone()));
+        }
+        conditions
+    }
+
+    // range 为None时统计整张表
+    fn digest(
+        &self,
+        table_name: &str,
+        range: Option<&KeyRange>,
+    ) -> Result<ChunkDigest, AppError> {
+        let mut params = Vec::new();
+        let conditions = range
+            .map(|range| self.target_conditions(range, &mut params))
+            .unwrap_or_default();
+        let mut sql = format!(
+            "SELECT count(*), coalesce(bit_xor(hash({})), 0) FROM {}.{}",
+            duck_columns(self.columns),
+            duck_ident(self.schema),
+            duck_ident(table_name)
+        );
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        let (rows, checksum): (i64, u64) =
+            self.duck.query_row(&sql, params_from_iter(params), |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+        Ok(ChunkDigest {
+            rows: rows as u64,
+            checksum,
+        })
+    }
+
+    // 用临时表中的源端数据替换目标表该区间的行
+    fn repair(&self, range: &KeyRange) -> Result<(), AppError> {
+        let mut params = Vec::new();
+        let conditions = self.target_conditions(range, &mut params);
+        let mut delete_sql = format!(
+            "DELETE FROM {}.{}",
+            duck_ident(self.schema),
+            duck_ident(self.table_name)
+        );
+        if !conditions.is_empty() {
+            delete_sql.push_str(" WHERE ");
+            delete_sql.push_str(&conditions.join(" AND "));
+        }
+
+        let tx = self.duck.unchecked_transaction()?;
+        tx.execute(&delete_sql, params_from_iter(params))?;
+        tx.execute_batch(&format!(
+            "INSERT INTO {}.{} ({}) SELECT {} FROM {}.{}",
+            duck_ident(self.schema),
+            duck_ident(self.table_name),
+            duck_columns(self.columns),
+            duck_columns(self.columns),
+            duck_ident(self.schema),
+            duck_ident(self.reconcile_table)
+        ))?;
+        tx.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashSet;
+
+use crate::{
+    app::Infrastructure,
+    biz::{
+        datasource::service::data_source_service::DataSourceService,
+        transtask::{
+            model::trans_task_column::{
+                TransTaskColumn, TransTaskColumnBo, TransTaskColumnVo,
+            },
+            repository::{
+                trans_task_column_repo::TransTaskColumnRepository,
+                trans_task_repo::TransTaskRepository,
+            },
+            service::{
+                trans_engine::load_columns,
+                type_mapping::{TYPE_MAPPINGS, TypeMapping},
+            },
+        },
+    },
+    error::error::AppError,
+    sys::user::model::user::User,
+};
+
+#[derive(Clone)]
+pub struct TransTaskColumnService {
+    trans_task_column_repo: TransTaskColumnRepository,
+    trans_task_repo: TransTaskRepository,
+    data_source_service: DataSourceService,
+}
+
+impl TransTaskColumnService {
+    pub fn new(infra: &Infrastructure) -> Self {
+        Self {
+            trans_task_column_repo: TransTaskColumnRepository::new(
+                infra.batis.clone(),
+            ),
+            trans_task_repo: TransTaskRepository::new(infra.batis.clone()),
+            data_source_service: DataSourceService::new(infra),
+        }
+    }
+
+    pub async fn select_by_trans_task_id(
+        &self,
+        trans_task_id: i64,
+    ) -> Result<Vec<TransTaskColumn>, AppError> {
+        Ok(self
+            .trans_task_column_repo
+            .select_by_trans_task_id(&trans_task_id)
+            .await?)
+    }
+
+    pub async fn list_by_trans_task_id(
+        &self,
+        trans_task_id: i64,
+    ) -> Result<Vec<TransTaskColumnVo>, AppError> {
+        Ok(self
+            .select_by_trans_task_id(trans_task_id)
+            .await?
+            .iter()
+            .map(|entity| entity.to_vo())
+            .collect())
+    }
+
+    // 整体保存任务的列映射，列顺序即目标表中的列顺序
+    pub async fn save(
+        &self,
+        trans_task_id: i64,
+        bos: Vec<TransTaskColumnBo>,
+        current_user: &User,
+    ) -> Result<Vec<TransTaskColumnVo>, AppError> {
+        self.trans_task_repo
+            .select_by_id(&trans_task_id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        let mut source_columns = HashSet::new();
+        let mut target_columns = HashSet::new();
+        for bo in &bos {
+            if !source_columns.insert(bo.source_column.as_str()) {
+                return Err(AppError::BusinessError("列映射的源列重复"));
+            }
+            if bo.included
+                && !target_columns.insert(bo.target_column.to_lowercase())
+            {
+                return Err(AppError::BusinessError("列映射的目标列重复"));
+            }
+        }
+        if target_columns.is_empty() {
+            return Err(AppError::BusinessError("列映射至少需要包含一列"));
+        }
+
+        let columns = bos
+            .into_iter()
+            .enumerate()
+            .map(|(index, bo)| {
+                TransTaskColumn::from_bo(
+                    trans_task_id,
+                    index as i32 + 1,
+                    bo,
+                    current_user,
+                )
+            })
+            .collect::<Vec<_>>();
+        self.trans_task_column_repo
+            .replace_by_trans_task_id(&trans_task_id, &columns)
+            .await?;
+        self.list_by_trans_task_id(trans_task_id).await
+    }
+
+    // 按源表 information_schema.columns 与默认类型映射生成列映射，覆盖已有映射
+    pub async fn generate(
+        &self,
+        trans_task_id: i64,
+        current_user: &User,
+    ) -> Result<Vec<TransTaskColumnVo>, AppError> {
+        let task = self
+            .trans_task_repo
+            .select_by_id(&trans_task_id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        let data_source = self
+            .data_source_service
+            .get_data_source_by_id(task.data_source_id)
+            .await
+            .ok_or(AppError::BusinessError("数据源不存在"))?;
+
+        let source_columns = tokio::task::spawn_blocking(move || {
+            let pool = DataSourceService::create_pool(&data_source, 1)?;
+            let mut conn = pool.get()?;
+            load_columns(&mut *conn, &data_source.db_name, &task.table_name)
+        })
+        .await
+        .map_err(|e| AppError::TransError(e.to_string()))??;
+        if source_columns.is_empty() {
+            return Err(AppError::BusinessError("源表不存在或没有列"));
+        }
+
+        let bos = source_columns
+            .into_iter()
+            .map(|column| TransTaskColumnBo {
+                source_column: column.name,
+                target_column: column.target_name,
+                target_type: column.target_type,
+                included: true,
+                default_value: None,
+            })
+            .collect();
+        self.save(trans_task_id, bos, current_user).await
+    }
+
+    // 默认的MySQL到DuckDB类型映射表
+    pub fn type_mappings(&self) -> &'static [TypeMapping] {
+        TYPE_MAPPINGS
+    }
+}
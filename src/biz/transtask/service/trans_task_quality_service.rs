@@ -0,0 +1,151 @@
+use rbatis::plugin::page::Page;
+
+use crate::{
+    app::Infrastructure,
+    biz::transtask::{
+        model::{
+            trans_task_quality_result::{
+                TransTaskQualityResult, TransTaskQualityResultVo,
+            },
+            trans_task_quality_rule::{
+                TransTaskQualityRule, TransTaskQualityRuleBo,
+                TransTaskQualityRuleVo,
+            },
+        },
+        repository::{
+            trans_task_quality_result_repo::TransTaskQualityResultRepository,
+            trans_task_quality_rule_repo::TransTaskQualityRuleRepository,
+            trans_task_repo::TransTaskRepository,
+        },
+    },
+    common::model::page::PageBo,
+    error::error::AppError,
+    sys::user::model::user::User,
+};
+
+#[derive(Clone)]
+pub struct TransTaskQualityService {
+    trans_task_quality_rule_repo: TransTaskQualityRuleRepository,
+    trans_task_quality_result_repo: TransTaskQualityResultRepository,
+    trans_task_repo: TransTaskRepository,
+}
+
+impl TransTaskQualityService {
+    pub fn new(infra: &Infrastructure) -> Self {
+        Self {
+            trans_task_quality_rule_repo: TransTaskQualityRuleRepository::new(
+                infra.batis.clone(),
+            ),
+            trans_task_quality_result_repo:
+                TransTaskQualityResultRepository::new(infra.batis.clone()),
+            trans_task_repo: TransTaskRepository::new(infra.batis.clone()),
+        }
+    }
+
+    pub async fn select_rules_by_trans_task_id(
+        &self,
+        trans_task_id: i64,
+    ) -> Result<Vec<TransTaskQualityRule>, AppError> {
+        Ok(self
+            .trans_task_quality_rule_repo
+            .select_by_trans_task_id(&trans_task_id)
+            .await?)
+    }
+
+    pub async fn list_rules_by_trans_task_id(
+        &self,
+        trans_task_id: i64,
+    ) -> Result<Vec<TransTaskQualityRuleVo>, AppError> {
+        Ok(self
+            .select_rules_by_trans_task_id(trans_task_id)
+            .await?
+            .iter()
+            .map(|entity| entity.to_vo())
+            .collect())
+    }
+
+    // 整体保存任务的质量规则
+    pub async fn save_rules(
+        &self,
+        trans_task_id: i64,
+        bos: Vec<TransTaskQualityRuleBo>,
+        current_user: &User,
+    ) -> Result<Vec<TransTaskQualityRuleVo>, AppError> {
+        self.trans_task_repo
+            .select_by_id(&trans_task_id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        let rules = bos
+            .into_iter()
+            .map(|bo| {
+                TransTaskQualityRule::from_bo(trans_task_id, bo, current_user)
+            })
+            .collect::<Vec<_>>();
+        self.trans_task_quality_rule_repo
+            .replace_by_trans_task_id(&trans_task_id, &rules)
+            .await?;
+        self.list_rules_by_trans_task_id(trans_task_id).await
+    }
+
+    // 保存一次执行的检查结果
+    pub async fn save_results(
+        &self,
+        trans_task_run_id: i64,
+        results: &[TransTaskQualityResult],
+    ) -> Result<(), AppError> {
+        let results = results
+            .iter()
+            .cloned()
+            .map(|mut result| {
+                result.trans_task_run_id = trans_task_run_id;
+                result
+            })
+            .collect::<Vec<_>>();
+        self.trans_task_quality_result_repo
+            .insert_batch(&results)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn page_results_by_trans_task_id(
+        &self,
+        trans_task_id: i64,
+        page: &PageBo,
+    ) -> Page<TransTaskQualityResultVo> {
+        let page_request = page.to_page_request();
+        match self
+            .trans_task_quality_result_repo
+            .select_page_by_trans_task_id(&page_request, &trans_task_id)
+            .await
+        {
+            Ok(result) => Page::new(
+                result.page_no,
+                result.page_size,
+                result.total,
+                result.records.iter().map(|entity| entity.to_vo()).collect(),
+            ),
+            Err(e) => {
+                tracing::error!("分页查询质量检查结果失败: {:?}", e);
+                Page::new(
+                    page_request.page_no,
+                    page_request.page_size,
+                    0,
+                    Vec::new(),
+                )
+            }
+        }
+    }
+
+    pub async fn list_results_by_trans_task_run_id(
+        &self,
+        trans_task_run_id: i64,
+    ) -> Result<Vec<TransTaskQualityResultVo>, AppError> {
+        Ok(self
+            .trans_task_quality_result_repo
+            .select_by_trans_task_run_id(&trans_task_run_id)
+            .await?
+            .iter()
+            .map(|entity| entity.to_vo())
+            .collect())
+    }
+}
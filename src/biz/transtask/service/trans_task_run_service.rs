@@ -0,0 +1,162 @@
+use rbatis::plugin::page::Page;
+use rbdc::DateTime;
+
+use crate::{
+    app::Infrastructure,
+    biz::transtask::{
+        model::{
+            trans_backfill::BackfillWindow,
+            trans_task_run::{
+                RUN_STATUS_CANCELLED, RUN_STATUS_FAILED, RUN_STATUS_PAUSED,
+                RUN_STATUS_SUCCESS, TransTaskRun, TransTaskRunListVo,
+            },
+        },
+        repository::trans_task_run_repo::TransTaskRunRepository,
+        service::trans_engine::TransOutcome,
+    },
+    common::model::page::PageBo,
+    error::error::AppError,
+};
+
+#[derive(Clone)]
+pub struct TransTaskRunService {
+    trans_task_run_repo: TransTaskRunRepository,
+}
+
+impl TransTaskRunService {
+    pub fn new(infra: &Infrastructure) -> Self {
+        Self {
+            trans_task_run_repo: TransTaskRunRepository::new(
+                infra.batis.clone(),
+            ),
+        }
+    }
+
+    pub async fn page_by_trans_task_id(
+        &self,
+        trans_task_id: i64,
+        page: &PageBo,
+    ) -> Page<TransTaskRunListVo> {
+        let page_request = page.to_page_request();
+        match self
+            .trans_task_run_repo
+            .select_page_by_trans_task_id(&page_request, &trans_task_id)
+            .await
+        {
+            Ok(result) => Page::new(
+                result.page_no,
+                result.page_size,
+                result.total,
+                result
+                    .records
+                    .iter()
+                    .map(|entity| entity.to_list_vo())
+                    .collect(),
+            ),
+            Err(e) => {
+                tracing::error!("分页查询执行记录失败: {:?}", e);
+                Page::new(
+                    page_request.page_no,
+                    page_request.page_size,
+                    0,
+                    Vec::new(),
+                )
+            }
+        }
+    }
+
+    // 登记一次未实际执行的触发（跳过或错过）
+    pub async fn record_not_run(
+        &self,
+        trans_task_id: i64,
+        status: &str,
+        fire_time: DateTime,
+        message: &str,
+        operator_id: i64,
+    ) -> Result<(), AppError> {
+        let run = TransTaskRun::not_run(
+            trans_task_id,
+            status,
+            fire_time,
+            message,
+            operator_id,
+        );
+        self.trans_task_run_repo.insert(&run).await?;
+        Ok(())
+    }
+
+    // 登记一次开始执行的记录
+    pub async fn start(
+        &self,
+        trans_task_id: i64,
+        operator_id: i64,
+        fence_token: u64,
+    ) -> Result<TransTaskRun, AppError> {
+        let mut run = TransTaskRun::start(trans_task_id, operator_id);
+        run.fence_token = fence_token as i64;
+        let result = self.trans_task_run_repo.insert(&run).await?;
+        run.trans_task_run_id = result.last_insert_id.as_i64();
+        Ok(run)
+    }
+
+    // 登记一次开始执行的回填或回填分片
+    pub async fn start_backfill(
+        &self,
+        trans_task_id: i64,
+        operator_id: i64,
+        fence_token: u64,
+        parent_run_id: Option<i64>,
+        window: &BackfillWindow,
+    ) -> Result<TransTaskRun, AppError> {
+        let mut run = TransTaskRun::backfill(
+            trans_task_id,
+            parent_run_id,
+            window,
+            operator_id,
+        );
+        run.fence_token = fence_token as i64;
+        let result = self.trans_task_run_repo.insert(&run).await?;
+        run.trans_task_run_id = result.last_insert_id.as_i64();
+        Ok(run)
+    }
+
+    // 根据执行结果更新执行记录
+    pub async fn finish(
+        &self,
+        run: &mut TransTaskRun,
+        result: &Result<TransOutcome, AppError>,
+    ) -> Result<(), AppError> {
+        match result {
+            Ok(outcome) => {
+                run.rows_read = outcome.rows_read as i64;
+                run.rows_written = outcome.rows_written as i64;
+                run.rows_deleted = outcome.rows_deleted as i64;
+                run.bytes = outcome.bytes as i64;
+                let export_error = outcome
+                    .export_error
+                    .as_ref()
+                    .map(|e| format!("导出Parquet失败: {}", e));
+                if let Some(e) = outcome.quality_error() {
+                    run.finish(RUN_STATUS_FAILED, Some(e.to_string()));
+                } else if outcome.paused {
+                    run.finish(RUN_STATUS_PAUSED, export_error);
+                } else {
+                    run.finish(RUN_STATUS_SUCCESS, export_error);
+                }
+            }
+            Err(AppError::TransPaused) => run.finish(RUN_STATUS_PAUSED, None),
+            Err(AppError::TransCancelled) => {
+                run.finish(RUN_STATUS_CANCELLED, None)
+            }
+            Err(e) => run.finish(RUN_STATUS_FAILED, Some(e.to_string())),
+        }
+
+        let trans_task_run_id = run
+            .trans_task_run_id
+            .ok_or(AppError::BusinessError("执行记录ID为空"))?;
+        self.trans_task_run_repo
+            .update_by_id(run, &trans_task_run_id)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use std::{collections::VecDeque, time::Duration};
+
+use chrono::{TimeZone, Utc};
+use r2d2::Pool;
+use rbdc::DateTime;
+use tokio::time::MissedTickBehavior;
+
+use crate::{
+    app::Infrastructure,
+    biz::transtask::{
+        model::{
+            trans_task::{RUN_STATE_PAUSED, TransTask},
+            trans_task_run::{RUN_STATUS_MISSED, RUN_STATUS_SKIPPED},
+        },
+        repository::trans_task_repo::TransTaskRepository,
+        service::{
+            trans_task_run_service::TransTaskRunService,
+            trans_task_service::TransTaskService,
+        },
+    },
+    config::config::{CatchUpPolicy, SchedulerConfig},
+    error::error::AppError,
+    util::redis_lock::RedisLock,
+};
+
+// 调度触发的执行记录使用的操作人ID
+const SCHEDULER_OPERATOR_ID: i64 = 0;
+
+// 触发时间与当前时间的差距在该时长内视为准时触发
+const MIN_GRACE_SECS: u64 = 60;
+
+// 调度锁的租期，多实例部署时同一任务每轮只由一个实例计算触发
+const SCHEDULE_LOCK_LEASE: Duration = Duration::from_secs(60);
+
+// 传输任务调度器：按cron表达式周期性触发传输任务
+#[derive(Clone)]
+pub struct TransTaskScheduler {
+    config: SchedulerConfig,
+    trans_task_repo: TransTaskRepository,
+    trans_task_service: TransTaskService,
+    trans_task_run_service: TransTaskRunService,
+    redis_pool: Pool<redis::Client>,
+}
+
+impl TransTaskScheduler {
+    pub fn new(
+        infra: &Infrastructure,
+        trans_task_service: TransTaskService,
+    ) -> Self {
+        Self {
+            config: infra.config.scheduler.clone(),
+            trans_task_repo: TransTaskRepository::new(infra.batis.clone()),
+            trans_task_service,
+            trans_task_run_service: TransTaskRunService::new(infra),
+            redis_pool: infra.pool.redis_pool.clone(),
+        }
+    }
+
+    // 在后台启动调度循环
+    pub fn start(self) {
+        if !self.config.enabled {
+            tracing::info!("传输任务调度器未启用");
+            return;
+        }
+        tracing::info!(
+            "传输任务调度器已启动, 轮询间隔 {} 秒",
+            self.config.poll_interval_secs
+        );
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(
+                self.config.poll_interval_secs.max(1),
+            ));
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                self.tick().await;
+            }
+        });
+    }
+
+    async fn tick(&self) {
+        let tasks = match self.trans_task_repo.select_scheduled().await {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                tracing::error!("查询调度任务失败: {:?}", e);
+                return;
+            }
+        };
+        for trans_task_id in tasks.iter().filter_map(|task| task.trans_task_id)
+        {
+            if let Err(e) = self.fire_locked(trans_task_id).await {
+                tracing::error!("调度传输任务失败: {}, {:?}", trans_task_id, e);
+            }
+        }
+    }
+
+    // 持有调度锁后重新读取任务再计算触发，其他实例正在处理时跳过
+    async fn fire_locked(&self, trans_task_id: i64) -> Result<(), AppError> {
+        let Some(lock) = RedisLock::new(
+            self.redis_pool.clone(),
+            format!("lock::scheduler::{}", trans_task_id),
+            SCHEDULE_LOCK_LEASE,
+        )
+        .try_acquire()?
+        else {
+            return Ok(());
+        };
+        let result =
+            match self.trans_task_repo.select_by_id(&trans_task_id).await {
+                Ok(Some(task)) => self.fire(&task).await,
+                Ok(None) => Ok(()),
+                Err(e) => Err(e.into()),
+            };
+        lock.release().await;
+        result
+    }
+
+    // 计算上次触发以来到期的触发时间：最近一次按时的触发入队执行，
+    // 其余视为停机期间错过，按补偿策略登记或补跑
+    async fn fire(&self, task: &TransTask) -> Result<(), AppError> {
+        let Some(trans_task_id) = task.trans_task_id else {
+            return Ok(());
+        };
+        let schedule = match task.cron_schedule() {
+            Ok(Some(schedule)) => schedule,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                tracing::warn!(
+                    "传输任务 {} 调度表达式无效: {}",
+                    trans_task_id,
+                    e
+                );
+                return Ok(());
+            }
+        };
+
+        let now = Utc::now();
+        // 首次调度时从当前时间开始计算，不补历史触发
+        let Some(last_fire_time) =
+            task.last_fire_time.as_ref().and_then(|time| {
+                Utc.timestamp_millis_opt(time.unix_timestamp_millis())
+                    .single()
+            })
+        else {
+            self.trans_task_repo
+                .update_last_fire_time(&trans_task_id, &db_time(now))
+                .await?;
+            return Ok(());
+        };
+
+        let mut fires = VecDeque::new();
+        let mut total = 0;
+        for fire_time in schedule
+            .fires_after(last_fire_time)
+            .take_while(|fire_time| *fire_time <= now)
+        {
+            total += 1;
+            fires.push_back(fire_time);
+            if fires.len() > self.config.max_missed_records + 1 {
+                fires.pop_front();
+            }
+        }
+        let Some(latest) = fires.pop_back() else {
+            return Ok(());
+        };
+
+        let grace = chrono::Duration::seconds(
+            (self.config.poll_interval_secs * 2).max(MIN_GRACE_SECS) as i64,
+        );
+        let on_time = now - latest <= grace;
+        let run_latest = on_time || self.config.catch_up == CatchUpPolicy::Once;
+        if !run_latest {
+            fires.push_back(latest);
+        }
+
+        // 先推进触发时间，避免下一轮重复触发
+        self.trans_task_repo
+            .update_last_fire_time(&trans_task_id, &db_time(latest))
+            .await?;
+
+        // 已暂停的任务等待手动恢复，期间的触发不执行也不登记
+        if task.run_state == RUN_STATE_PAUSED {
+            return Ok(());
+        }
+
+        let missed = total - usize::from(run_latest);
+        if missed > fires.len() {
+            tracing::warn!(
+                "传输任务 {} 共错过 {} 次触发, 仅登记最近 {} 次",
+                trans_task_id,
+                missed,
+                fires.len()
+            );
+        }
+        for fire_time in fires {
+            self.trans_task_run_service
+                .record_not_run(
+                    trans_task_id,
+                    RUN_STATUS_MISSED,
+                    db_time(fire_time),
+                    "调度器停机期间错过的触发",
+                    SCHEDULER_OPERATOR_ID,
+                )
+                .await?;
+        }
+        if !run_latest {
+            return Ok(());
+        }
+
+        if self.trans_task_service.is_running(trans_task_id) {
+            tracing::info!(
+                "传输任务 {} 上一次执行尚未结束, 跳过",
+                trans_task_id
+            );
+            return self
+                .trans_task_run_service
+                .record_not_run(
+                    trans_task_id,
+                    RUN_STATUS_SKIPPED,
+                    db_time(latest),
+                    "上一次执行尚未结束",
+                    SCHEDULER_OPERATOR_ID,
+                )
+                .await;
+        }
+
+        tracing::info!("调度触发传输任务 {}", trans_task_id);
+        let trans_task_service = self.trans_task_service.clone();
+        tokio::spawn(async move {
+            if let Err(e) = trans_task_service
+                .run_task(trans_task_id, SCHEDULER_OPERATOR_ID)
+                .await
+            {
+                tracing::error!(
+                    "调度执行传输任务失败: {}, {:?}",
+                    trans_task_id,
+                    e
+                );
+            }
+        });
+        Ok(())
+    }
+}
+
+// 转换为与 DateTime::now() 相同时区的数据库时间
+fn db_time(time: chrono::DateTime<Utc>) -> DateTime {
+    DateTime::from_timestamp_millis(time.timestamp_millis())
+        .set_offset(DateTime::now().offset())
+}
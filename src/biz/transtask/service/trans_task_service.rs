@@ -1,24 +1,165 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{Local, Utc};
+use r2d2::Pool;
+use r2d2_mysql::mysql::prelude::Queryable;
+use rbdc::DateTime;
+use redis::Commands;
+use tokio::sync::mpsc;
+
 use crate::{
     app::Infrastructure,
-    biz::transtask::{
-        model::trans_task::{
-            TransTask, TransTaskCreateBo, TransTaskDetailVo, TransTaskListVo,
-            TransTaskUpdateBo,
+    biz::{
+        datasource::{
+            model::data_source::DataSourceDetailVo,
+            service::data_source_service::DataSourceService,
+        },
+        transtask::{
+            model::{
+                trans_backfill::{BackfillWindow, TransTaskBackfillBo},
+                trans_plan::TransPlanVo,
+                trans_progress::TransProgressVo,
+                trans_reconcile::{TransTaskReconcileBo, TransTaskReconcileVo},
+                trans_task::{
+                    LOAD_STRATEGY_MERGE, RUN_STATE_CANCELLED, RUN_STATE_IDLE,
+                    RUN_STATE_PAUSED, RUN_STATE_RUNNING, SYNC_MODE_CDC,
+                    SYNC_MODE_INCREMENTAL, TransTask, TransTaskBulkCreateBo,
+                    TransTaskBulkCreateVo, TransTaskBulkTableVo,
+                    TransTaskCreateBo, TransTaskDetailVo, TransTaskListVo,
+                    TransTaskUpdateBo, validate_table_name,
+                },
+                trans_task_run::{TransTaskRun, TransTaskRunListVo},
+            },
+            repository::trans_task_repo::TransTaskRepository,
+            service::{
+                run_control::{RunControl, RunSignal},
+                source_query::SourceQuery,
+                trans_engine::{
+                    TaskSettings, TransEngine, TransOutcome, load_tables,
+                },
+                trans_progress::{ProgressHub, ProgressReporter},
+                trans_reconcile::TransReconciler,
+                trans_task_column_service::TransTaskColumnService,
+                trans_task_quality_service::TransTaskQualityService,
+                trans_task_run_service::TransTaskRunService,
+            },
         },
-        repository::trans_task_repo::TransTaskRepository,
     },
+    error::error::AppError,
     sys::user::model::user::User,
+    util::redis_lock::{LockGuard, RedisLock},
 };
 
+// 执行锁的租期，持有期间由心跳续租
+const RUN_LOCK_LEASE: Duration = Duration::from_secs(30);
+
+// 转发给其他实例的控制信号的有效期
+const CONTROL_SIGNAL_TTL: u64 = 60;
+
+// 执行中检查Redis控制信号的间隔
+const CONTROL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// 重试等待期间检查控制信号的间隔
+const RETRY_WAIT_STEP: Duration = Duration::from_millis(200);
+
 #[derive(Clone)]
 pub struct TransTaskService {
     trans_task_repo: TransTaskRepository,
+    data_source_service: DataSourceService,
+    trans_task_run_service: TransTaskRunService,
+    trans_task_column_service: TransTaskColumnService,
+    trans_task_quality_service: TransTaskQualityService,
+    trans_engine: TransEngine,
+    trans_reconciler: TransReconciler,
+    redis_pool: Pool<redis::Client>,
+    progress_hub: ProgressHub,
+    // 正在执行的任务及其控制句柄，同一任务不允许并发执行
+    running: Arc<Mutex<HashMap<i64, RunControl>>>,
+}
+
+// 登记正在执行的任务，离开作用域时自动移除
+struct RunningGuard {
+    running: Arc<Mutex<HashMap<i64, RunControl>>>,
+    trans_task_id: i64,
+    control: RunControl,
+}
+
+impl RunningGuard {
+    fn acquire(
+        running: &Arc<Mutex<HashMap<i64, RunControl>>>,
+        trans_task_id: i64,
+    ) -> Option<Self> {
+        let mut running_map = running.lock().unwrap();
+        if running_map.contains_key(&trans_task_id) {
+            return None;
+        }
+        let control = RunControl::default();
+        running_map.insert(trans_task_id, control.clone());
+        Some(Self {
+            running: running.clone(),
+            trans_task_id,
+            control,
+        })
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.control.finish();
+        self.running.lock().unwrap().remove(&self.trans_task_id);
+    }
+}
+
+// 后台执行回填所需的上下文，执行期间持有执行登记与执行锁
+struct Backfill {
+    task: TransTask,
+    data_source: DataSourceDetailVo,
+    settings: TaskSettings,
+    guard: RunningGuard,
+    lock: LockGuard,
+    operator_id: i64,
+}
+
+// 执行结束后任务所处的状态
+fn run_state_of(result: &Result<TransOutcome, AppError>) -> &'static str {
+    match result {
+        Ok(outcome) if outcome.paused => RUN_STATE_PAUSED,
+        Err(AppError::TransPaused) => RUN_STATE_PAUSED,
+        Err(AppError::TransCancelled) => RUN_STATE_CANCELLED,
+        _ => RUN_STATE_IDLE,
+    }
+}
+
+fn control_key(trans_task_id: i64) -> String {
+    format!("transtask::control::{}", trans_task_id)
 }
 
 impl TransTaskService {
     pub fn new(infra: &Infrastructure) -> Self {
         Self {
             trans_task_repo: TransTaskRepository::new(infra.batis.clone()),
+            data_source_service: DataSourceService::new(infra),
+            trans_task_run_service: TransTaskRunService::new(infra),
+            trans_task_column_service: TransTaskColumnService::new(infra),
+            trans_task_quality_service: TransTaskQualityService::new(infra),
+            trans_engine: TransEngine::new(infra),
+            trans_reconciler: TransReconciler::new(
+                infra.pool.duck_pool.clone(),
+            ),
+            redis_pool: infra.pool.redis_pool.clone(),
+            progress_hub: ProgressHub::new(
+                infra
+                    .config
+                    .progress
+                    .redis_pubsub
+                    .then(|| infra.pool.redis_client.clone()),
+                infra.pool.redis_pool.clone(),
+            ),
+            running: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -40,7 +181,14 @@ impl TransTaskService {
         id: i64,
     ) -> Option<TransTaskDetailVo> {
         match self.trans_task_repo.select_by_id(&id).await {
-            Ok(Some(entity)) => Some(entity.to_detail_vo()),
+            Ok(Some(mut entity)) => {
+                // 执行实例异常退出时状态停留在RUNNING，以执行锁为准
+                if entity.run_state == RUN_STATE_RUNNING && !self.is_running(id)
+                {
+                    entity.run_state = String::from(RUN_STATE_IDLE);
+                }
+                Some(entity.to_detail_vo())
+            }
             Ok(None) => None,
             Err(e) => {
                 tracing::error!("根据ID查询失败: {:?}", e);
@@ -49,6 +197,40 @@ impl TransTaskService {
         }
     }
 
+    // 保存前在数据源上校验抽取来源：数据源类型须支持传输，
+    // 自定义过滤条件或SELECT时解析命名参数并执行 EXPLAIN
+    pub async fn validate_source(
+        &self,
+        data_source_id: i64,
+        table_name: &str,
+        source_filter: Option<&str>,
+        source_sql: Option<&str>,
+    ) -> Result<(), AppError> {
+        let data_source = self
+            .data_source_service
+            .get_data_source_by_id(data_source_id)
+            .await
+            .ok_or(AppError::BusinessError("数据源不存在"))?;
+        DataSourceService::check_transferable(&data_source)?;
+        let query = SourceQuery::new(
+            table_name,
+            source_filter,
+            source_sql,
+            None,
+            Local::now().date_naive(),
+        )?;
+        if query.is_plain() {
+            return Ok(());
+        }
+        tokio::task::spawn_blocking(move || {
+            let pool = DataSourceService::create_pool(&data_source, 1)?;
+            let mut conn = pool.get()?;
+            query.explain(&mut *conn)
+        })
+        .await
+        .map_err(|e| AppError::TransError(e.to_string()))?
+    }
+
     pub async fn insert_by_bo(
         &self,
         bo: TransTaskCreateBo,
@@ -64,6 +246,118 @@ impl TransTaskService {
         }
     }
 
+    // 读取数据源的表，按通配符筛选后在同一事务中批量创建任务。
+    // 已有任务的表、增量模式缺少水位列的表、合并或CDC模式缺少主键的表跳过
+    pub async fn bulk_create(
+        &self,
+        bo: TransTaskBulkCreateBo,
+        current_user: &User,
+    ) -> Result<TransTaskBulkCreateVo, AppError> {
+        let data_source_id = bo.data_source_id.unwrap();
+        let data_source = self
+            .data_source_service
+            .get_data_source_by_id(data_source_id)
+            .await
+            .ok_or(AppError::BusinessError("数据源不存在"))?;
+        DataSourceService::check_transferable(&data_source)?;
+        let watermark_column = bo
+            .watermark_column
+            .clone()
+            .filter(|_| bo.sync_mode == SYNC_MODE_INCREMENTAL);
+        let query_column = watermark_column.clone();
+        let (tables, keyed_tables, watermark_tables) =
+            tokio::task::spawn_blocking(move || {
+                let pool = DataSourceService::create_pool(&data_source, 1)?;
+                let mut conn = pool.get()?;
+                let db_name = &data_source.db_name;
+                let tables = load_tables(&mut *conn, db_name)?;
+                let keyed_tables: HashSet<String> = conn
+                    .exec::<String, _, _>(
+                        "SELECT DISTINCT TABLE_NAME \
+                         FROM information_schema.KEY_COLUMN_USAGE \
+                         WHERE TABLE_SCHEMA = ? AND CONSTRAINT_NAME = 'PRIMARY'",
+                        (db_name,),
+                    )?
+                    .into_iter()
+                    .collect();
+                let watermark_tables: HashSet<String> = match &query_column {
+                    Some(column) => conn
+                        .exec::<String, _, _>(
+                            "SELECT TABLE_NAME FROM information_schema.COLUMNS \
+                             WHERE TABLE_SCHEMA = ? AND COLUMN_NAME = ?",
+                            (db_name, column),
+                        )?
+                        .into_iter()
+                        .collect(),
+                    None => HashSet::new(),
+                };
+                Ok::<_, AppError>((tables, keyed_tables, watermark_tables))
+            })
+            .await
+            .map_err(|e| AppError::TransError(e.to_string()))??;
+
+        let existing: HashSet<String> = self
+            .trans_task_repo
+            .select_all()
+            .await?
+            .into_iter()
+            .filter(|task| {
+                task.data_source_id == data_source_id
+                    && !task.base_entity.is_deleted()
+            })
+            .map(|task| task.table_name)
+            .collect();
+        let requires_key = bo.sync_mode == SYNC_MODE_CDC
+            || bo.load_strategy == LOAD_STRATEGY_MERGE;
+
+        let mut result = TransTaskBulkCreateVo {
+            created: Vec::new(),
+            skipped: Vec::new(),
+        };
+        let mut entities = Vec::new();
+        for table in tables.iter().filter(|table| bo.matches(&table.name)) {
+            let skip_reason = if validate_table_name(&table.name).is_err() {
+                Some(String::from("表名包含不支持的字符"))
+            } else if existing.contains(&table.name) {
+                Some(String::from("已存在该表的传输任务"))
+            } else if requires_key && !keyed_tables.contains(&table.name) {
+                Some(String::from("缺少主键"))
+            } else if let Some(column) = &watermark_column
+                && !watermark_tables.contains(&table.name)
+            {
+                Some(format!("缺少水位列 {}", column))
+            } else {
+                None
+            };
+            let create_bo = bo.to_create_bo(&table.name, &table.comment);
+            let table_vo = TransTaskBulkTableVo {
+                table_name: create_bo.table_name.clone(),
+                table_comment: create_bo.table_comment.clone(),
+                skip_reason,
+            };
+            if table_vo.skip_reason.is_some() {
+                result.skipped.push(table_vo);
+            } else {
+                entities
+                    .push(TransTask::from_create_bo(create_bo, current_user));
+                result.created.push(table_vo);
+            }
+        }
+        if result.created.is_empty() && result.skipped.is_empty() {
+            return Err(AppError::BusinessError("没有匹配的表"));
+        }
+        if !entities.is_empty() {
+            self.trans_task_repo.insert_batch(&entities).await?;
+        }
+        tracing::info!(
+            "数据源 {} 批量创建传输任务 {} 个, 跳过 {} 张表",
+            data_source_id,
+            result.created.len(),
+            result.skipped.len()
+        );
+        Ok(result)
+    }
+
     pub async fn update_by_bo(
         &self,
         bo: TransTaskUpdateBo,
@@ -133,4 +427,686 @@ impl TransTaskService {
             }
         }
     }
+
+    // 集群内同一任务的执行锁
+    fn run_lock(&self, id: i64) -> RedisLock {
+        RedisLock::new(
+            self.redis_pool.clone(),
+            format!("lock::transtask::{}", id),
+            RUN_LOCK_LEASE,
+        )
+    }
+
+    // 本实例或集群内其他实例是否正在执行该任务
+    pub fn is_running(&self, id: i64) -> bool {
+        if self.running.lock().unwrap().contains_key(&id) {
+            return true;
+        }
+        self.run_lock(id).is_locked().unwrap_or_else(|e| {
+            tracing::error!("查询执行锁失败: {:?}", e);
+            false
+        })
+    }
+
+    pub async fn run_by_id(
+        &self,
+        id: i64,
+        current_user: &User,
+    ) -> Result<TransTaskRunListVo, AppError> {
+        self.run_task(id, current_user.get_user_id()).await
+    }
+
+    // 执行一次传输，手动执行与调度触发共用
+    pub async fn run_task(
+        &self,
+        id: i64,
+        operator_id: i64,
+    ) -> Result<TransTaskRunListVo, AppError> {
+        let guard = RunningGuard::acquire(&self.running, id)
+            .ok_or(AppError::BusinessError("传输任务正在执行"))?;
+        let lock = self
+            .run_lock(id)
+            .try_acquire()?
+            .ok_or(AppError::BusinessError("传输任务正在其他实例执行"))?;
+        let result = self.run_locked(id, operator_id, &guard, &lock).await;
+        lock.release().await;
+        result
+    }
+
+    // 持有执行登记与执行锁后执行传输并回写任务
+    async fn run_locked(
+        &self,
+        id: i64,
+        operator_id: i64,
+        guard: &RunningGuard,
+        lock: &LockGuard,
+    ) -> Result<TransTaskRunListVo, AppError> {
+        let task = self
+            .trans_task_repo
+            .select_by_id(&id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        let data_source = self
+            .data_source_service
+            .get_data_source_by_id(task.data_source_id)
+            .await
+            .ok_or(AppError::BusinessError("数据源不存在"))?;
+        let settings = self.load_settings(id).await?;
+
+        let mut run = self
+            .trans_task_run_service
+            .start(id, operator_id, lock.token())
+            .await?;
+        self.trans_task_repo
+            .update_run_state(&id, RUN_STATE_RUNNING)
+            .await?;
+        self.watch_control_signal(id, guard.control.clone());
+
+        let (result, attempts, last_error) = self
+            .run_with_retry(
+                &task,
+                &settings,
+                &data_source,
+                lock,
+                &guard.control,
+                &run,
+            )
+            .await;
+        run.attempts = attempts;
+        self.trans_task_run_service
+            .finish(&mut run, &result)
+            .await?;
+        if let (Ok(outcome), Some(trans_task_run_id)) =
+            (&result, run.trans_task_run_id)
+            && let Err(e) = self
+                .trans_task_quality_service
+                .save_results(trans_task_run_id, &outcome.quality_results)
+                .await
+        {
+            tracing::error!("保存质量检查结果失败: {:?}", e);
+        }
+        self.progress_hub.publish(TransProgressVo::finished(
+            &run,
+            result.as_ref().map_or(0, |outcome| outcome.batches),
+        ));
+
+        // 重新读取任务，避免覆盖执行期间对任务的修改
+        let mut entity = self
+            .trans_task_repo
+            .select_by_id(&id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        entity.run_state = String::from(run_state_of(&result));
+        entity.last_attempt_count = attempts;
+        entity.last_error = last_error;
+        let outcome = match result {
+            Ok(outcome) if outcome.rolled_back => {
+                let e = outcome
+                    .quality_error()
+                    .unwrap_or(AppError::BusinessError("数据质量检查未通过"));
+                entity.last_error = Some(e.to_string());
+                self.write_back(&mut entity, lock).await?;
+                return Err(e);
+            }
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.write_back(&mut entity, lock).await?;
+                return Err(e);
+            }
+        };
+        entity.row_count = outcome.target_rows as i64;
+        entity.last_trans_time = DateTime::now();
+        // 仅在加载成功后推进高水位，暂停时推进到已提交部分的高水位
+        if outcome.watermark.is_some() {
+            entity.watermark_value = outcome.watermark.clone();
+        }
+        // CDC模式记录已提交的binlog位置，下次从此处继续
+        if let Some(position) = &outcome.binlog_position {
+            entity.binlog_file = Some(position.file.clone());
+            entity.binlog_position = Some(position.position as i64);
+            entity.gtid_set = position.gtid_set.clone();
+        }
+        // 阻断规则未通过但未要求回滚：数据已提交，执行记为失败
+        let quality_error = outcome.quality_error();
+        if let Some(e) = &quality_error {
+            entity.last_error = Some(e.to_string());
+        }
+        self.write_back(&mut entity, lock).await?;
+        match quality_error {
+            Some(e) => Err(e),
+            None => Ok(run.to_list_vo()),
+        }
+    }
+
+    async fn load_settings(&self, id: i64) -> Result<TaskSettings, AppError> {
+        Ok(TaskSettings {
+            mappings: self
+                .trans_task_column_service
+                .select_by_trans_task_id(id)
+                .await?,
+            rules: self
+                .trans_task_quality_service
+                .select_rules_by_trans_task_id(id)
+                .await?,
+            backfill: None,
+        })
+    }
+
+    // 按水位列区间回填增量任务：整个回填登记为一次执行，各分片作为其子执行
+    // 在后台依次执行，每个分片在一个事务中删除目标表该区间的行并重新写入，
+    // 不推进任务的高水位。回填期间占用执行锁，与常规执行互斥；
+    // 回填不支持暂停，收到暂停信号时按取消处理
+    pub async fn backfill(
+        &self,
+        id: i64,
+        bo: TransTaskBackfillBo,
+        operator_id: i64,
+    ) -> Result<TransTaskRunListVo, AppError> {
+        let window = bo.window().map_err(AppError::TransError)?;
+        let slices = bo.slices().map_err(AppError::TransError)?;
+        let guard = RunningGuard::acquire(&self.running, id)
+            .ok_or(AppError::BusinessError("传输任务正在执行"))?;
+        let lock = self
+            .run_lock(id)
+            .try_acquire()?
+            .ok_or(AppError::BusinessError("传输任务正在其他实例执行"))?;
+        let task = self
+            .trans_task_repo
+            .select_by_id(&id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        if task.sync_mode != SYNC_MODE_INCREMENTAL {
+            return Err(AppError::BusinessError("仅增量同步的任务支持回填"));
+        }
+        let data_source = self
+            .data_source_service
+            .get_data_source_by_id(task.data_source_id)
+            .await
+            .ok_or(AppError::BusinessError("数据源不存在"))?;
+        let settings = self.load_settings(id).await?;
+
+        let run = self
+            .trans_task_run_service
+            .start_backfill(id, operator_id, lock.token(), None, &window)
+            .await?;
+        self.trans_task_repo
+            .update_run_state(&id, RUN_STATE_RUNNING)
+            .await?;
+        self.watch_control_signal(id, guard.control.clone());
+
+        let vo = run.to_list_vo();
+        let service = self.clone();
+        tokio::spawn(async move {
+            let backfill = Backfill {
+                task,
+                data_source,
+                settings,
+                guard,
+                lock,
+                operator_id,
+            };
+            if let Err(e) =
+                service.execute_backfill(backfill, run, slices).await
+            {
+                tracing::error!("传输任务 {} 回填失败: {:?}", id, e);
+            }
+        });
+        Ok(vo)
+    }
+
+    async fn execute_backfill(
+        &self,
+        backfill: Backfill,
+        mut run: TransTaskRun,
+        slices: Vec<BackfillWindow>,
+    ) -> Result<(), AppError> {
+        let Backfill {
+            task,
+            data_source,
+            settings,
+            guard,
+            lock,
+            operator_id,
+        } = backfill;
+        let id = task.trans_task_id.unwrap_or_default();
+        let mut total = TransOutcome::default();
+        // 是否有分片已提交，已提交的分片不随后续分片失败回滚
+        let mut committed = false;
+        // 中途出错时同样结束父执行并恢复任务状态，错误记入结果
+        let mut result = Ok(());
+        for window in slices {
+            // 收到控制信号后不再开始新的分片
+            if guard.control.signal() != RunSignal::None {
+                result = Err(AppError::TransCancelled);
+                break;
+            }
+            let mut slice_run = match self
+                .trans_task_run_service
+                .start_backfill(
+                    id,
+                    operator_id,
+                    lock.token(),
+                    run.trans_task_run_id,
+                    &window,
+                )
+                .await
+            {
+                Ok(slice_run) => slice_run,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            };
+            let slice_settings = TaskSettings {
+                backfill: Some(window.clone()),
+                ..settings.clone()
+            };
+            let (slice_result, attempts, _) = self
+                .run_with_retry(
+                    &task,
+                    &slice_settings,
+                    &data_source,
+                    &lock,
+                    &guard.control,
+                    &slice_run,
+                )
+                .await;
+            let slice_result = slice_result.map_err(|e| match e {
+                AppError::TransPaused => AppError::TransCancelled,
+                e => e,
+            });
+            slice_run.attempts = attempts;
+            let slice_finished = self
+                .trans_task_run_service
+                .finish(&mut slice_run, &slice_result)
+                .await;
+            if let (Ok(outcome), Some(trans_task_run_id)) =
+                (&slice_result, slice_run.trans_task_run_id)
+                && let Err(e) = self
+                    .trans_task_quality_service
+                    .save_results(trans_task_run_id, &outcome.quality_results)
+                    .await
+            {
+                tracing::error!("保存质量检查结果失败: {:?}", e);
+            }
+            self.progress_hub.publish(TransProgressVo::finished(
+                &slice_run,
+                slice_result.as_ref().map_or(0, |outcome| outcome.batches),
+            ));
+
+            let slice_error = match slice_result {
+                Ok(outcome) => {
+                    if !outcome.rolled_back {
+                        committed = true;
+                        total.rows_read += outcome.rows_read;
+                        total.rows_written += outcome.rows_written;
+                        total.rows_deleted += outcome.rows_deleted;
+                        total.bytes += outcome.bytes;
+                        total.batches += outcome.batches;
+                        total.target_rows = outcome.target_rows;
+                    }
+                    outcome.quality_error()
+                }
+                Err(AppError::TransCancelled) => {
+                    result = Err(AppError::TransCancelled);
+                    break;
+                }
+                Err(e) => Some(e),
+            };
+            if let Some(e) = slice_error {
+                result = Err(AppError::TransError(format!(
+                    "分片 [{}, {}) 失败: {}",
+                    window.start_string(),
+                    window.end_string(),
+                    e
+                )));
+                break;
+            }
+            if let Err(e) = slice_finished {
+                result = Err(e);
+                break;
+            }
+        }
+        let target_rows = committed.then_some(total.target_rows);
+        let result = result.map(|_| total);
+        let finished =
+            self.trans_task_run_service.finish(&mut run, &result).await;
+        let written = self
+            .finish_backfill_task(id, &result, target_rows, &lock)
+            .await;
+        lock.release().await;
+        finished.and(written)
+    }
+
+    // 回填不推进高水位，只同步目标表行数与执行状态
+    async fn finish_backfill_task(
+        &self,
+        id: i64,
+        result: &Result<TransOutcome, AppError>,
+        target_rows: Option<u64>,
+        lock: &LockGuard,
+    ) -> Result<(), AppError> {
+        let mut entity = self
+            .trans_task_repo
+            .select_by_id(&id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        entity.run_state = String::from(RUN_STATE_IDLE);
+        entity.last_error = result.as_ref().err().map(|e| e.to_string());
+        if let Some(target_rows) = target_rows {
+            entity.row_count = target_rows as i64;
+        }
+        self.write_back(&mut entity, lock).await
+    }
+
+    // fencing：以执行锁的token作为条件回写任务，已有更新的持有者回写过时放弃
+    async fn write_back(
+        &self,
+        entity: &mut TransTask,
+        lock: &LockGuard,
+    ) -> Result<(), AppError> {
+        let id = entity.trans_task_id.unwrap_or_default();
+        entity.fence_token = lock.token() as i64;
+        let result = self
+            .trans_task_repo
+            .update_by_id_fenced(entity, &id)
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(AppError::BusinessError(
+                "执行锁已被新的持有者获取, 放弃回写",
+            ));
+        }
+        Ok(())
+    }
+
+    // 按任务的重试策略执行传输，仅对可重试的错误（网络、锁等待超时、死锁等）重试。
+    // 返回最终结果、尝试次数以及最后一次失败的错误信息
+    async fn run_with_retry(
+        &self,
+        task: &TransTask,
+        settings: &TaskSettings,
+        data_source: &DataSourceDetailVo,
+        lock: &LockGuard,
+        control: &RunControl,
+        run: &TransTaskRun,
+    ) -> (Result<TransOutcome, AppError>, i32, Option<String>) {
+        let max_attempts = task.retry_max_attempts.max(1);
+        let mut last_error = None;
+        let mut attempt = 1;
+        loop {
+            // 传输过程为阻塞IO，放到阻塞线程池中执行；每次尝试都在独立事务中，失败即回滚
+            let engine = self.trans_engine.clone();
+            let holder = lock.holder();
+            let run_control = control.clone();
+            let data_source_clone = data_source.clone();
+            let task_clone = task.clone();
+            let settings_clone = settings.clone();
+            let mut progress = ProgressReporter::new(
+                self.progress_hub.clone(),
+                task.trans_task_id.unwrap_or_default(),
+                run.trans_task_run_id.unwrap_or_default(),
+            );
+            let result = tokio::task::spawn_blocking(move || {
+                engine.run(
+                    &data_source_clone,
+                    &task_clone,
+                    &settings_clone,
+                    &holder,
+                    &mut progress,
+                    &run_control,
+                )
+            })
+            .await
+            .unwrap_or_else(|e| Err(AppError::TransError(e.to_string())));
+
+            let e = match result {
+                Err(e)
+                    if !matches!(
+                        e,
+                        AppError::TransPaused | AppError::TransCancelled
+                    ) =>
+                {
+                    e
+                }
+                result => return (result, attempt, last_error),
+            };
+            last_error = Some(e.to_string());
+            if !e.is_retryable()
+                || attempt >= max_attempts
+                || !lock.holder().is_held()
+            {
+                return (Err(e), attempt, last_error);
+            }
+
+            let delay = task.retry_delay(attempt);
+            tracing::warn!(
+                "传输任务第{}次执行失败, {}ms后重试: {:?}",
+                attempt,
+                delay.as_millis(),
+                e
+            );
+            // 等待期间同样响应暂停与取消
+            if let Err(e) = Self::wait_retry(delay, control).await {
+                return (Err(e), attempt, last_error);
+            }
+            attempt += 1;
+        }
+    }
+
+    // 分段等待重试间隔，期间收到控制信号立即结束
+    async fn wait_retry(
+        delay: Duration,
+        control: &RunControl,
+    ) -> Result<(), AppError> {
+        let deadline = tokio::time::Instant::now() + delay;
+        loop {
+            match control.signal() {
+                RunSignal::Cancel => return Err(AppError::TransCancelled),
+                RunSignal::Pause => return Err(AppError::TransPaused),
+                RunSignal::None => {}
+            }
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Ok(());
+            }
+            tokio::time::sleep((deadline - now).min(RETRY_WAIT_STEP)).await;
+        }
+    }
+
+    // 执行期间轮询其他实例经Redis转发来的控制信号
+    fn watch_control_signal(&self, id: i64, control: RunControl) {
+        let redis_pool = self.redis_pool.clone();
+        tokio::spawn(async move {
+            while !control.is_finished() {
+                tokio::time::sleep(CONTROL_POLL_INTERVAL).await;
+                let signal = redis_pool.get().map_err(AppError::from).and_then(
+                    |mut conn| {
+                        conn.get_del::<_, Option<String>>(control_key(id))
+                            .map_err(AppError::from)
+                    },
+                );
+                match signal {
+                    Ok(Some(signal)) => control.send(RunSignal::parse(&signal)),
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("读取控制信号失败: {:?}", e),
+                }
+            }
+        });
+    }
+
+    // 向执行中的任务发出控制信号；任务在其他实例执行时经Redis转发
+    async fn send_signal(
+        &self,
+        id: i64,
+        signal: RunSignal,
+    ) -> Result<bool, AppError> {
+        if let Some(control) = self.running.lock().unwrap().get(&id) {
+            control.send(signal);
+            return Ok(true);
+        }
+        if self.run_lock(id).is_locked()? {
+            let mut conn = self.redis_pool.get()?;
+            conn.set_ex::<_, _, ()>(
+                control_key(id),
+                signal.as_str(),
+                CONTROL_SIGNAL_TTL,
+            )?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    // 取消执行中的任务并回滚本次写入；已暂停的任务直接标记为取消
+    pub async fn cancel(&self, id: i64) -> Result<(), AppError> {
+        if self.send_signal(id, RunSignal::Cancel).await? {
+            return Ok(());
+        }
+        let entity = self
+            .trans_task_repo
+            .select_by_id(&id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        if entity.run_state != RUN_STATE_PAUSED {
+            return Err(AppError::BusinessError("传输任务未在执行"));
+        }
+        self.trans_task_repo
+            .update_run_state(&id, RUN_STATE_CANCELLED)
+            .await?;
+        Ok(())
+    }
+
+    // 暂停执行中的任务：增量模式提交已写入的批次，其余模式回滚
+    pub async fn pause(&self, id: i64) -> Result<(), AppError> {
+        if self.send_signal(id, RunSignal::Pause).await? {
+            Ok(())
+        } else {
+            Err(AppError::BusinessError("传输任务未在执行"))
+        }
+    }
+
+    // 恢复已暂停的任务：增量模式从已提交的高水位之后继续，其余模式重新执行
+    pub async fn resume(
+        &self,
+        id: i64,
+        current_user: &User,
+    ) -> Result<TransTaskRunListVo, AppError> {
+        let entity = self
+            .trans_task_repo
+            .select_by_id(&id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        if entity.run_state != RUN_STATE_PAUSED {
+            return Err(AppError::BusinessError("传输任务未暂停"));
+        }
+        self.run_task(id, current_user.get_user_id()).await
+    }
+
+    // 订阅任务的执行进度
+    pub fn subscribe_progress(
+        &self,
+        id: i64,
+    ) -> mpsc::Receiver<TransProgressVo> {
+        self.progress_hub.subscribe(id)
+    }
+
+    // 按任务当前配置预览一次传输，不移动数据
+    pub async fn plan(&self, id: i64) -> Result<TransPlanVo, AppError> {
+        let task = self
+            .trans_task_repo
+            .select_by_id(&id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        let data_source = self
+            .data_source_service
+            .get_data_source_by_id(task.data_source_id)
+            .await
+            .ok_or(AppError::BusinessError("数据源不存在"))?;
+        let settings = TaskSettings {
+            mappings: self
+                .trans_task_column_service
+                .select_by_trans_task_id(id)
+                .await?,
+            ..Default::default()
+        };
+
+        let engine = self.trans_engine.clone();
+        tokio::task::spawn_blocking(move || {
+            engine.plan(&data_source, &task, &settings)
+        })
+        .await
+        .map_err(|e| AppError::TransError(e.to_string()))?
+    }
+
+    // 与源表对账，可按需重新复制不一致的区间；对账期间占用执行锁，不与传输同时进行
+    pub async fn reconcile(
+        &self,
+        id: i64,
+        bo: TransTaskReconcileBo,
+    ) -> Result<TransTaskReconcileVo, AppError> {
+        let _guard = RunningGuard::acquire(&self.running, id)
+            .ok_or(AppError::BusinessError("传输任务正在执行"))?;
+        let lock = self
+            .run_lock(id)
+            .try_acquire()?
+            .ok_or(AppError::BusinessError("传输任务正在其他实例执行"))?;
+        let task = self
+            .trans_task_repo
+            .select_by_id(&id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        let data_source = self
+            .data_source_service
+            .get_data_source_by_id(task.data_source_id)
+            .await
+            .ok_or(AppError::BusinessError("数据源不存在"))?;
+        let mappings = self
+            .trans_task_column_service
+            .select_by_trans_task_id(id)
+            .await?;
+
+        let reconciler = self.trans_reconciler.clone();
+        let holder = lock.holder();
+        let repair = bo.repair;
+        let report = tokio::task::spawn_blocking(move || {
+            reconciler.reconcile(&data_source, &task, &mappings, &bo, &holder)
+        })
+        .await
+        .map_err(|e| AppError::TransError(e.to_string()))??;
+
+        // 修复后目标表与源端一致，同步任务记录的行数
+        if repair && !report.matched {
+            let mut entity = self
+                .trans_task_repo
+                .select_by_id(&id)
+                .await?
+                .ok_or(AppError::BusinessError("传输任务不存在"))?;
+            entity.row_count = report.source_rows as i64;
+            self.write_back(&mut entity, &lock).await?;
+        }
+        lock.release().await;
+        Ok(report)
+    }
+
+    // 预览任务接下来的触发时间，按任务时区输出
+    pub async fn next_runs(
+        &self,
+        id: i64,
+        count: usize,
+    ) -> Result<Vec<String>, AppError> {
+        let entity = self
+            .trans_task_repo
+            .select_by_id(&id)
+            .await?
+            .ok_or(AppError::BusinessError("传输任务不存在"))?;
+        let schedule = entity
+            .cron_schedule()
+            .map_err(AppError::ScheduleError)?
+            .ok_or(AppError::BusinessError("传输任务未配置调度"))?;
+        let timezone = schedule.timezone();
+        Ok(schedule
+            .fires_after(Utc::now())
+            .take(count)
+            .map(|time| timezone.format(time))
+            .collect())
+    }
 }
@@ -0,0 +1,129 @@
+use serde::Serialize;
+
+// MySQL列类型到DuckDB列类型的默认映射，传输引擎建表与生成列映射时均以此为准：
+//
+// | MySQL                                   | DuckDB                 | 说明                                  |
+// |-----------------------------------------|------------------------|---------------------------------------|
+// | tinyint / smallint / bigint             | 同名（unsigned为U前缀） | unsigned bigint 对应 UBIGINT          |
+// | mediumint / int / integer               | INTEGER / UINTEGER     |                                       |
+// | decimal(p,s) / numeric(p,s)             | DECIMAL(p,s)           | p 超过38位（如 decimal(65,30)）为VARCHAR |
+// | float / double / real                   | FLOAT / DOUBLE         |                                       |
+// | year                                    | SMALLINT               |                                       |
+// | date                                    | DATE                   | 零日期写入NULL                         |
+// | datetime / timestamp                    | TIMESTAMP              | 零日期写入NULL                         |
+// | time                                    | INTERVAL               | 可超过24小时或为负                      |
+// | char / varchar / *text                  | VARCHAR                |                                       |
+// | enum                                    | VARCHAR                | 写入成员名                             |
+// | set                                     | VARCHAR                | 逗号分隔的成员名                        |
+// | json                                    | VARCHAR                | 原样写入JSON文本                        |
+// | bit(1)                                  | BOOLEAN                |                                       |
+// | bit(n)                                  | UBIGINT                | 按大端无符号整数解析                     |
+// | binary / varbinary / *blob / geometry   | BLOB                   | geometry 为内部WKB格式                  |
+// | 其他                                    | VARCHAR                |                                       |
+//
+// 列映射可覆盖目标类型，写入时由DuckDB按目标类型转换
+#[derive(Clone, Debug, Serialize)]
+pub struct TypeMapping {
+    pub mysql_type: &'static str,
+    pub duck_type: &'static str,
+    // unsigned 列的目标类型，None 表示与 duck_type 相同
+    pub unsigned_duck_type: Option<&'static str>,
+    pub remark: &'static str,
+}
+
+const fn mapping(
+    mysql_type: &'static str,
+    duck_type: &'static str,
+    unsigned_duck_type: Option<&'static str>,
+    remark: &'static str,
+) -> TypeMapping {
+    TypeMapping {
+        mysql_type,
+        duck_type,
+        unsigned_duck_type,
+        remark,
+    }
+}
+
+pub const TYPE_MAPPINGS: &[TypeMapping] = &[
+    mapping("tinyint", "TINYINT", Some("UTINYINT"), ""),
+    mapping("smallint", "SMALLINT", Some("USMALLINT"), ""),
+    mapping("mediumint", "INTEGER", Some("UINTEGER"), ""),
+    mapping("int", "INTEGER", Some("UINTEGER"), ""),
+    mapping("integer", "INTEGER", Some("UINTEGER"), ""),
+    mapping("bigint", "BIGINT", Some("UBIGINT"), ""),
+    mapping("decimal", "DECIMAL(p,s)", None, "精度超过38位时为VARCHAR"),
+    mapping("numeric", "DECIMAL(p,s)", None, "精度超过38位时为VARCHAR"),
+    mapping("float", "FLOAT", None, ""),
+    mapping("double", "DOUBLE", None, ""),
+    mapping("real", "DOUBLE", None, ""),
+    mapping("year", "SMALLINT", None, ""),
+    mapping("date", "DATE", None, "零日期写入NULL"),
+    mapping("datetime", "TIMESTAMP", None, "零日期写入NULL"),
+    mapping("timestamp", "TIMESTAMP", None, "零日期写入NULL"),
+    mapping("time", "INTERVAL", None, "可超过24小时或为负"),
+    mapping("char", "VARCHAR", None, ""),
+    mapping("varchar", "VARCHAR", None, ""),
+    mapping("tinytext", "VARCHAR", None, ""),
+    mapping("text", "VARCHAR", None, ""),
+    mapping("mediumtext", "VARCHAR", None, ""),
+    mapping("longtext", "VARCHAR", None, ""),
+    mapping("enum", "VARCHAR", None, "写入成员名"),
+    mapping("set", "VARCHAR", None, "逗号分隔的成员名"),
+    mapping("json", "VARCHAR", None, "原样写入JSON文本"),
+    mapping(
+        "bit",
+        "UBIGINT",
+        None,
+        "bit(1)为BOOLEAN，其余按大端无符号整数解析",
+    ),
+    mapping("binary", "BLOB", None, ""),
+    mapping("varbinary", "BLOB", None, ""),
+    mapping("tinyblob", "BLOB", None, ""),
+    mapping("blob", "BLOB", None, ""),
+    mapping("mediumblob", "BLOB", None, ""),
+    mapping("longblob", "BLOB", None, ""),
+    mapping("geometry", "BLOB", None, "内部WKB格式"),
+];
+
+// 未在映射表中的类型按文本写入
+const FALLBACK_DUCK_TYPE: &str = "VARCHAR";
+
+// DuckDB的DECIMAL精度上限
+const DUCK_DECIMAL_MAX_PRECISION: u64 = 38;
+
+// 按映射表计算源列的默认DuckDB类型，data_type 与 column_type 均为小写
+pub fn duck_type(
+    data_type: &str,
+    column_type: &str,
+    numeric_precision: Option<u64>,
+    numeric_scale: Option<u64>,
+) -> String {
+    match data_type {
+        "decimal" | "numeric" => {
+            let precision = numeric_precision.unwrap_or(18);
+            let scale = numeric_scale.unwrap_or(0);
+            if precision <= DUCK_DECIMAL_MAX_PRECISION {
+                format!("DECIMAL({}, {})", precision, scale)
+            } else {
+                // 超出DuckDB精度的值按原文写入，不做有损转换；
+                // 需要数值类型时可通过列映射覆盖目标类型
+                String::from("VARCHAR")
+            }
+        }
+        "bit" if column_type == "bit(1)" => String::from("BOOLEAN"),
+        _ => {
+            let unsigned = column_type.contains("unsigned");
+            let duck_type = TYPE_MAPPINGS
+                .iter()
+                .find(|mapping| mapping.mysql_type == data_type)
+                .map_or(FALLBACK_DUCK_TYPE, |mapping| {
+                    match mapping.unsigned_duck_type {
+                        Some(unsigned_type) if unsigned => unsigned_type,
+                        _ => mapping.duck_type,
+                    }
+                });
+            String::from(duck_type)
+        }
+    }
+}
@@ -1 +1,2 @@
 pub mod entity;
+pub mod page;
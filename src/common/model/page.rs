@@ -0,0 +1,18 @@
+use rbatis::plugin::page::PageRequest;
+use serde::Deserialize;
+
+// 分页查询参数
+#[derive(Clone, Debug, Deserialize)]
+pub struct PageBo {
+    pub page_no: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
+impl PageBo {
+    pub fn to_page_request(&self) -> PageRequest {
+        PageRequest::new(
+            self.page_no.unwrap_or(1),
+            self.page_size.unwrap_or(10),
+        )
+    }
+}
@@ -1,22 +1,33 @@
 use serde::Deserialize;
 
 // 配置结构体
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct AppConfig {
     pub server: ServerConfig,
     pub db: DbConfig,
     pub redis: RedisConfig,
+    pub duckdb: DuckDbConfig,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    #[serde(default)]
+    pub progress: ProgressConfig,
+    #[serde(default)]
+    pub transfer: TransferConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub datasource: DataSourceConfig,
 }
 
 // 服务器配置结构体
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub port: i32,
 }
 
 // 数据库配置结构体
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct DbConfig {
     pub url: String,
     pub port: i32,
@@ -26,10 +37,117 @@ pub struct DbConfig {
 }
 
 // Redis配置结构体
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct RedisConfig {
     pub url: String,
     pub port: i32,
     pub password: Option<String>,
     pub db: i32,
 }
+
+// DuckDB数仓配置结构体
+#[derive(Clone, Debug, Deserialize)]
+pub struct DuckDbConfig {
+    pub path: String,
+}
+
+// 调度器停机期间错过触发的补偿策略
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CatchUpPolicy {
+    // 仅记录错过的触发，不补跑
+    Skip,
+    // 记录错过的触发，并补跑一次
+    Once,
+}
+
+// 传输任务调度器配置结构体
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    pub enabled: bool,
+    pub poll_interval_secs: u64,
+    pub catch_up: CatchUpPolicy,
+    // 单个任务每次最多登记的错过触发条数
+    pub max_missed_records: usize,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            poll_interval_secs: 10,
+            catch_up: CatchUpPolicy::Skip,
+            max_missed_records: 100,
+        }
+    }
+}
+
+// 传输进度推送配置结构体
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ProgressConfig {
+    // 多实例部署时通过Redis发布订阅转发进度，任一实例都可订阅
+    pub redis_pubsub: bool,
+}
+
+// 传输引擎配置结构体
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct TransferConfig {
+    // 单个任务并行读取源表的线程数上限，任务配置的并行度超过时按此截断
+    pub max_parallelism: usize,
+    // DAG执行时同时执行的任务数上限
+    pub dag_concurrency: usize,
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            max_parallelism: 8,
+            dag_concurrency: 4,
+        }
+    }
+}
+
+// 数据导出配置结构体
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    // Parquet导出的根目录，按 {schema}/{表名}/ 分目录存放
+    pub parquet_dir: String,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            parquet_dir: String::from("data/parquet"),
+        }
+    }
+}
+
+// 数据源配置结构体
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct DataSourceConfig {
+    // 表列表、表结构在Redis中的缓存时间
+    pub catalog_cache_ttl_secs: u64,
+    // 数据预览单次返回的行数上限
+    pub preview_max_rows: usize,
+    // 数据预览查询的超时时间，超时后查询在数据库端被中止
+    pub preview_timeout_secs: u64,
+    // 文件型数据源（SQLite、DuckDB、CSV等）允许访问的根目录，
+    // 相对路径按此目录解析，解析后位于此目录之外的路径被拒绝
+    pub file_root: String,
+}
+
+impl Default for DataSourceConfig {
+    fn default() -> Self {
+        Self {
+            catalog_cache_ttl_secs: 600,
+            preview_max_rows: 1000,
+            preview_timeout_secs: 30,
+            file_root: String::from("data/files"),
+        }
+    }
+}
@@ -52,6 +52,94 @@ pub enum AppError {
 
     #[error("业务异常: {0}")]
     BusinessError(&'static str),
+
+    #[error("连接池错误: {0}")]
+    PoolError(#[from] r2d2::Error),
+
+    #[error("MySQL错误: {0}")]
+    MySqlError(#[from] r2d2_mysql::mysql::Error),
+
+    #[error("PostgreSQL错误: {0}")]
+    PostgresError(#[from] postgres::Error),
+
+    #[error("SQLite错误: {0}")]
+    SqliteError(#[from] rusqlite::Error),
+
+    #[error("数据源错误: {0}")]
+    DataSourceError(String),
+
+    #[error("Redis错误: {0}")]
+    RedisError(#[from] redis::RedisError),
+
+    #[error("DuckDB错误: {0}")]
+    DuckDbError(#[from] duckdb::Error),
+
+    #[error("数据传输错误: {0}")]
+    TransError(String),
+
+    #[error("传输已取消")]
+    TransCancelled,
+
+    #[error("传输已暂停")]
+    TransPaused,
+
+    #[error("调度配置错误: {0}")]
+    ScheduleError(String),
+
+    #[error("抽取SQL校验失败: {0}")]
+    SourceSqlError(String),
+
+    #[error("数据质量检查未通过: {0}")]
+    QualityCheckFailed(String),
+
+    #[error("任务依赖配置错误: {0}")]
+    DependencyError(String),
+}
+
+// 可重试的MySQL服务端错误码：锁等待超时、死锁、连接过多、服务器关闭/断开、
+// 查询被中断（如服务端重启）
+const RETRYABLE_MYSQL_CODES: &[u16] =
+    &[1040, 1053, 1205, 1213, 1317, 2006, 2013];
+
+impl AppError {
+    // 区分瞬时故障（网络、锁等待、死锁、写冲突）与不可恢复的错误（表不存在、认证失败、
+    // 配置或数据错误），仅前者值得重试
+    pub fn is_retryable(&self) -> bool {
+        use r2d2_mysql::mysql::{DriverError, Error as MyError};
+
+        match self {
+            AppError::MySqlError(e) => match e {
+                MyError::IoError(_) | MyError::CodecError(_) => true,
+                MyError::MySqlError(e) => {
+                    RETRYABLE_MYSQL_CODES.contains(&e.code)
+                }
+                MyError::DriverError(e) => matches!(
+                    e,
+                    DriverError::ConnectTimeout
+                        | DriverError::CouldNotConnect(_)
+                        | DriverError::PacketOutOfSync
+                        | DriverError::UnexpectedPacket
+                        | DriverError::Timeout
+                ),
+                _ => false,
+            },
+            // 获取连接超时多为网络问题，但认证失败也会表现为超时
+            AppError::PoolError(e) => {
+                let message = e.to_string();
+                !message.contains("Access denied")
+                    && !message.contains("Unknown database")
+            }
+            AppError::RedisError(e) => {
+                e.is_io_error() || e.is_timeout() || e.is_connection_dropped()
+            }
+            AppError::DuckDbError(e) => {
+                let message = e.to_string().to_lowercase();
+                message.contains("conflict")
+                    || message.contains("could not set lock")
+            }
+            _ => false,
+        }
+    }
 }
 
 impl IntoResponse for AppError {
@@ -66,6 +154,22 @@ impl IntoResponse for AppError {
             AppError::JwtTokenError(e) => {
                 (StatusCode::UNAUTHORIZED, e.to_string())
             }
+            e @ (AppError::PoolError(_)
+            | AppError::MySqlError(_)
+            | AppError::PostgresError(_)
+            | AppError::SqliteError(_)
+            | AppError::DataSourceError(_)
+            | AppError::RedisError(_)
+            | AppError::DuckDbError(_)
+            | AppError::TransError(_)
+            | AppError::TransCancelled
+            | AppError::TransPaused
+            | AppError::ScheduleError(_)
+            | AppError::SourceSqlError(_)
+            | AppError::QualityCheckFailed(_)
+            | AppError::DependencyError(_)) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+            }
         };
         let body = R::<()>::error_with_code_and_message(
             status.as_u16(),
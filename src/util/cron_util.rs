@@ -0,0 +1,581 @@
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime,
+    TimeZone, Timelike, Utc,
+};
+use chrono_tz::Tz;
+
+// 向后查找触发时间的最大跨度，超过则认为表达式永不触发（如 2月30日）
+const SEARCH_YEARS: i64 = 5;
+
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT",
+    "NOV", "DEC",
+];
+
+const WEEKDAY_NAMES: &[&str] =
+    &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+// 调度时区：UTC、服务器本地时区、固定偏移或IANA时区（含夏令时规则）
+#[derive(Clone, Copy, Debug)]
+pub enum ScheduleTimezone {
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+    Zone(Tz),
+}
+
+impl ScheduleTimezone {
+    // 支持 UTC、Local、+08:00 / UTC+8 形式的偏移以及 Europe/Berlin 等IANA时区名
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        match name.to_uppercase().as_str() {
+            "" | "UTC" | "GMT" | "Z" => return Ok(ScheduleTimezone::Utc),
+            "LOCAL" => return Ok(ScheduleTimezone::Local),
+            _ => {}
+        }
+        if let Ok(zone) = name.parse::<Tz>() {
+            return Ok(ScheduleTimezone::Zone(zone));
+        }
+        let offset = name
+            .strip_prefix("UTC")
+            .or_else(|| name.strip_prefix("GMT"))
+            .unwrap_or(name);
+        parse_offset(offset)
+            .map(ScheduleTimezone::Fixed)
+            .ok_or_else(|| format!("不支持的时区: {}", name))
+    }
+
+    fn local_time(self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            ScheduleTimezone::Utc => time.naive_utc(),
+            ScheduleTimezone::Local => time.with_timezone(&Local).naive_local(),
+            ScheduleTimezone::Fixed(offset) => {
+                time.with_timezone(&offset).naive_local()
+            }
+            ScheduleTimezone::Zone(zone) => {
+                time.with_timezone(&zone).naive_local()
+            }
+        }
+    }
+
+    // 本地时间换算回UTC，夏令时跳过的时间返回None，重复的时间取较早者
+    fn utc_time(self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            ScheduleTimezone::Utc => Some(local.and_utc()),
+            ScheduleTimezone::Local => Local
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|time| time.with_timezone(&Utc)),
+            ScheduleTimezone::Fixed(offset) => offset
+                .from_local_datetime(&local)
+                .single()
+                .map(|time| time.with_timezone(&Utc)),
+            ScheduleTimezone::Zone(zone) => zone
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|time| time.with_timezone(&Utc)),
+        }
+    }
+
+    // 按该时区输出 RFC 3339 格式的时间
+    pub fn format(self, time: DateTime<Utc>) -> String {
+        match self {
+            ScheduleTimezone::Utc => time.to_rfc3339(),
+            ScheduleTimezone::Local => time.with_timezone(&Local).to_rfc3339(),
+            ScheduleTimezone::Fixed(offset) => {
+                time.with_timezone(&offset).to_rfc3339()
+            }
+            ScheduleTimezone::Zone(zone) => {
+                time.with_timezone(&zone).to_rfc3339()
+            }
+        }
+    }
+}
+
+// 标准5段cron表达式：分 时 日 月 周
+// 支持 * ? , - / 、月份与星期英文缩写、周日写作0或7，以及 @hourly 等别名
+#[derive(Clone, Debug)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    // 日与星期同时受限时，任一满足即触发（与crontab一致）
+    day_restricted: bool,
+    weekday_restricted: bool,
+    timezone: ScheduleTimezone,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str, timezone: &str) -> Result<Self, String> {
+        let expression = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields = expression.split_whitespace().collect::<Vec<_>>();
+        if fields.len() != 5 {
+            return Err(format!(
+                "cron表达式应为5段（分 时 日 月 周）: {}",
+                expression
+            ));
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7, WEEKDAY_NAMES)?;
+        // 7 与 0 都表示周日
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59, &[])?,
+            hours: parse_field(fields[1], 0, 23, &[])?,
+            days: parse_field(fields[2], 1, 31, &[])?,
+            months: parse_field(fields[3], 1, 12, MONTH_NAMES)?,
+            weekdays,
+            day_restricted: is_restricted(fields[2]),
+            weekday_restricted: is_restricted(fields[4]),
+            timezone: ScheduleTimezone::parse(timezone)?,
+        })
+    }
+
+    pub fn timezone(&self) -> ScheduleTimezone {
+        self.timezone
+    }
+
+    // 严格晚于 after 的下一次触发时间
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = self.timezone.local_time(after);
+        let mut local = start
+            .with_second(0)
+            .and_then(|time| time.with_nanosecond(0))
+            .unwrap_or(start)
+            + Duration::minutes(1);
+        let limit = local + Duration::days(366 * SEARCH_YEARS);
+
+        while local < limit {
+            if !has_bit(self.months, local.month()) {
+                let (year, month) = if local.month() == 12 {
+                    (local.year() + 1, 1)
+                } else {
+                    (local.year(), local.month() + 1)
+                };
+                local = NaiveDate::from_ymd_opt(year, month, 1)?
+                    .and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.day_matches(local.date()) {
+                local = local.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !has_bit(self.hours, local.hour()) {
+                local = local.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !has_bit(self.minutes, local.minute()) {
+                local += Duration::minutes(1);
+                continue;
+            }
+            match self.timezone.utc_time(local) {
+                Some(time) if time > after => return Some(time),
+                _ => local += Duration::minutes(1),
+            }
+        }
+        None
+    }
+
+    // 晚于 after 的所有触发时间，按时间先后排列
+    pub fn fires_after(
+        &self,
+        after: DateTime<Utc>,
+    ) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        std::iter::successors(self.next_after(after), |time| {
+            self.next_after(*time)
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = has_bit(self.days, date.day());
+        let weekday =
+            has_bit(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.day_restricted && self.weekday_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+}
+
+fn has_bit(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn is_restricted(field: &str) -> bool {
+    !(field.starts_with('*') || field == "?")
+}
+
+// 解析单个字段为位图，第n位表示取值n
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("cron步长无效: {}", part))?;
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+        let (start, end) = match range {
+            "*" | "?" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (
+                    parse_value(start, min, names)?,
+                    parse_value(end, min, names)?,
+                ),
+                // 形如 5/10 表示从5开始每10个单位
+                None if step.is_some() => {
+                    (parse_value(range, min, names)?, max)
+                }
+                None => {
+                    let value = parse_value(range, min, names)?;
+                    (value, value)
+                }
+            },
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("cron字段超出范围 {}-{}: {}", min, max, part));
+        }
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(value: &str, min: u32, names: &[&str]) -> Result<u32, String> {
+    if let Some(index) = names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(value))
+    {
+        return Ok(index as u32 + min);
+    }
+    value
+        .parse::<u32>()
+        .map_err(|_| format!("cron取值无效: {}", value))
+}
+
+// 解析 +08:00、-0530、+8 形式的UTC偏移
+fn parse_offset(offset: &str) -> Option<FixedOffset> {
+    let (sign, rest) = match offset.chars().next()? {
+        '+' => (1, &offset[1..]),
+        '-' => (-1, &offset[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let hours = hours.parse::<i32>().ok().filter(|hours| *hours <= 14)?;
+    let minutes = minutes
+        .parse::<i32>()
+        .ok()
+        .filter(|minutes| *minutes < 60)?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    // 从 after 起的前 count 次触发时间
+    fn fires(
+        expression: &str,
+        timezone: &str,
+        after: &str,
+        count: usize,
+    ) -> Vec<DateTime<Utc>> {
+        CronSchedule::parse(expression, timezone)
+            .unwrap()
+            .fires_after(utc(after))
+            .take(count)
+            .collect()
+    }
+
+    fn times(values: &[&str]) -> Vec<DateTime<Utc>> {
+        values.iter().map(|value| utc(value)).collect()
+    }
+
+    #[test]
+    fn ranges_and_lists() {
+        assert_eq!(
+            fires("0 9-11,15 * * *", "UTC", "2024-05-01T10:30:00Z", 4),
+            times(&[
+                "2024-05-01T11:00:00Z",
+                "2024-05-01T15:00:00Z",
+                "2024-05-02T09:00:00Z",
+                "2024-05-02T10:00:00Z",
+            ])
+        );
+    }
+
+    #[test]
+    fn steps() {
+        assert_eq!(
+            fires("*/20 * * * *", "UTC", "2024-05-01T10:45:00Z", 3),
+            times(&[
+                "2024-05-01T11:00:00Z",
+                "2024-05-01T11:20:00Z",
+                "2024-05-01T11:40:00Z",
+            ])
+        );
+        // 起始值加步长，以及区间加步长
+        assert_eq!(
+            fires("5/25 1-5/2 * * *", "UTC", "2024-05-01T00:00:00Z", 4),
+            times(&[
+                "2024-05-01T01:05:00Z",
+                "2024-05-01T01:30:00Z",
+                "2024-05-01T01:55:00Z",
+                "2024-05-01T03:05:00Z",
+            ])
+        );
+    }
+
+    #[test]
+    fn month_and_weekday_names() {
+        // 2024-01-01 为周一
+        assert_eq!(
+            fires("0 8 * jan,MAR Mon-wed", "UTC", "2024-01-03T09:00:00Z", 3),
+            times(&[
+                "2024-01-08T08:00:00Z",
+                "2024-01-09T08:00:00Z",
+                "2024-01-10T08:00:00Z",
+            ])
+        );
+        assert_eq!(
+            fires("0 0 1 FEB *", "UTC", "2024-01-03T09:00:00Z", 1),
+            times(&["2024-02-01T00:00:00Z"])
+        );
+    }
+
+    #[test]
+    fn sunday_as_zero_or_seven() {
+        let expected = times(&["2024-05-05T00:00:00Z", "2024-05-12T00:00:00Z"]);
+        assert_eq!(
+            fires("0 0 * * 0", "UTC", "2024-05-01T00:00:00Z", 2),
+            expected
+        );
+        assert_eq!(
+            fires("0 0 * * 7", "UTC", "2024-05-01T00:00:00Z", 2),
+            expected
+        );
+        assert_eq!(
+            fires("0 0 * * SUN", "UTC", "2024-05-01T00:00:00Z", 2),
+            expected
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        let after = "2024-05-15T10:30:00Z";
+        assert_eq!(
+            fires("@hourly", "UTC", after, 1),
+            times(&["2024-05-15T11:00:00Z"])
+        );
+        assert_eq!(
+            fires("@daily", "UTC", after, 1),
+            times(&["2024-05-16T00:00:00Z"])
+        );
+        assert_eq!(
+            fires("@midnight", "UTC", after, 1),
+            times(&["2024-05-16T00:00:00Z"])
+        );
+        // 2024-05-19 为周日
+        assert_eq!(
+            fires("@weekly", "UTC", after, 1),
+            times(&["2024-05-19T00:00:00Z"])
+        );
+        assert_eq!(
+            fires("@monthly", "UTC", after, 1),
+            times(&["2024-06-01T00:00:00Z"])
+        );
+        assert_eq!(
+            fires("@yearly", "UTC", after, 1),
+            times(&["2025-01-01T00:00:00Z"])
+        );
+        assert_eq!(
+            fires("@annually", "UTC", after, 1),
+            times(&["2025-01-01T00:00:00Z"])
+        );
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // 日与星期都受限时任一满足即触发：每月13日以及每个周五
+        assert_eq!(
+            fires("0 0 13 * FRI", "UTC", "2024-09-01T00:00:00Z", 4),
+            times(&[
+                "2024-09-06T00:00:00Z",
+                "2024-09-13T00:00:00Z",
+                "2024-09-20T00:00:00Z",
+                "2024-09-27T00:00:00Z",
+            ])
+        );
+        assert_eq!(
+            fires("0 0 13 * FRI", "UTC", "2024-10-01T00:00:00Z", 2),
+            times(&["2024-10-04T00:00:00Z", "2024-10-11T00:00:00Z"])
+        );
+        // 只限制其中之一时，另一个为 * 或 ? 不起作用
+        assert_eq!(
+            fires("0 0 13 * *", "UTC", "2024-09-01T00:00:00Z", 2),
+            times(&["2024-09-13T00:00:00Z", "2024-10-13T00:00:00Z"])
+        );
+        assert_eq!(
+            fires("0 0 ? * FRI", "UTC", "2024-09-01T00:00:00Z", 2),
+            times(&["2024-09-06T00:00:00Z", "2024-09-13T00:00:00Z"])
+        );
+    }
+
+    #[test]
+    fn month_and_year_rollover() {
+        // 跳过没有31日的月份
+        assert_eq!(
+            fires("0 0 31 * *", "UTC", "2024-04-01T00:00:00Z", 3),
+            times(&[
+                "2024-05-31T00:00:00Z",
+                "2024-07-31T00:00:00Z",
+                "2024-08-31T00:00:00Z",
+            ])
+        );
+        assert_eq!(
+            fires("59 23 31 12 *", "UTC", "2024-12-31T23:59:00Z", 1),
+            times(&["2025-12-31T23:59:00Z"])
+        );
+        assert_eq!(
+            fires("0 0 * * *", "UTC", "2024-12-31T23:59:59Z", 1),
+            times(&["2025-01-01T00:00:00Z"])
+        );
+    }
+
+    #[test]
+    fn leap_day() {
+        assert_eq!(
+            fires("0 0 29 2 *", "UTC", "2023-03-01T00:00:00Z", 2),
+            times(&["2024-02-29T00:00:00Z", "2028-02-29T00:00:00Z"])
+        );
+        // 2月30日永不触发
+        let schedule = CronSchedule::parse("0 0 30 2 *", "UTC").unwrap();
+        assert_eq!(schedule.next_after(utc("2024-01-01T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn strictly_after() {
+        assert_eq!(
+            fires("0 * * * *", "UTC", "2024-05-01T10:00:00Z", 1),
+            times(&["2024-05-01T11:00:00Z"])
+        );
+        assert_eq!(
+            fires("0 * * * *", "UTC", "2024-05-01T09:59:59.500Z", 1),
+            times(&["2024-05-01T10:00:00Z"])
+        );
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        for expression in [
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "10-5 * * * *",
+            "* * * FOO *",
+            "@reboot",
+        ] {
+            assert!(
+                CronSchedule::parse(expression, "UTC").is_err(),
+                "{}",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn timezones() {
+        assert!(matches!(
+            ScheduleTimezone::parse("utc"),
+            Ok(ScheduleTimezone::Utc)
+        ));
+        assert!(matches!(
+            ScheduleTimezone::parse("Europe/Berlin"),
+            Ok(ScheduleTimezone::Zone(_))
+        ));
+        assert!(ScheduleTimezone::parse("Mars/Olympus").is_err());
+        assert!(ScheduleTimezone::parse("+15:00").is_err());
+        let expected = times(&["2024-05-01T01:00:00Z"]);
+        for timezone in ["+08:00", "UTC+8", "+0800", "Asia/Shanghai", "PRC"] {
+            assert_eq!(
+                fires("0 9 * * *", timezone, "2024-04-30T12:00:00Z", 1),
+                expected,
+                "{}",
+                timezone
+            );
+        }
+        assert_eq!(
+            fires("30 9 * * *", "Asia/Kolkata", "2024-04-30T12:00:00Z", 1),
+            times(&["2024-05-01T04:00:00Z"])
+        );
+    }
+
+    #[test]
+    fn daylight_saving_offset_changes() {
+        // 柏林2024-03-31切换为夏令时，本地9点对应的UTC时间提前1小时
+        assert_eq!(
+            fires("0 9 * * *", "Europe/Berlin", "2024-03-29T12:00:00Z", 3),
+            times(&[
+                "2024-03-30T08:00:00Z",
+                "2024-03-31T07:00:00Z",
+                "2024-04-01T07:00:00Z",
+            ])
+        );
+        assert_eq!(
+            fires("0 9 * * *", "America/New_York", "2024-11-02T12:00:00Z", 2),
+            times(&["2024-11-02T13:00:00Z", "2024-11-03T14:00:00Z"])
+        );
+    }
+
+    #[test]
+    fn daylight_saving_gap_and_overlap() {
+        // 纽约2024-03-10的2:00-3:00不存在，当天2:30不触发
+        assert_eq!(
+            fires("30 2 * * *", "America/New_York", "2024-03-09T12:00:00Z", 2),
+            times(&["2024-03-11T06:30:00Z", "2024-03-12T06:30:00Z"])
+        );
+        // 2024-11-03的1:00-2:00出现两次，只在较早的一次触发
+        assert_eq!(
+            fires("30 1 * * *", "America/New_York", "2024-11-02T12:00:00Z", 2),
+            times(&["2024-11-03T05:30:00Z", "2024-11-04T06:30:00Z"])
+        );
+    }
+}
@@ -1,2 +1,5 @@
 pub mod jwt_util;
 pub mod crypto_util;
+pub mod cron_util;
+pub mod redis_lock;
+pub mod path_util;
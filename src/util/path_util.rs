@@ -0,0 +1,100 @@
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+// 可直接用作目录名的标识符：字母、数字、下划线、$ 与 -，不含路径分隔符与 .
+pub fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '-'))
+}
+
+// 路径仅由普通路径段组成：非绝对路径，不含 . 与 ..
+pub fn is_relative_normal(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_)))
+}
+
+// 规范化路径（解析符号链接与 ..），路径不存在时规范化其最近的已存在上级目录后拼接其余部分
+pub fn canonicalize_lenient(path: &Path) -> io::Result<PathBuf> {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        match fs::canonicalize(existing) {
+            Ok(canonical) => {
+                return Ok(rest
+                    .iter()
+                    .rev()
+                    .fold(canonical, |path, name| path.join(name)));
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let (Some(parent), Some(name)) =
+                    (existing.parent(), existing.file_name())
+                else {
+                    return Err(e);
+                };
+                rest.push(name.to_os_string());
+                existing = if parent.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    parent
+                };
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// 规范化后 path 是否位于 root 之下（含 root 本身）
+pub fn is_within(root: &Path, path: &Path) -> io::Result<bool> {
+    Ok(canonicalize_lenient(path)?.starts_with(canonicalize_lenient(root)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifiers() {
+        for name in ["orders", "t_2024", "订单", "a$b", "my-source"] {
+            assert!(is_identifier(name), "{}", name);
+        }
+        for name in ["", ".", "..", "a/b", "a\\b", "/etc", "a.b", "a b"] {
+            assert!(!is_identifier(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn relative_normal_paths() {
+        assert!(is_relative_normal(Path::new("a/b")));
+        assert!(!is_relative_normal(Path::new("../a")));
+        assert!(!is_relative_normal(Path::new("a/../../b")));
+        assert!(!is_relative_normal(Path::new("/a")));
+        assert!(!is_relative_normal(Path::new("./a")));
+    }
+
+    #[test]
+    fn within_root() {
+        let root = std::env::temp_dir().join(format!(
+            "path_util_{}_{}",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+        fs::create_dir_all(root.join("data")).unwrap();
+        let outside = root.join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        let data = root.join("data");
+        assert!(is_within(&data, &data.join("a/b.csv")).unwrap());
+        assert!(is_within(&data, &data).unwrap());
+        assert!(!is_within(&data, &data.join("../outside")).unwrap());
+        assert!(!is_within(&data, &root.join("outside/x")).unwrap());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, data.join("link")).unwrap();
+            assert!(!is_within(&data, &data.join("link/x.csv")).unwrap());
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
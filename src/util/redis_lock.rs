@@ -0,0 +1,238 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use r2d2::Pool;
+use redis::{Commands, Script};
+
+use crate::error::error::AppError;
+
+// 仅当锁仍归自己所有时续租
+const RENEW_SCRIPT: &str = r#"
+if redis.call('get', KEYS[1]) == ARGV[1] then
+    return redis.call('pexpire', KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+// 仅当锁仍归自己所有时释放
+const RELEASE_SCRIPT: &str = r#"
+if redis.call('get', KEYS[1]) == ARGV[1] then
+    return redis.call('del', KEYS[1])
+end
+return 0
+"#;
+
+// 基于Redis的租约锁：SET NX PX 加锁，持有期间后台线程按租期的1/3续租，
+// 持有者崩溃后租约自然过期，距上次成功续租超过租期即视为失去锁。
+// 每次加锁从计数器领取单调递增的fencing token，写入方可据此拒绝已失去锁的旧持有者
+#[derive(Clone)]
+pub struct RedisLock {
+    redis_pool: Pool<redis::Client>,
+    key: String,
+    lease: Duration,
+}
+
+impl RedisLock {
+    pub fn new(
+        redis_pool: Pool<redis::Client>,
+        key: impl Into<String>,
+        lease: Duration,
+    ) -> Self {
+        Self {
+            redis_pool,
+            key: key.into(),
+            lease,
+        }
+    }
+
+    // 尝试获取锁，已被其他持有者占用时返回None
+    pub fn try_acquire(&self) -> Result<Option<LockGuard>, AppError> {
+        let mut conn = self.redis_pool.get()?;
+        let owner =
+            format!("{}-{:016x}", std::process::id(), rand::random::<u64>());
+        // 租约从发出请求前开始计算，宁可提前认为过期
+        let requested_at = Instant::now();
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(&self.key)
+            .arg(&owner)
+            .arg("NX")
+            .arg("PX")
+            .arg(self.lease.as_millis() as u64)
+            .query(&mut *conn)?;
+        if acquired.is_none() {
+            return Ok(None);
+        }
+        let token: u64 = conn.incr(format!("{}::fence", self.key), 1)?;
+
+        let holder = LockHolder {
+            token,
+            held: Arc::new(AtomicBool::new(true)),
+            expires_at: Arc::new(Mutex::new(requested_at + self.lease)),
+        };
+        let (stop, stopped) = mpsc::channel();
+        let heartbeat = {
+            let redis_pool = self.redis_pool.clone();
+            let key = self.key.clone();
+            let owner = owner.clone();
+            let lease = self.lease;
+            let holder = holder.clone();
+            thread::spawn(move || {
+                let script = Script::new(RENEW_SCRIPT);
+                // 收到停止信号或发送端被丢弃时退出
+                while let Err(RecvTimeoutError::Timeout) =
+                    stopped.recv_timeout(lease / 3)
+                {
+                    let requested_at = Instant::now();
+                    let renewed = redis_pool
+                        .get()
+                        .map_err(AppError::from)
+                        .and_then(|mut conn| {
+                            script
+                                .key(&key)
+                                .arg(&owner)
+                                .arg(lease.as_millis() as u64)
+                                .invoke::<i64>(&mut *conn)
+                                .map_err(AppError::from)
+                        });
+                    match renewed {
+                        Ok(1) => {
+                            *holder.expires_at.lock().unwrap() =
+                                requested_at + lease;
+                        }
+                        Ok(_) => {
+                            tracing::warn!("分布式锁租约已丢失: {}", key);
+                            holder.held.store(false, Ordering::SeqCst);
+                            break;
+                        }
+                        // 网络抖动时继续重试，租约到期前恢复即可；
+                        // 到期后锁可能已被他人获取，不再视为持有
+                        Err(e) => {
+                            tracing::warn!(
+                                "分布式锁续租失败: {}, {:?}",
+                                key,
+                                e
+                            );
+                            if !holder.is_held() {
+                                tracing::warn!("分布式锁租约已过期: {}", key);
+                                holder.held.store(false, Ordering::SeqCst);
+                                break;
+                            }
+                        }
+                    }
+                }
+            })
+        };
+
+        Ok(Some(LockGuard {
+            holder,
+            release: Some(Release {
+                redis_pool: self.redis_pool.clone(),
+                key: self.key.clone(),
+                owner,
+                stop,
+                heartbeat,
+            }),
+        }))
+    }
+
+    // 锁当前是否被任意持有者占用
+    pub fn is_locked(&self) -> Result<bool, AppError> {
+        let mut conn = self.redis_pool.get()?;
+        Ok(conn.exists(&self.key)?)
+    }
+}
+
+// 持有者视图，可跨线程传递，用于在提交前确认仍持有锁
+#[derive(Clone, Debug)]
+pub struct LockHolder {
+    token: u64,
+    held: Arc<AtomicBool>,
+    // 最近一次成功续租（或加锁）后租约的到期时间
+    expires_at: Arc<Mutex<Instant>>,
+}
+
+impl LockHolder {
+    pub fn token(&self) -> u64 {
+        self.token
+    }
+
+    // 锁已被他人获取，或距上次成功续租已超过租期（续租线程阻塞在Redis上时同样适用）时为false
+    pub fn is_held(&self) -> bool {
+        self.held.load(Ordering::SeqCst)
+            && Instant::now() < *self.expires_at.lock().unwrap()
+    }
+}
+
+// 已获取的锁。应在异步任务中调用 release 释放；未释放即离开作用域时
+// （提前返回、出错）交给阻塞线程池释放，不阻塞异步任务
+pub struct LockGuard {
+    holder: LockHolder,
+    release: Option<Release>,
+}
+
+impl LockGuard {
+    pub fn holder(&self) -> LockHolder {
+        self.holder.clone()
+    }
+
+    pub fn token(&self) -> u64 {
+        self.holder.token
+    }
+
+    // 停止心跳并释放锁，等待心跳线程退出与Redis往返在阻塞线程池中完成
+    pub async fn release(mut self) {
+        if let Some(release) = self.release.take()
+            && let Err(e) = tokio::task::spawn_blocking(|| release.run()).await
+        {
+            tracing::warn!("释放分布式锁失败: {:?}", e);
+        }
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        let Some(release) = self.release.take() else {
+            return;
+        };
+        // 不在异步运行时中时可直接阻塞
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => drop(handle.spawn_blocking(|| release.run())),
+            Err(_) => release.run(),
+        }
+    }
+}
+
+// 释放锁所需的状态，释放过程会阻塞
+struct Release {
+    redis_pool: Pool<redis::Client>,
+    key: String,
+    owner: String,
+    stop: Sender<()>,
+    heartbeat: JoinHandle<()>,
+}
+
+impl Release {
+    fn run(self) {
+        drop(self.stop);
+        let _ = self.heartbeat.join();
+        let released = self.redis_pool.get().map_err(AppError::from).and_then(
+            |mut conn| {
+                Script::new(RELEASE_SCRIPT)
+                    .key(&self.key)
+                    .arg(&self.owner)
+                    .invoke::<i64>(&mut *conn)
+                    .map_err(AppError::from)
+            },
+        );
+        if let Err(e) = released {
+            tracing::warn!("释放分布式锁失败: {}, {:?}", self.key, e);
+        }
+    }
+}